@@ -1,8 +1,9 @@
 //! Application orchestrator.
 
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::sync::{
-    Arc,
+    Arc, Mutex, PoisonError,
     atomic::{AtomicBool, Ordering},
 };
 use std::thread;
@@ -12,20 +13,150 @@ use anyhow::{Context as _, Result};
 use crossbeam_channel::{Receiver, Sender, bounded, unbounded};
 use egui::Context;
 use egui_overlay::EguiOverlay;
-use tracing::{error, warn};
+use tracing::{error, info, warn};
 
+use crate::clock::{Clock, SystemClock};
 use crate::config;
+use crate::config_handle::ConfigHandle;
 use crate::input;
-use crate::renderer::{Renderer, create_renderer};
-use crate::types::{AppConfig, InputEvent};
-use crate::watcher::ConfigWatcher;
+use crate::input::BackendStatus;
+use crate::layout::recenter_rect;
+use crate::renderer::{Metrics, Renderer, create_renderer};
+use crate::state;
+use crate::types::{AppConfig, AppError, CLOSE_KEY_NAME, InputEvent};
+use crate::watcher::{ConfigWatcher, ReloadPause};
 
 const INPUT_THREAD_NAME: &str = "input-backend";
-const ESCAPE_KEY_NAME: &str = "Escape";
 const DOUBLE_ESCAPE_INTERVAL: Duration = Duration::from_millis(400);
+const CONFIG_ERROR_TOAST: &str = "config error, keeping previous settings";
+/// Shown while the input backend is failed, one line per row.
+const INPUT_FAILURE_BANNER: &str = "Key capture failed\nGrant input monitoring or\naccessibility permission\nand restart the overlay";
+/// Quiet period after the last runtime tweak before it is written back to disk.
+const AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+/// How long the startup help takes to fade out after `helpDurationMs`.
+const HELP_FADE_OUT: Duration = Duration::from_millis(500);
+/// How long the watcher ignores changes after an auto-save, covering its debounce.
+const AUTOSAVE_RELOAD_PAUSE: Duration = Duration::from_millis(1500);
+
+/// Runtime actions bound to configurable keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HotkeyAction {
+    ToggleTheme,
+    MoveKeyLeft,
+    MoveKeyRight,
+    ResetCounters,
+    TogglePassthrough,
+    ToggleCounter,
+}
+
+/// Direction to move a key within the overlay's key order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyMove {
+    Left,
+    Right,
+}
+
+/// Where runtime tweaks are saved, and the watcher pause that hides the save from reload.
+#[derive(Debug)]
+struct AutoSave {
+    config_path: PathBuf,
+    reload_pause: Arc<ReloadPause>,
+}
+
+/// Overlay data saved once the window closes, refreshed by the orchestrator every frame.
+#[derive(Debug, Clone, Default)]
+struct ShutdownSnapshot {
+    overlay_state: state::OverlayState,
+    session_stats: state::SessionStats,
+    /// The configured `statsExportPath`, if any.
+    stats_export_path: Option<PathBuf>,
+}
+
+/// Session-only switches that are not part of the persisted config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RunOptions {
+    /// Draw alignment gridlines and the window size.
+    pub setup_grid: bool,
+    /// Record every input event to this file as JSON lines, for `replayFile`.
+    pub record_to: Option<PathBuf>,
+}
 
 /// Runs the full application lifecycle.
 pub fn run(config_path: &Path) -> Result<()> {
+    run_with_options(config_path, RunOptions::default())
+}
+
+/// Validates the config file and prints its warnings without starting the overlay.
+///
+/// Returns an error for unreadable or invalid configs so the process exits non-zero.
+pub fn check(config_path: &Path) -> Result<()> {
+    let warnings = config::check_config(config_path)
+        .map_err(anyhow::Error::from)
+        .with_context(|| format!("config '{}' is invalid", config_path.display()))?;
+
+    for warning in &warnings {
+        println!("warning: {warning}");
+    }
+    println!(
+        "config '{}' is valid ({} warning(s))",
+        config_path.display(),
+        warnings.len()
+    );
+    Ok(())
+}
+
+/// Prints the resolved config as TOML, creating the file with defaults if absent.
+pub fn print_config(config_path: &Path) -> Result<()> {
+    let config = config::ensure_config_exists(config_path)
+        .map_err(anyhow::Error::from)
+        .with_context(|| {
+            format!(
+                "failed to load or create config at '{}'",
+                config_path.display()
+            )
+        })?;
+    let serialized = config::serialize_config(&config)
+        .map_err(anyhow::Error::from)
+        .context("failed to serialize config")?;
+    print!("{serialized}");
+    Ok(())
+}
+
+/// Prints the JSON schema of the config file.
+pub fn emit_schema() -> Result<()> {
+    let schema = config::json_schema()
+        .map_err(anyhow::Error::from)
+        .context("failed to generate config schema")?;
+    println!("{schema}");
+    Ok(())
+}
+
+/// Prints every key name accepted in `[[key]]` entries, one per line.
+pub fn list_keys() {
+    for name in input::KeyId::all_names() {
+        println!("{name}");
+    }
+}
+
+/// Runs the full application lifecycle with session-only options.
+pub fn run_with_options(config_path: &Path, options: RunOptions) -> Result<()> {
+    run_with_config_handle(
+        config_path,
+        options,
+        ConfigHandle::new(AppConfig::default()),
+    )
+}
+
+/// Runs the application while `config_handle` can push config updates.
+///
+/// The handle starts from the config loaded at `config_path`; updates sent
+/// through it are applied like file reloads, and reloads show up in
+/// [`ConfigHandle::current`].
+pub fn run_with_config_handle(
+    config_path: &Path,
+    options: RunOptions,
+    config_handle: ConfigHandle,
+) -> Result<()> {
     let config = config::ensure_config_exists(config_path)
         .map_err(anyhow::Error::from)
         .with_context(|| {
@@ -38,15 +169,57 @@ pub fn run(config_path: &Path) -> Result<()> {
     let log_dir = resolve_log_dir(config_path);
     let _log_guard = crate::logging::init_logging(config.log_to_file, &log_dir);
 
-    let (input_rx, input_shutdown_tx) = start_input_thread()?;
-    let (config_rx, mut config_watcher) = start_config_watcher(config_path)?;
+    let (input_rx, backend_status_rx, input_shutdown_tx) =
+        start_input_thread(config.input_backend, config.replay_file.clone())?;
+    config_handle.store(config.clone());
+    let reload_pause = Arc::new(ReloadPause::default());
+    let (config_error_rx, mut config_watcher) = start_config_watcher(
+        config_path,
+        config_handle.sender(),
+        Arc::clone(&reload_pause),
+    )?;
     let shutdown_requested = Arc::new(AtomicBool::new(false));
     install_ctrlc_handler(&shutdown_requested);
 
-    let renderer = create_renderer(config);
-    let app = AppOrchestrator::new(renderer, input_rx, config_rx, shutdown_requested);
+    let state_path = state::state_path_for(config_path);
+    let overlay_state = state::load_state(&state_path).unwrap_or_else(|err| {
+        warn!("failed to load overlay state, starting fresh: {err}");
+        state::OverlayState::default()
+    });
+
+    let mut renderer = create_renderer(config);
+    renderer.set_overlay_state(overlay_state);
+    renderer.set_setup_grid(options.setup_grid);
+    let mut app = AppOrchestrator::new(
+        renderer,
+        input_rx,
+        config_handle.receiver(),
+        config_error_rx,
+        shutdown_requested,
+        Arc::new(SystemClock),
+    );
+    app.config_handle = Some(config_handle);
+    app.backend_status_rx = Some(backend_status_rx);
+    if app.base_config.show_help_on_startup {
+        app.show_startup_help(config_path);
+    }
+    if let Some(path) = &options.record_to {
+        app.record_to(path)
+            .with_context(|| format!("failed to start recording to '{}'", path.display()))?;
+    }
+    app.autosave = Some(AutoSave {
+        config_path: config_path.to_path_buf(),
+        reload_pause,
+    });
+    let shutdown_snapshot = Arc::clone(&app.shutdown_snapshot);
     egui_overlay::start(app);
 
+    let snapshot = shutdown_snapshot
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    save_shutdown_snapshot(&snapshot, &state_path);
+
     drop(input_shutdown_tx);
     config_watcher
         .stop()
@@ -56,6 +229,25 @@ pub fn run(config_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Writes the data the overlay published before it closed, logging any failure.
+fn save_shutdown_snapshot(snapshot: &ShutdownSnapshot, state_path: &Path) {
+    if let Err(err) = state::save_state(state_path, &snapshot.overlay_state) {
+        warn!(
+            "failed to save overlay state to '{}': {err}",
+            state_path.display()
+        );
+    }
+
+    if let Some(path) = &snapshot.stats_export_path
+        && let Err(err) = state::save_session_stats(path, &snapshot.session_stats)
+    {
+        warn!(
+            "failed to export session stats to '{}': {err}",
+            path.display()
+        );
+    }
+}
+
 fn resolve_log_dir(config_path: &Path) -> PathBuf {
     config_path
         .parent()
@@ -64,26 +256,36 @@ fn resolve_log_dir(config_path: &Path) -> PathBuf {
         .join("logs")
 }
 
-fn start_input_thread() -> Result<(Receiver<InputEvent>, Sender<()>)> {
-    let mut backend = input::create_backend();
+fn start_input_thread(
+    backend_kind: input::BackendKind,
+    replay_file: Option<PathBuf>,
+) -> Result<(Receiver<InputEvent>, Receiver<BackendStatus>, Sender<()>)> {
+    let mut backend = input::create_backend(backend_kind, replay_file.as_deref());
     let (event_tx, event_rx) = unbounded::<InputEvent>();
+    let (status_tx, status_rx) = unbounded::<BackendStatus>();
     let (shutdown_tx, shutdown_rx) = bounded::<()>(1);
 
     thread::Builder::new()
         .name(INPUT_THREAD_NAME.to_string())
-        .spawn(move || run_input_backend(&mut backend, event_tx, shutdown_rx))
+        .spawn(move || run_input_backend(&mut backend, event_tx, status_tx, shutdown_rx))
         .context("failed to spawn input backend thread")?;
 
-    Ok((event_rx, shutdown_tx))
+    Ok((event_rx, status_rx, shutdown_tx))
 }
 
 fn run_input_backend(
     backend: &mut Box<dyn input::InputBackend>,
     event_tx: Sender<InputEvent>,
+    status_tx: Sender<BackendStatus>,
     shutdown_rx: Receiver<()>,
 ) {
+    // No `Running` is sent after a successful start: the orchestrator already
+    // assumes it, and the backend may have reported `Failed` from its own
+    // listener thread before `start` returns.
+    backend.set_status_sender(status_tx.clone());
     if let Err(err) = backend.start(event_tx) {
         error!("input backend failed to start: {err}");
+        let _ = status_tx.send(BackendStatus::Failed(err.to_string()));
         return;
     }
 
@@ -94,8 +296,12 @@ fn run_input_backend(
     }
 }
 
-fn start_config_watcher(config_path: &Path) -> Result<(Receiver<AppConfig>, ConfigWatcher)> {
-    let (config_tx, config_rx) = unbounded::<AppConfig>();
+fn start_config_watcher(
+    config_path: &Path,
+    config_tx: Sender<AppConfig>,
+    reload_pause: Arc<ReloadPause>,
+) -> Result<(Receiver<AppError>, ConfigWatcher)> {
+    let (error_tx, error_rx) = unbounded::<AppError>();
     let callback = Box::new(move |new_config: AppConfig| {
         if let Err(err) = config_tx.send(new_config) {
             warn!("failed to forward reloaded config: {err}");
@@ -110,12 +316,16 @@ fn start_config_watcher(config_path: &Path) -> Result<(Receiver<AppConfig>, Conf
                 config_path.display()
             )
         })?;
+    watcher.set_error_callback(Arc::new(move |err: AppError| {
+        let _ = error_tx.send(err);
+    }));
+    watcher.set_reload_pause(reload_pause);
     watcher
         .start()
         .map_err(anyhow::Error::from)
         .context("failed to start config watcher")?;
 
-    Ok((config_rx, watcher))
+    Ok((error_rx, watcher))
 }
 
 fn install_ctrlc_handler(shutdown_requested: &Arc<AtomicBool>) {
@@ -132,9 +342,39 @@ struct AppOrchestrator {
     renderer: Renderer,
     input_rx: Receiver<InputEvent>,
     config_rx: Receiver<AppConfig>,
+    config_error_rx: Receiver<AppError>,
     shutdown_requested: Arc<AtomicBool>,
     escape_down: bool,
     last_escape_press_at: Option<Instant>,
+    /// Shared with `run_with_config_handle`, which saves it after the window closes.
+    shutdown_snapshot: Arc<Mutex<ShutdownSnapshot>>,
+    /// Config as loaded from disk, before any runtime theme is applied.
+    base_config: AppConfig,
+    active_theme: Option<usize>,
+    /// Hotkeys currently held down, so auto-repeat fires an action only once.
+    held_hotkeys: HashSet<String>,
+    last_input_at: Instant,
+    /// Set when a reload should move the window to keep its center fixed.
+    recenter_pending: bool,
+    /// Most recently pressed overlay key, moved by the reorder hotkeys.
+    selected_key: Option<String>,
+    autosave: Option<AutoSave>,
+    /// When the last unsaved runtime tweak was made.
+    tweaked_at: Option<Instant>,
+    recorder: Option<input::EventRecorder>,
+    /// Source of every time reading, replaced by a mock clock in tests.
+    clock: Arc<dyn Clock>,
+    /// When the orchestrator was created, for timing the startup help.
+    started_at: Instant,
+    /// Whether mouse input passes through the overlay to the window below.
+    passthrough: bool,
+    /// Passthrough state last sent to the window, `None` before the first frame.
+    applied_passthrough: Option<bool>,
+    /// Handle kept in sync with every applied config.
+    config_handle: Option<ConfigHandle>,
+    backend_status_rx: Option<Receiver<BackendStatus>>,
+    /// Last status reported by the input backend.
+    backend_status: BackendStatus,
 }
 
 impl AppOrchestrator {
@@ -142,51 +382,355 @@ impl AppOrchestrator {
         renderer: Renderer,
         input_rx: Receiver<InputEvent>,
         config_rx: Receiver<AppConfig>,
+        config_error_rx: Receiver<AppError>,
         shutdown_requested: Arc<AtomicBool>,
+        clock: Arc<dyn Clock>,
     ) -> Self {
+        let base_config = renderer.config().clone();
+
         Self {
             renderer,
             input_rx,
             config_rx,
+            config_error_rx,
             shutdown_requested,
             escape_down: false,
             last_escape_press_at: None,
+            shutdown_snapshot: Arc::default(),
+            base_config,
+            active_theme: None,
+            held_hotkeys: HashSet::new(),
+            last_input_at: clock.now(),
+            started_at: clock.now(),
+            recenter_pending: false,
+            selected_key: None,
+            autosave: None,
+            tweaked_at: None,
+            recorder: None,
+            clock,
+            passthrough: true,
+            applied_passthrough: None,
+            config_handle: None,
+            backend_status_rx: None,
+            backend_status: BackendStatus::Running,
+        }
+    }
+
+    /// Starts writing every received input event to `path` as JSON lines.
+    fn record_to(&mut self, path: &Path) -> Result<()> {
+        let recorder = input::EventRecorder::create(path, self.clock.now())?;
+        self.recorder = Some(recorder);
+        Ok(())
+    }
+
+    /// Appends `event` to the recording, stopping it after a write failure.
+    fn record_event(&mut self, event: &InputEvent, now: Instant) {
+        if let Some(recorder) = &mut self.recorder
+            && let Err(err) = recorder.record(event, now)
+        {
+            warn!("stopping input recording: {err}");
+            self.recorder = None;
         }
     }
 
+    /// Shows the close shortcut, configured hotkeys and config location.
+    fn show_startup_help(&mut self, config_path: &Path) {
+        let mut lines = vec![format!(
+            "Focus the overlay and press {CLOSE_KEY_NAME} twice to close"
+        )];
+        lines.extend(
+            self.base_config
+                .control_hotkeys()
+                .into_iter()
+                .filter(|(_, key)| *key != CLOSE_KEY_NAME)
+                .map(|(name, key)| format!("{key}: {name}")),
+        );
+        lines.push(format!("Config: {}", config_path.display()));
+        self.renderer.show_help(lines);
+    }
+
+    fn update_help(&mut self, now: Instant) {
+        if self.renderer.is_help_visible() {
+            let alpha = help_alpha(
+                now.saturating_duration_since(self.started_at),
+                Duration::from_millis(self.base_config.help_duration_ms),
+            );
+            self.renderer.set_help_alpha(alpha);
+        }
+    }
+
+    /// Applies only the newest pending config; intermediate reloads are discarded.
+    ///
+    /// Failed reloads keep the current config and show a brief toast instead.
     fn process_config_updates(&mut self) {
-        for config in self.config_rx.try_iter() {
-            self.renderer.set_config(config);
+        if self.config_error_rx.try_iter().count() > 0 {
+            self.renderer.show_toast(CONFIG_ERROR_TOAST);
+        }
+
+        if let Some(config) = self.config_rx.try_iter().last() {
+            self.renderer.record_config_reload();
+            self.recenter_pending = config.recenter_on_reload;
+            if let Some(handle) = &self.config_handle {
+                handle.store(config.clone());
+            }
+            self.base_config = config;
+            if self
+                .active_theme
+                .is_some_and(|index| index >= self.base_config.themes.len())
+            {
+                self.active_theme = None;
+            }
+            self.apply_active_theme();
+            self.renderer.reset_counter_visibility();
+        }
+    }
+
+    /// Press counts per configured key since startup.
+    fn session_stats(&self) -> state::SessionStats {
+        let duration = self.clock.now().saturating_duration_since(self.started_at);
+        let columns = &self.renderer.bar_manager.columns;
+        state::SessionStats::new(
+            duration.as_secs_f64(),
+            self.renderer.config().keys.iter().map(|key| {
+                let presses = columns
+                    .get(&key.key_name)
+                    .map_or(0, |column| column.press_count);
+                (key.key_name.clone(), presses)
+            }),
+        )
+    }
+
+    /// Refreshes the data `run_with_config_handle` saves once the window closes.
+    fn publish_shutdown_snapshot(&self) {
+        let mut snapshot = self
+            .shutdown_snapshot
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        snapshot.overlay_state = self.renderer.overlay_state().clone();
+        snapshot.session_stats = self.session_stats();
+        snapshot
+            .stats_export_path
+            .clone_from(&self.renderer.config().stats_export_path);
+    }
+
+    /// Applies the newest input backend status, showing a banner while it is failed.
+    fn process_backend_status(&mut self) {
+        let Some(status) = self
+            .backend_status_rx
+            .as_ref()
+            .and_then(|status_rx| status_rx.try_iter().last())
+        else {
+            return;
+        };
+
+        match &status {
+            BackendStatus::Running => self.renderer.set_error_banner(None),
+            BackendStatus::Failed(reason) => {
+                warn!("input capture unavailable: {reason}");
+                self.renderer
+                    .set_error_banner(Some(INPUT_FAILURE_BANNER.to_string()));
+            }
+        }
+        self.backend_status = status;
+    }
+
+    /// Telemetry counters for events, reloads and frames.
+    fn metrics(&self) -> Metrics {
+        self.renderer.metrics()
+    }
+
+    fn apply_active_theme(&mut self) {
+        let config = match self
+            .active_theme
+            .and_then(|index| self.base_config.themes.get(index))
+        {
+            Some(theme) => theme.apply(&self.base_config),
+            None => self.base_config.clone(),
+        };
+        self.renderer.set_config(config);
+    }
+
+    fn hotkey_action(&self, key: &str) -> Option<HotkeyAction> {
+        let config = &self.base_config;
+        if config.toggle_theme_key.as_deref() == Some(key) {
+            return Some(HotkeyAction::ToggleTheme);
+        }
+        if config.move_key_left_key.as_deref() == Some(key) {
+            return Some(HotkeyAction::MoveKeyLeft);
+        }
+        if config.move_key_right_key.as_deref() == Some(key) {
+            return Some(HotkeyAction::MoveKeyRight);
+        }
+        if config.reset_key.as_deref() == Some(key) {
+            return Some(HotkeyAction::ResetCounters);
+        }
+        if config.toggle_passthrough_key.as_deref() == Some(key) {
+            return Some(HotkeyAction::TogglePassthrough);
+        }
+        if config.toggle_counter_key.as_deref() == Some(key) {
+            return Some(HotkeyAction::ToggleCounter);
+        }
+
+        None
+    }
+
+    /// Runs the hotkey bound to `key` on its first press; repeats while held are ignored.
+    fn handle_hotkey_press(&mut self, key: &str) {
+        let Some(action) = self.hotkey_action(key) else {
+            self.select_key(key);
+            return;
+        };
+        if !self.held_hotkeys.insert(key.to_string()) {
+            return;
+        }
+
+        match action {
+            HotkeyAction::ToggleTheme => self.toggle_theme(),
+            HotkeyAction::MoveKeyLeft => self.move_selected_key(KeyMove::Left),
+            HotkeyAction::MoveKeyRight => self.move_selected_key(KeyMove::Right),
+            HotkeyAction::ResetCounters => self.reset_counters(),
+            HotkeyAction::TogglePassthrough => self.toggle_passthrough(),
+            HotkeyAction::ToggleCounter => self.renderer.toggle_counter(),
+        }
+    }
+
+    fn toggle_passthrough(&mut self) {
+        self.passthrough = !self.passthrough;
+        let state = if self.passthrough { "on" } else { "off" };
+        self.renderer.show_toast(format!("click-through {state}"));
+    }
+
+    /// Passthrough value to send to the window, or `None` when it is already applied.
+    fn pending_passthrough(&mut self) -> Option<bool> {
+        if self.applied_passthrough == Some(self.passthrough) {
+            return None;
+        }
+        self.applied_passthrough = Some(self.passthrough);
+        Some(self.passthrough)
+    }
+
+    fn reset_counters(&mut self) {
+        self.renderer.bar_manager.reset_counters();
+        if self.base_config.reset_clears_bars {
+            self.renderer.bar_manager.clear_bars();
+        }
+        self.renderer.show_toast("counters reset");
+    }
+
+    fn select_key(&mut self, key: &str) {
+        if self.base_config.keys.iter().any(|k| k.key_name == key) {
+            self.selected_key = Some(key.to_string());
+        }
+    }
+
+    fn move_selected_key(&mut self, direction: KeyMove) {
+        if let Some(key) = self.selected_key.clone() {
+            self.move_key(&key, direction);
+        }
+    }
+
+    /// Swaps `key_name` with its neighbour in the live config and re-lays out the overlay.
+    ///
+    /// Returns `false` without changes when the key is unknown or already at that end.
+    /// The new order lasts until the next reload.
+    fn move_key(&mut self, key_name: &str, direction: KeyMove) -> bool {
+        let keys = &mut self.base_config.keys;
+        let Some(index) = keys.iter().position(|key| key.key_name == key_name) else {
+            return false;
+        };
+        let target = match direction {
+            KeyMove::Left => index.checked_sub(1),
+            KeyMove::Right => Some(index + 1).filter(|&target| target < keys.len()),
+        };
+        let Some(target) = target else {
+            return false;
+        };
+
+        keys.swap(index, target);
+        self.apply_active_theme();
+        self.tweaked_at = Some(self.clock.now());
+        true
+    }
+
+    fn handle_hotkey_release(&mut self, key: &str) {
+        self.held_hotkeys.remove(key);
+    }
+
+    fn toggle_theme(&mut self) {
+        self.active_theme = next_theme_index(self.active_theme, self.base_config.themes.len());
+        self.apply_active_theme();
+
+        let name = self
+            .active_theme
+            .and_then(|index| self.base_config.themes.get(index))
+            .map_or("default", |theme| theme.name.as_str());
+        self.renderer.show_toast(format!("theme: {name}"));
+    }
+
+    /// Writes pending runtime tweaks to the config file once they have settled.
+    ///
+    /// The watcher is paused around the write so the save is not reloaded.
+    fn autosave_tweaks(&mut self, now: Instant) {
+        if !self.base_config.auto_save_tweaks || !autosave_due(self.tweaked_at, now, AUTOSAVE_DELAY)
+        {
+            return;
+        }
+        let Some(autosave) = &self.autosave else {
+            return;
+        };
+
+        self.tweaked_at = None;
+        autosave.reload_pause.pause_for(AUTOSAVE_RELOAD_PAUSE, now);
+        if let Err(err) = config::save_config(&autosave.config_path, &self.base_config) {
+            warn!(
+                "failed to auto-save config to '{}': {err}",
+                autosave.config_path.display()
+            );
         }
     }
 
     fn process_input_events(&mut self, is_window_focused: bool) -> bool {
         let mut should_close = false;
         let events: Vec<InputEvent> = self.input_rx.try_iter().collect();
+        let now = self.clock.now();
+        if !events.is_empty() {
+            self.last_input_at = now;
+        }
+        let show_presses = is_window_focused || !self.base_config.pause_when_unfocused;
 
         for event in events {
+            self.record_event(&event, now);
             match event {
                 InputEvent::KeyPress(key) => {
-                    if key == ESCAPE_KEY_NAME
+                    if key == CLOSE_KEY_NAME
                         && is_window_focused
                         && self.should_close_on_double_escape()
                     {
                         should_close = true;
                     }
 
-                    self.renderer.on_key_press(&key);
+                    self.handle_hotkey_press(&key);
+                    if show_presses {
+                        self.renderer.on_key_press(&key);
+                    }
                 }
                 InputEvent::MousePress(key) => {
-                    self.renderer.on_key_press(&key);
+                    self.handle_hotkey_press(&key);
+                    if show_presses {
+                        self.renderer.on_key_press(&key);
+                    }
                 }
                 InputEvent::KeyRelease(key) => {
-                    if key == ESCAPE_KEY_NAME {
+                    if key == CLOSE_KEY_NAME {
                         self.escape_down = false;
                     }
 
+                    // Releases always pass so a key pressed before focus was lost is not stuck.
+                    self.handle_hotkey_release(&key);
                     self.renderer.on_key_release(&key);
                 }
                 InputEvent::MouseRelease(key) => {
+                    self.handle_hotkey_release(&key);
                     self.renderer.on_key_release(&key);
                 }
             }
@@ -195,13 +739,45 @@ impl AppOrchestrator {
         should_close
     }
 
+    /// Moves and resizes the window together so a reload keeps its center in place.
+    fn recenter_window(
+        &mut self,
+        glfw_backend: &mut egui_overlay::egui_window_glfw_passthrough::GlfwBackend,
+    ) {
+        if !std::mem::take(&mut self.recenter_pending) {
+            return;
+        }
+
+        let (x, y) = glfw_backend.window.get_pos();
+        let (width, height) = glfw_backend.window.get_size();
+        let old = egui::Rect::from_min_size(
+            egui::pos2(x as f32, y as f32),
+            egui::vec2(width as f32, height as f32),
+        );
+        let [new_width, new_height] = self.renderer.desired_window_size();
+        let target = recenter_rect(old, egui::vec2(new_width, new_height));
+
+        glfw_backend
+            .window
+            .set_pos(target.min.x.round() as i32, target.min.y.round() as i32);
+        glfw_backend.set_window_size([new_width, new_height]);
+    }
+
+    fn update_idle_state(&mut self, now: Instant) {
+        let hidden = is_idle(
+            now.saturating_duration_since(self.last_input_at),
+            self.base_config.idle_hide_ms,
+        );
+        self.renderer.set_idle_hidden(hidden);
+    }
+
     fn should_close_on_double_escape(&mut self) -> bool {
         if self.escape_down {
             return false;
         }
 
         self.escape_down = true;
-        let now = Instant::now();
+        let now = self.clock.now();
 
         if let Some(previous) = self.last_escape_press_at
             && now.duration_since(previous) <= DOUBLE_ESCAPE_INTERVAL
@@ -215,6 +791,44 @@ impl AppOrchestrator {
     }
 }
 
+/// Whether a tweak made at `tweaked_at` has been quiet for `delay` by `now`.
+fn autosave_due(tweaked_at: Option<Instant>, now: Instant, delay: Duration) -> bool {
+    tweaked_at.is_some_and(|at| now.saturating_duration_since(at) >= delay)
+}
+
+/// Opacity of the startup help: opaque for `duration`, then fading out over [`HELP_FADE_OUT`].
+fn help_alpha(elapsed: Duration, duration: Duration) -> f32 {
+    let Some(fading_for) = elapsed.checked_sub(duration) else {
+        return 1.0;
+    };
+    (1.0 - fading_for.as_secs_f32() / HELP_FADE_OUT.as_secs_f32()).max(0.0)
+}
+
+/// Whether the overlay should auto-hide after `since_last_input` without input.
+///
+/// An `idle_hide_ms` of zero disables auto-hide.
+fn is_idle(since_last_input: Duration, idle_hide_ms: u64) -> bool {
+    idle_hide_ms > 0 && since_last_input >= Duration::from_millis(idle_hide_ms)
+}
+
+/// Picks the theme after `current`: the first two themes alternate, and a single
+/// theme alternates with the unthemed config.
+fn next_theme_index(current: Option<usize>, theme_count: usize) -> Option<usize> {
+    match (current, theme_count) {
+        (_, 0) => None,
+        (None, _) => Some(0),
+        (Some(0), 1) => None,
+        (Some(0), _) => Some(1),
+        (Some(_), _) => Some(0),
+    }
+}
+
+impl Drop for AppOrchestrator {
+    fn drop(&mut self) {
+        info!("session metrics: {:?}", self.metrics());
+    }
+}
+
 impl EguiOverlay for AppOrchestrator {
     fn gui_run(
         &mut self,
@@ -223,6 +837,8 @@ impl EguiOverlay for AppOrchestrator {
         glfw_backend: &mut egui_overlay::egui_window_glfw_passthrough::GlfwBackend,
     ) {
         self.process_config_updates();
+        self.process_backend_status();
+        self.recenter_window(glfw_backend);
 
         let is_window_focused = glfw_backend.window.is_focused();
         let should_close_from_escape = self.process_input_events(is_window_focused);
@@ -230,8 +846,1028 @@ impl EguiOverlay for AppOrchestrator {
         if should_close_from_escape || should_close_from_signal {
             glfw_backend.window.set_should_close(true);
         }
+        let now = self.clock.now();
+        self.update_idle_state(now);
+        self.update_help(now);
+        self.autosave_tweaks(now);
+        if let Some(passthrough) = self.pending_passthrough() {
+            glfw_backend.set_passthrough(passthrough);
+        }
 
         self.renderer
             .gui_run(egui_context, default_gfx_backend, glfw_backend);
+        self.publish_shutdown_snapshot();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, Instant};
+
+    use crossbeam_channel::{Sender, unbounded};
+
+    use super::{
+        AUTOSAVE_DELAY, AppOrchestrator, AutoSave, CONFIG_ERROR_TOAST, DOUBLE_ESCAPE_INTERVAL,
+        HELP_FADE_OUT, INPUT_FAILURE_BANNER, KeyMove, ShutdownSnapshot, autosave_due, help_alpha,
+        is_idle, next_theme_index, save_shutdown_snapshot,
+    };
+    use crate::clock::{Clock, MockClock, SystemClock};
+    use crate::config_handle::ConfigHandle;
+    use crate::input::BackendStatus;
+    use crate::input::{InputBackend, MockBackend, RecordingBackend};
+    use crate::observer::{EventKind, PressObserver};
+    use crate::renderer::Renderer;
+    use crate::types::{AppConfig, AppError, CLOSE_KEY_NAME, Color, InputEvent, Theme};
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<(String, EventKind)>>,
+    }
+
+    impl PressObserver for RecordingObserver {
+        fn on_event(&self, key: &crate::types::KeyConfig, kind: EventKind) {
+            self.events
+                .lock()
+                .expect("observer lock should not be poisoned")
+                .push((key.key_name.clone(), kind));
+        }
+    }
+
+    struct Channels {
+        input_tx: crossbeam_channel::Sender<InputEvent>,
+        config_tx: crossbeam_channel::Sender<AppConfig>,
+        config_error_tx: crossbeam_channel::Sender<AppError>,
+    }
+
+    fn published_snapshot(orchestrator: &AppOrchestrator) -> ShutdownSnapshot {
+        orchestrator
+            .shutdown_snapshot
+            .lock()
+            .expect("snapshot lock should not be poisoned")
+            .clone()
+    }
+
+    fn mk_orchestrator() -> (AppOrchestrator, Channels) {
+        mk_orchestrator_with_clock(Arc::new(SystemClock))
+    }
+
+    fn mk_orchestrator_with_clock(clock: Arc<dyn Clock>) -> (AppOrchestrator, Channels) {
+        let (input_tx, input_rx) = unbounded();
+        let (config_tx, config_rx) = unbounded();
+        let (config_error_tx, config_error_rx) = unbounded();
+        let orchestrator = AppOrchestrator::new(
+            Renderer::new(AppConfig::default()),
+            input_rx,
+            config_rx,
+            config_error_rx,
+            Arc::new(AtomicBool::new(false)),
+            clock,
+        );
+
+        (
+            orchestrator,
+            Channels {
+                input_tx,
+                config_tx,
+                config_error_tx,
+            },
+        )
+    }
+
+    #[test]
+    fn test_process_config_updates_applies_only_latest_config() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+
+        for height in [701.0, 702.0, 703.0] {
+            channels
+                .config_tx
+                .send(AppConfig {
+                    height,
+                    ..AppConfig::default()
+                })
+                .expect("config channel should be open");
+        }
+
+        orchestrator.process_config_updates();
+
+        assert_eq!(orchestrator.renderer.config().height, 703.0);
+        assert!(orchestrator.config_rx.is_empty());
+    }
+
+    #[test]
+    fn test_config_handle_update_resizes_window() {
+        let handle = ConfigHandle::new(AppConfig::default());
+        let (_input_tx, input_rx) = unbounded();
+        let (_config_error_tx, config_error_rx) = unbounded();
+        let mut orchestrator = AppOrchestrator::new(
+            Renderer::new(AppConfig::default()),
+            input_rx,
+            handle.receiver(),
+            config_error_rx,
+            Arc::new(AtomicBool::new(false)),
+            Arc::new(SystemClock),
+        );
+        let [_, default_height] = orchestrator.renderer.desired_window_size();
+
+        handle
+            .update(|config| config.height = default_height + 100.0)
+            .expect("valid update");
+        orchestrator.process_config_updates();
+
+        let [_, height] = orchestrator.renderer.desired_window_size();
+        assert_eq!(height, default_height + 100.0);
+        assert_eq!(handle.current().height, default_height + 100.0);
+    }
+
+    #[test]
+    fn test_backend_failure_shows_banner_until_running_again() {
+        let (mut orchestrator, _channels) = mk_orchestrator();
+        let (status_tx, status_rx) = unbounded();
+        orchestrator.backend_status_rx = Some(status_rx);
+        orchestrator.process_backend_status();
+        assert_eq!(orchestrator.backend_status, BackendStatus::Running);
+        assert_eq!(orchestrator.renderer.error_banner(), None);
+
+        status_tx
+            .send(BackendStatus::Running)
+            .expect("status channel should be open");
+        status_tx
+            .send(BackendStatus::Failed("permission denied".to_string()))
+            .expect("status channel should be open");
+        orchestrator.process_backend_status();
+
+        assert_eq!(
+            orchestrator.backend_status,
+            BackendStatus::Failed("permission denied".to_string())
+        );
+        assert_eq!(
+            orchestrator.renderer.error_banner(),
+            Some(INPUT_FAILURE_BANNER)
+        );
+
+        status_tx
+            .send(BackendStatus::Running)
+            .expect("status channel should be open");
+        orchestrator.process_backend_status();
+        assert_eq!(orchestrator.renderer.error_banner(), None);
+    }
+
+    #[test]
+    fn test_run_input_backend_reports_start_failure() {
+        let mut backend: Box<dyn crate::input::InputBackend> =
+            Box::new(crate::input::MockBackend::default().with_start_error("no access"));
+        let (event_tx, _event_rx) = unbounded();
+        let (status_tx, status_rx) = unbounded();
+        let (_shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(1);
+
+        super::run_input_backend(&mut backend, event_tx, status_tx, shutdown_rx);
+
+        match status_rx.try_recv().expect("status should be reported") {
+            BackendStatus::Failed(reason) => assert!(reason.contains("no access"), "{reason}"),
+            BackendStatus::Running => panic!("start failure reported as running"),
+        }
+    }
+
+    /// Backend whose listener fails right away but whose `start` still succeeds,
+    /// like rdev without input permissions.
+    struct FailingListenerBackend {
+        status_tx: Option<Sender<BackendStatus>>,
+    }
+
+    impl InputBackend for FailingListenerBackend {
+        fn start(&mut self, _tx: Sender<InputEvent>) -> Result<(), AppError> {
+            if let Some(status_tx) = &self.status_tx {
+                let _ = status_tx.send(BackendStatus::Failed("listen failed".to_string()));
+            }
+            Ok(())
+        }
+
+        fn stop(&mut self) -> Result<(), AppError> {
+            Ok(())
+        }
+
+        fn set_status_sender(&mut self, status_tx: Sender<BackendStatus>) {
+            self.status_tx = Some(status_tx);
+        }
+
+        fn name(&self) -> &'static str {
+            "failing-listener"
+        }
+    }
+
+    #[test]
+    fn test_failure_reported_inside_start_keeps_the_banner() {
+        let mut backend: Box<dyn InputBackend> =
+            Box::new(FailingListenerBackend { status_tx: None });
+        let (event_tx, _event_rx) = unbounded();
+        let (status_tx, status_rx) = unbounded();
+        let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(1);
+        shutdown_tx
+            .send(())
+            .expect("shutdown channel should be open");
+
+        super::run_input_backend(&mut backend, event_tx, status_tx, shutdown_rx);
+
+        let (mut orchestrator, _channels) = mk_orchestrator();
+        orchestrator.backend_status_rx = Some(status_rx);
+        orchestrator.process_backend_status();
+        assert_eq!(
+            orchestrator.backend_status,
+            BackendStatus::Failed("listen failed".to_string())
+        );
+        assert_eq!(
+            orchestrator.renderer.error_banner(),
+            Some(INPUT_FAILURE_BANNER)
+        );
+
+        orchestrator.process_backend_status();
+        assert_eq!(
+            orchestrator.renderer.error_banner(),
+            Some(INPUT_FAILURE_BANNER)
+        );
+    }
+
+    #[test]
+    fn test_shutdown_snapshot_saves_overlay_state_to_state_path() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.state.toml");
+        let (mut orchestrator, _channels) = mk_orchestrator();
+        orchestrator.renderer.record_kps(9.5);
+
+        orchestrator.publish_shutdown_snapshot();
+        save_shutdown_snapshot(&published_snapshot(&orchestrator), &path);
+
+        let saved = crate::state::load_state(&path).expect("state should be saved");
+        assert_eq!(saved.peak_kps, 9.5);
+    }
+
+    #[test]
+    fn test_shutdown_snapshot_exports_session_stats_when_configured() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("stats.json");
+        let clock = Arc::new(MockClock::default());
+        let (mut orchestrator, channels) = mk_orchestrator_with_clock(clock.clone());
+        channels
+            .config_tx
+            .send(AppConfig {
+                stats_export_path: Some(path.clone()),
+                ..AppConfig::default()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::KeyPress("Z".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+        clock.advance(Duration::from_secs(30));
+
+        orchestrator.publish_shutdown_snapshot();
+        let state_path = dir.path().join("config.state.toml");
+        save_shutdown_snapshot(&published_snapshot(&orchestrator), &state_path);
+
+        let contents = std::fs::read_to_string(&path).expect("stats should be exported");
+        let stats: crate::state::SessionStats =
+            serde_json::from_str(&contents).expect("stats should parse");
+        assert_eq!(
+            stats,
+            crate::state::SessionStats::new(30.0, [("Z".to_string(), 2), ("X".to_string(), 0)])
+        );
+    }
+
+    #[test]
+    fn test_process_config_updates_flags_recenter_only_when_enabled() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+
+        channels
+            .config_tx
+            .send(AppConfig::default())
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        assert!(!orchestrator.recenter_pending);
+
+        channels
+            .config_tx
+            .send(AppConfig {
+                recenter_on_reload: true,
+                ..AppConfig::default()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        assert!(orchestrator.recenter_pending);
+    }
+
+    #[test]
+    fn test_process_config_updates_without_pending_config_keeps_current() {
+        let (mut orchestrator, _channels) = mk_orchestrator();
+
+        orchestrator.process_config_updates();
+
+        assert_eq!(*orchestrator.renderer.config(), AppConfig::default());
+    }
+
+    #[test]
+    fn test_process_config_updates_shows_toast_for_failed_reload() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+
+        channels
+            .config_error_tx
+            .send(AppError::Config("failed to parse TOML".to_string()))
+            .expect("error channel should be open");
+
+        orchestrator.process_config_updates();
+
+        assert_eq!(
+            orchestrator.renderer.toast_message(),
+            Some(CONFIG_ERROR_TOAST)
+        );
+        assert_eq!(*orchestrator.renderer.config(), AppConfig::default());
+    }
+
+    fn send_events(channels: &Channels, events: &[InputEvent]) {
+        for event in events {
+            channels
+                .input_tx
+                .send(event.clone())
+                .expect("input channel should be open");
+        }
+    }
+
+    #[test]
+    fn test_orchestrator_applies_exactly_the_recorded_backend_events() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        let mut backend = RecordingBackend::new(MockBackend::new(vec![
+            InputEvent::KeyPress("Z".to_string()),
+            InputEvent::KeyRelease("Z".to_string()),
+            InputEvent::KeyPress("Z".to_string()),
+            InputEvent::KeyPress("X".to_string()),
+        ]));
+        let recorded = backend.recorded();
+        backend
+            .start(channels.input_tx.clone())
+            .expect("recording backend start should succeed");
+
+        // Events are recorded before they are forwarded, so everything the
+        // orchestrator has processed is already in the list.
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while orchestrator.renderer.bar_manager.total_presses() < 3 && Instant::now() < deadline {
+            orchestrator.process_input_events(false);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let presses = |key: &str| {
+            recorded
+                .lock()
+                .expect("recorded lock")
+                .iter()
+                .filter(|event| **event == InputEvent::KeyPress(key.to_string()))
+                .count() as u64
+        };
+        let columns = &orchestrator.renderer.bar_manager.columns;
+        assert_eq!(columns["Z"].press_count, presses("Z"));
+        assert_eq!(columns["X"].press_count, presses("X"));
+        assert_eq!(orchestrator.renderer.held_keys(), vec!["X", "Z"]);
+    }
+
+    #[derive(Debug, Default)]
+    struct CountingObserver {
+        presses: AtomicUsize,
+    }
+
+    impl PressObserver for CountingObserver {
+        fn on_event(&self, _key: &crate::types::KeyConfig, kind: EventKind) {
+            if kind == EventKind::Press {
+                self.presses.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    #[test]
+    fn test_press_observer_is_notified_once_per_processed_press() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        let observer = Arc::new(CountingObserver::default());
+        orchestrator
+            .renderer
+            .set_press_observer(Arc::clone(&observer) as Arc<dyn PressObserver>);
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyPress("X".to_string()),
+                InputEvent::KeyRelease("X".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        assert_eq!(observer.presses.load(Ordering::SeqCst), 3);
+    }
+
+    fn pause_when_unfocused_orchestrator(pause: bool) -> (AppOrchestrator, Channels) {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(AppConfig {
+                pause_when_unfocused: pause,
+                reset_key: Some("F5".to_string()),
+                ..AppConfig::default()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        (orchestrator, channels)
+    }
+
+    #[test]
+    fn test_pause_when_unfocused_ignores_presses_without_focus() {
+        let (mut orchestrator, channels) = pause_when_unfocused_orchestrator(true);
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::MousePress("MouseLeft".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+        assert!(orchestrator.renderer.bar_manager.columns.is_empty());
+
+        send_events(&channels, &[InputEvent::KeyPress("Z".to_string())]);
+        orchestrator.process_input_events(true);
+        assert_eq!(
+            orchestrator.renderer.bar_manager.columns["Z"].press_count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_pause_when_unfocused_still_releases_keys_and_runs_hotkeys() {
+        let (mut orchestrator, channels) = pause_when_unfocused_orchestrator(true);
+        send_events(&channels, &[InputEvent::KeyPress("Z".to_string())]);
+        orchestrator.process_input_events(true);
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::KeyPress("F5".to_string()),
+                InputEvent::KeyRelease("F5".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        let column = &orchestrator.renderer.bar_manager.columns["Z"];
+        assert!(!column.is_held);
+        assert_eq!(column.press_count, 0, "reset hotkey ran while unfocused");
+    }
+
+    #[test]
+    fn test_pause_when_unfocused_disabled_counts_presses_without_focus() {
+        let (mut orchestrator, channels) = pause_when_unfocused_orchestrator(false);
+
+        send_events(&channels, &[InputEvent::KeyPress("Z".to_string())]);
+        orchestrator.process_input_events(false);
+
+        assert_eq!(
+            orchestrator.renderer.bar_manager.columns["Z"].press_count,
+            1
+        );
+    }
+
+    #[test]
+    fn test_next_theme_index_alternates_between_two_themes() {
+        let mut current = None;
+        let mut seen = Vec::new();
+
+        for _ in 0..4 {
+            current = next_theme_index(current, 2);
+            seen.push(current);
+        }
+
+        assert_eq!(seen, vec![Some(0), Some(1), Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_next_theme_index_single_theme_alternates_with_base() {
+        assert_eq!(next_theme_index(None, 1), Some(0));
+        assert_eq!(next_theme_index(Some(0), 1), None);
+        assert_eq!(next_theme_index(None, 0), None);
+    }
+
+    fn key_order(orchestrator: &AppOrchestrator) -> Vec<&str> {
+        orchestrator
+            .renderer
+            .config()
+            .keys
+            .iter()
+            .map(|key| key.key_name.as_str())
+            .collect()
+    }
+
+    fn three_key_config() -> AppConfig {
+        let mut config = AppConfig::default();
+        let mut c_key = config.keys[0].clone();
+        c_key.key_name = "C".to_string();
+        c_key.display_name = "C".to_string();
+        config.keys.push(c_key);
+        config
+    }
+
+    #[test]
+    fn test_record_to_writes_received_events_in_order() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("events.jsonl");
+        let (mut orchestrator, channels) = mk_orchestrator();
+        orchestrator
+            .record_to(&path)
+            .expect("recording should start");
+
+        let events = [
+            InputEvent::KeyPress("Z".to_string()),
+            InputEvent::KeyRelease("Z".to_string()),
+        ];
+        send_events(&channels, &events);
+        orchestrator.process_input_events(false);
+
+        let recorded: Vec<InputEvent> = crate::input::replay::read_event_log(&path)
+            .expect("recording should read")
+            .into_iter()
+            .map(|recorded| recorded.event)
+            .collect();
+        assert_eq!(recorded, events);
+    }
+
+    #[test]
+    fn test_reset_key_zeroes_counters_without_spawning_a_bar() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(AppConfig {
+                reset_key: Some("F5".to_string()),
+                ..AppConfig::default()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::KeyPress("X".to_string()),
+                InputEvent::KeyRelease("X".to_string()),
+                InputEvent::KeyPress("F5".to_string()),
+                InputEvent::KeyRelease("F5".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        let bar_manager = &orchestrator.renderer.bar_manager;
+        assert_eq!(bar_manager.total_presses(), 0);
+        assert_eq!(bar_manager.columns["Z"].bars.len(), 1);
+        assert!(!bar_manager.columns.contains_key("F5"));
+        assert_eq!(
+            orchestrator.renderer.toast_message(),
+            Some("counters reset")
+        );
+    }
+
+    #[test]
+    fn test_toggle_passthrough_key_flips_state_once_per_press() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(AppConfig {
+                toggle_passthrough_key: Some("F6".to_string()),
+                ..AppConfig::default()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        assert!(orchestrator.passthrough);
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("F6".to_string()),
+                InputEvent::KeyPress("F6".to_string()),
+                InputEvent::KeyRelease("F6".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        assert!(!orchestrator.passthrough);
+        assert_eq!(
+            orchestrator.renderer.toast_message(),
+            Some("click-through off")
+        );
+        assert!(!orchestrator.renderer.bar_manager.columns.contains_key("F6"));
+    }
+
+    #[test]
+    fn test_toggle_counter_key_hides_counter_until_reload() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        let config = AppConfig {
+            counter: true,
+            toggle_counter_key: Some("F7".to_string()),
+            ..AppConfig::default()
+        };
+        channels
+            .config_tx
+            .send(config.clone())
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        assert!(orchestrator.renderer.is_counter_visible());
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("F7".to_string()),
+                InputEvent::KeyPress("F7".to_string()),
+                InputEvent::KeyRelease("F7".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        assert!(!orchestrator.renderer.is_counter_visible());
+        assert!(!orchestrator.renderer.bar_manager.columns.contains_key("F7"));
+
+        channels
+            .config_tx
+            .send(config)
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        assert!(orchestrator.renderer.is_counter_visible());
+    }
+
+    #[test]
+    fn test_pending_passthrough_reports_only_changes() {
+        let (mut orchestrator, _channels) = mk_orchestrator();
+
+        assert_eq!(orchestrator.pending_passthrough(), Some(true));
+        assert_eq!(orchestrator.pending_passthrough(), None);
+
+        orchestrator.toggle_passthrough();
+        assert_eq!(orchestrator.pending_passthrough(), Some(false));
+        assert_eq!(orchestrator.pending_passthrough(), None);
+    }
+
+    #[test]
+    fn test_move_key_swaps_neighbours_in_the_middle() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(three_key_config())
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+
+        assert!(orchestrator.move_key("X", KeyMove::Right));
+        assert_eq!(key_order(&orchestrator), vec!["Z", "C", "X"]);
+
+        assert!(orchestrator.move_key("X", KeyMove::Left));
+        assert!(orchestrator.move_key("X", KeyMove::Left));
+        assert_eq!(key_order(&orchestrator), vec!["X", "Z", "C"]);
+    }
+
+    #[test]
+    fn test_autosave_due_waits_for_quiet_period() {
+        let tweaked = Instant::now();
+        let delay = Duration::from_secs(2);
+
+        assert!(!autosave_due(None, tweaked + delay, delay));
+        assert!(!autosave_due(Some(tweaked), tweaked + delay / 2, delay));
+        assert!(autosave_due(Some(tweaked), tweaked + delay, delay));
+    }
+
+    #[test]
+    fn test_autosave_tweaks_writes_config_and_pauses_watcher() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let config_path = dir.path().join("config.toml");
+        let reload_pause = Arc::new(crate::watcher::ReloadPause::default());
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(AppConfig {
+                auto_save_tweaks: true,
+                ..three_key_config()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+        orchestrator.autosave = Some(AutoSave {
+            config_path: config_path.clone(),
+            reload_pause: Arc::clone(&reload_pause),
+        });
+
+        assert!(orchestrator.move_key("C", KeyMove::Left));
+        let tweaked_at = orchestrator.tweaked_at.expect("tweak should be recorded");
+
+        orchestrator.autosave_tweaks(tweaked_at);
+        assert!(!config_path.exists());
+
+        let save_time = tweaked_at + AUTOSAVE_DELAY;
+        orchestrator.autosave_tweaks(save_time);
+
+        let saved = crate::config::load_config(&config_path).expect("saved config should load");
+        let order: Vec<&str> = saved.keys.iter().map(|key| key.key_name.as_str()).collect();
+        assert_eq!(order, vec!["Z", "C", "X"]);
+        assert!(reload_pause.is_paused(save_time));
+        assert_eq!(orchestrator.tweaked_at, None);
+    }
+
+    #[test]
+    fn test_move_key_is_noop_at_either_end() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(three_key_config())
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+
+        assert!(!orchestrator.move_key("Z", KeyMove::Left));
+        assert!(!orchestrator.move_key("C", KeyMove::Right));
+        assert!(!orchestrator.move_key("Q", KeyMove::Left));
+        assert_eq!(key_order(&orchestrator), vec!["Z", "X", "C"]);
+    }
+
+    #[test]
+    fn test_move_key_hotkeys_move_last_pressed_key() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        channels
+            .config_tx
+            .send(AppConfig {
+                move_key_left_key: Some("F6".to_string()),
+                move_key_right_key: Some("F7".to_string()),
+                ..three_key_config()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("C".to_string()),
+                InputEvent::KeyRelease("C".to_string()),
+                InputEvent::KeyPress("F6".to_string()),
+                InputEvent::KeyRelease("F6".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        assert_eq!(key_order(&orchestrator), vec!["Z", "C", "X"]);
+    }
+
+    #[test]
+    fn test_toggle_theme_key_is_edge_triggered_and_applies_colors() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        let night = Color::from_rgba_u8(10, 10, 40, 255);
+        channels
+            .config_tx
+            .send(AppConfig {
+                toggle_theme_key: Some("F8".to_string()),
+                themes: vec![
+                    Theme {
+                        name: "night".to_string(),
+                        background_color: Some(night.clone()),
+                        key_colors: Vec::new(),
+                    },
+                    Theme {
+                        name: "day".to_string(),
+                        background_color: None,
+                        key_colors: Vec::new(),
+                    },
+                ],
+                ..AppConfig::default()
+            })
+            .expect("config channel should be open");
+        orchestrator.process_config_updates();
+
+        // Auto-repeat delivers several presses before the release.
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("F8".to_string()),
+                InputEvent::KeyPress("F8".to_string()),
+                InputEvent::KeyPress("F8".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        assert_eq!(orchestrator.active_theme, Some(0));
+        assert_eq!(orchestrator.renderer.config().background_color, night);
+        assert_eq!(orchestrator.renderer.toast_message(), Some("theme: night"));
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyRelease("F8".to_string()),
+                InputEvent::KeyPress("F8".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        assert_eq!(orchestrator.active_theme, Some(1));
+        assert_eq!(
+            orchestrator.renderer.config().background_color,
+            Color::black()
+        );
+    }
+
+    #[test]
+    fn test_orchestrator_processes_timed_mock_events_in_order_until_stopped() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        let observer = Arc::new(RecordingObserver::default());
+        orchestrator
+            .renderer
+            .set_press_observer(Arc::clone(&observer) as Arc<dyn PressObserver>);
+
+        let step = Duration::from_millis(5);
+        let mut backend = MockBackend::with_timed_events(vec![
+            (step, InputEvent::KeyPress("Z".to_string())),
+            (step, InputEvent::KeyRelease("Z".to_string())),
+            (step, InputEvent::KeyPress("X".to_string())),
+            (
+                Duration::from_secs(30),
+                InputEvent::KeyPress("Z".to_string()),
+            ),
+        ]);
+        backend
+            .start(channels.input_tx.clone())
+            .expect("mock backend start should succeed");
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while observer.events.lock().expect("observer lock").len() < 3 && Instant::now() < deadline
+        {
+            orchestrator.process_input_events(false);
+            std::thread::sleep(step);
+        }
+        backend.stop().expect("mock backend stop should succeed");
+        std::thread::sleep(Duration::from_millis(50));
+        orchestrator.process_input_events(false);
+
+        assert_eq!(
+            *observer.events.lock().expect("observer lock"),
+            vec![
+                ("Z".to_string(), EventKind::Press),
+                ("Z".to_string(), EventKind::Release),
+                ("X".to_string(), EventKind::Press),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_metrics_track_events_reloads_and_frames() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+
+        send_events(
+            &channels,
+            &[
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::KeyPress("Q".to_string()),
+            ],
+        );
+        orchestrator.process_input_events(false);
+
+        for _ in 0..2 {
+            channels
+                .config_tx
+                .send(AppConfig::default())
+                .expect("config channel should be open");
+            orchestrator.process_config_updates();
+        }
+
+        let egui_context = egui::Context::default();
+        for _ in 0..3 {
+            let _ = egui_context.run(egui::RawInput::default(), |ctx| {
+                orchestrator.renderer.render_frame(ctx);
+            });
+        }
+
+        let metrics = orchestrator.metrics();
+        assert_eq!(metrics.events_processed, 3);
+        assert_eq!(metrics.events_dropped, 1);
+        assert_eq!(metrics.config_reloads, 2);
+        assert_eq!(metrics.frames_rendered, 3);
+    }
+
+    #[test]
+    fn test_is_idle_respects_threshold_and_zero_disables() {
+        let threshold_ms = 3_000;
+        // Inputs at 0s, 1s and 5s, sampled every half second up to 9s.
+        let inputs = [0.0, 1.0, 5.0];
+        let mut hidden_at = Vec::new();
+
+        for step in 0..=18 {
+            let now = step as f64 * 0.5;
+            let last_input = inputs
+                .iter()
+                .copied()
+                .filter(|&time| time <= now)
+                .fold(0.0, f64::max);
+            if is_idle(Duration::from_secs_f64(now - last_input), threshold_ms) {
+                hidden_at.push(now);
+            }
+        }
+
+        assert_eq!(hidden_at, vec![4.0, 4.5, 8.0, 8.5, 9.0]);
+        assert!(!is_idle(Duration::from_secs(3_600), 0));
+    }
+
+    #[test]
+    fn test_update_idle_state_hides_after_threshold_and_input_restores() {
+        let (mut orchestrator, channels) = mk_orchestrator();
+        orchestrator.base_config.idle_hide_ms = 1_000;
+        let start = orchestrator.last_input_at;
+
+        orchestrator.update_idle_state(start + Duration::from_millis(1_500));
+        assert!(orchestrator.renderer.is_idle_hidden());
+
+        send_events(&channels, &[InputEvent::KeyPress("Z".to_string())]);
+        orchestrator.process_input_events(false);
+        orchestrator.update_idle_state(Instant::now());
+
+        assert!(!orchestrator.renderer.is_idle_hidden());
+    }
+
+    fn press_and_release_escape(orchestrator: &mut AppOrchestrator, channels: &Channels) -> bool {
+        send_events(
+            channels,
+            &[
+                InputEvent::KeyPress(CLOSE_KEY_NAME.to_string()),
+                InputEvent::KeyRelease(CLOSE_KEY_NAME.to_string()),
+            ],
+        );
+        orchestrator.process_input_events(true)
+    }
+
+    #[test]
+    fn test_double_escape_within_interval_closes() {
+        let clock = MockClock::default();
+        let (mut orchestrator, channels) = mk_orchestrator_with_clock(Arc::new(clock.clone()));
+
+        assert!(!press_and_release_escape(&mut orchestrator, &channels));
+        clock.advance(DOUBLE_ESCAPE_INTERVAL);
+
+        assert!(press_and_release_escape(&mut orchestrator, &channels));
+    }
+
+    #[test]
+    fn test_double_escape_outside_interval_does_not_close() {
+        let clock = MockClock::default();
+        let (mut orchestrator, channels) = mk_orchestrator_with_clock(Arc::new(clock.clone()));
+
+        assert!(!press_and_release_escape(&mut orchestrator, &channels));
+        clock.advance(DOUBLE_ESCAPE_INTERVAL + Duration::from_millis(1));
+        assert!(!press_and_release_escape(&mut orchestrator, &channels));
+
+        clock.advance(Duration::from_millis(100));
+        assert!(press_and_release_escape(&mut orchestrator, &channels));
+    }
+
+    #[test]
+    fn test_help_alpha_holds_then_fades_out() {
+        let duration = Duration::from_secs(5);
+
+        assert_eq!(help_alpha(Duration::ZERO, duration), 1.0);
+        assert_eq!(help_alpha(duration, duration), 1.0);
+        assert!(
+            (help_alpha(duration + HELP_FADE_OUT / 2, duration) - 0.5).abs() < 1e-6,
+            "help should be half faded halfway through the fade"
+        );
+        assert_eq!(help_alpha(duration + HELP_FADE_OUT, duration), 0.0);
+        assert_eq!(help_alpha(Duration::from_secs(60), duration), 0.0);
+    }
+
+    #[test]
+    fn test_startup_help_fades_out_on_the_orchestrator_clock() {
+        let clock = MockClock::default();
+        let (mut orchestrator, _channels) = mk_orchestrator_with_clock(Arc::new(clock.clone()));
+        orchestrator.base_config.toggle_theme_key = Some("F8".to_string());
+        orchestrator.show_startup_help(std::path::Path::new("config.toml"));
+        let canvas = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(400.0, 700.0));
+        let texts: Vec<String> = orchestrator
+            .renderer
+            .build_draw_list(canvas)
+            .texts()
+            .map(str::to_string)
+            .collect();
+        assert!(texts.iter().any(|text| text == "F8: toggleThemeKey"));
+        assert!(texts.iter().any(|text| text == "Config: config.toml"));
+
+        let help_delay = Duration::from_millis(orchestrator.base_config.help_duration_ms);
+        clock.advance(help_delay);
+        orchestrator.update_help(clock.now());
+        assert!(orchestrator.renderer.is_help_visible());
+
+        clock.advance(HELP_FADE_OUT);
+        orchestrator.update_help(clock.now());
+        assert!(!orchestrator.renderer.is_help_visible());
     }
 }
@@ -1,51 +1,265 @@
 //! Bar state machine and delta-time physics.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
 
-use crate::types::Color;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq)]
+use crate::types::{Color, KeyConfig};
+
+/// Slowest a decelerating bar may scroll, as a fraction of the base bar speed,
+/// so bars always eventually leave the window.
+const MIN_VELOCITY_RATIO: f32 = 0.1;
+
+/// How long press timestamps are kept for KPS queries.
+const PRESS_HISTORY_SECS: f32 = 5.0;
+
+/// How long press timestamps across all keys are kept for APM queries.
+const APM_HISTORY_SECS: f32 = 60.0;
+
+/// Serializes a [`Color`] as its raw `[r, g, b, a]` channels, so snapshots
+/// round-trip exactly instead of through the lossy config byte string.
+mod raw_color {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::types::Color;
+
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        [color.r, color.g, color.b, color.a].serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        let [r, g, b, a] = <[f32; 4]>::deserialize(deserializer)?;
+        Ok(Color { r, g, b, a })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Bar {
     pub y_position: f32,
     pub height: f32,
+    #[serde(with = "raw_color")]
     pub color: Color,
+    #[serde(with = "raw_color")]
     pub pressed_color: Color,
+    /// Seconds since the bar was created.
+    pub age: f32,
+    /// Current scroll speed under acceleration; `None` until the bar starts moving.
+    pub velocity: Option<f32>,
+    /// Value of `age` when the key was released; `None` while held.
+    #[serde(default)]
+    pub released_at: Option<f32>,
+    /// Seconds between the column's previous press and this one; `None` when
+    /// no press falls within the KPS history.
+    #[serde(default)]
+    pub press_interval: Option<f32>,
+    /// Value of `age` when the bar reached the top of the window and became a
+    /// ghost pinned there; `None` while it scrolls or when ghost trails are off.
+    #[serde(default)]
+    pub ghost_since: Option<f32>,
+}
+
+impl Bar {
+    /// Seconds since the bar's key was released, or `None` while it is held.
+    pub fn since_release(&self) -> Option<f32> {
+        self.released_at.map(|released_at| self.age - released_at)
+    }
+
+    /// Opacity factor of a ghost fading out over `ghost_trail`: 1.0 before
+    /// the bar becomes a ghost, falling linearly to 0.0 at the end of the trail.
+    pub fn ghost_alpha(&self, ghost_trail: Duration) -> f32 {
+        let Some(ghost_since) = self.ghost_since else {
+            return 1.0;
+        };
+        let trail_secs = ghost_trail.as_secs_f32();
+        if trail_secs <= 0.0 {
+            return 0.0;
+        }
+        (1.0 - (self.age - ghost_since) / trail_secs).clamp(0.0, 1.0)
+    }
 }
 
+/// Bar-space rectangle covering one or more bars of the same color.
+///
+/// `y_position` and `height` have the same meaning as on [`Bar`].
 #[derive(Debug, Clone, PartialEq)]
+pub struct MergedRect {
+    pub y_position: f32,
+    pub height: f32,
+    pub color: Color,
+}
+
+impl From<&Bar> for MergedRect {
+    fn from(bar: &Bar) -> Self {
+        Self {
+            y_position: bar.y_position,
+            height: bar.height,
+            color: bar.color.clone(),
+        }
+    }
+}
+
+/// Combines bars that touch or overlap and share a `color`, so overlapping
+/// translucent bars are drawn once. The result is ordered lowest first.
+pub fn merge_bar_rects(bars: &[Bar]) -> Vec<MergedRect> {
+    let mut sorted: Vec<&Bar> = bars.iter().collect();
+    sorted.sort_by(|a, b| a.y_position.total_cmp(&b.y_position));
+
+    let mut merged: Vec<MergedRect> = Vec::with_capacity(sorted.len());
+    for bar in sorted {
+        if let Some(last) = merged.last_mut()
+            && last.color == bar.color
+            && bar.y_position <= last.y_position + last.height
+        {
+            let top = (last.y_position + last.height).max(bar.y_position + bar.height);
+            last.height = top - last.y_position;
+            continue;
+        }
+        merged.push(MergedRect::from(bar));
+    }
+
+    merged
+}
+
+/// Shortens bars from the top so each ends at least `gap` below the next bar
+/// up, keeping consecutive presses visibly apart. Bottoms are unchanged.
+///
+/// Bars squeezed out entirely are left with zero height.
+pub fn enforce_bar_gaps(bars: &mut [Bar], gap: f32) {
+    if gap <= 0.0 {
+        return;
+    }
+
+    let mut order: Vec<usize> = (0..bars.len()).collect();
+    order.sort_by(|&a, &b| bars[a].y_position.total_cmp(&bars[b].y_position));
+    for pair in order.windows(2) {
+        let upper_bottom = bars[pair[1]].y_position;
+        let lower = &mut bars[pair[0]];
+        let max_height = (upper_bottom - gap - lower.y_position).max(0.0);
+        lower.height = lower.height.min(max_height);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct BarColumn {
     pub bars: Vec<Bar>,
     pub press_count: u64,
     pub is_held: bool,
+    #[serde(with = "raw_color")]
     color: Color,
+    #[serde(with = "raw_color")]
+    pressed_color: Color,
+    max_bars: Option<usize>,
+    min_lifetime: Option<Duration>,
+    /// How long bars linger as fading ghosts after leaving the window.
+    #[serde(default)]
+    ghost_trail: Option<Duration>,
+    acceleration: f32,
+    /// Scroll speed overriding the manager's `bar_speed`.
+    #[serde(default)]
+    bar_speed: Option<f32>,
+    /// Column-local clock advanced by `update`, in seconds.
+    elapsed: f32,
+    /// Clock readings of recent presses, oldest first.
+    press_times: VecDeque<f32>,
 }
 
 impl BarColumn {
     pub fn new(color: Color) -> Self {
+        Self::with_pressed_color(color, None)
+    }
+
+    /// Creates a column whose held bar uses `pressed_color`, or the dimmed
+    /// base color when `None`.
+    pub fn with_pressed_color(color: Color, pressed_color: Option<Color>) -> Self {
         Self {
             bars: Vec::new(),
             press_count: 0,
             is_held: false,
+            pressed_color: pressed_color.unwrap_or_else(|| color.pressed()),
             color,
+            max_bars: None,
+            min_lifetime: None,
+            ghost_trail: None,
+            acceleration: 0.0,
+            bar_speed: None,
+            elapsed: 0.0,
+            press_times: VecDeque::new(),
         }
     }
 
+    /// Caps how many bars this column keeps; the oldest bars are dropped first.
+    pub fn set_max_bars(&mut self, max_bars: Option<usize>) {
+        self.max_bars = max_bars;
+    }
+
+    /// Changes the colors used for future bars; bars already on screen keep theirs.
+    pub fn set_colors(&mut self, color: Color, pressed_color: Option<Color>) {
+        self.pressed_color = pressed_color.unwrap_or_else(|| color.pressed());
+        self.color = color;
+    }
+
+    /// Keeps bars younger than `min_lifetime` even after they leave the window.
+    pub fn set_min_lifetime(&mut self, min_lifetime: Option<Duration>) {
+        self.min_lifetime = min_lifetime;
+    }
+
+    /// Keeps bars that leave the window as ghosts for `ghost_trail` before dropping them.
+    pub fn set_ghost_trail(&mut self, ghost_trail: Option<Duration>) {
+        self.ghost_trail = ghost_trail;
+    }
+
+    /// Sets how quickly released bars speed up (positive) or slow down (negative).
+    pub fn set_acceleration(&mut self, acceleration: f32) {
+        self.acceleration = acceleration;
+    }
+
+    /// Overrides the speed [`BarManager::update`] moves this column's bars at.
+    pub fn set_bar_speed(&mut self, bar_speed: Option<f32>) {
+        self.bar_speed = bar_speed;
+    }
+
+    /// This column's scroll speed, or `default` without an override.
+    pub fn bar_speed_or(&self, default: f32) -> f32 {
+        self.bar_speed.unwrap_or(default)
+    }
+
     pub fn on_key_press(&mut self) {
         if self.is_held {
             return;
         }
 
+        let press_interval = self.press_times.back().map(|&last| self.elapsed - last);
         self.bars.push(Bar {
             y_position: 0.0,
             height: 1.0,
             color: self.color.clone(),
-            pressed_color: self.color.pressed(),
+            pressed_color: self.pressed_color.clone(),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval,
+            ghost_since: None,
         });
         self.press_count += 1;
         self.is_held = true;
+        self.press_times.push_back(self.elapsed);
+
+        // The cap is at least one so the freshly pushed, held bar always survives.
+        if let Some(max_bars) = self.max_bars.map(|max| max.max(1))
+            && self.bars.len() > max_bars
+        {
+            let excess = self.bars.len() - max_bars;
+            self.bars.drain(..excess);
+        }
     }
 
     pub fn on_key_release(&mut self) {
+        if self.is_held
+            && let Some(bar) = self.bars.last_mut()
+        {
+            bar.released_at = Some(bar.age);
+        }
         self.is_held = false;
     }
 
@@ -54,6 +268,15 @@ impl BarColumn {
             return;
         }
 
+        self.elapsed += dt;
+        while self
+            .press_times
+            .front()
+            .is_some_and(|&time| self.elapsed - time > PRESS_HISTORY_SECS)
+        {
+            self.press_times.pop_front();
+        }
+
         let delta = bar_speed * dt;
 
         let active_index = if self.is_held {
@@ -63,11 +286,22 @@ impl BarColumn {
         };
 
         for (index, bar) in self.bars.iter_mut().enumerate() {
-            if Some(index) == active_index {
+            bar.age += dt;
+            if Some(index) == active_index || bar.ghost_since.is_some() {
+                continue;
+            }
+
+            if self.acceleration == 0.0 {
+                bar.y_position += delta;
                 continue;
             }
 
-            bar.y_position += delta;
+            // Average of start and end speed is exact for constant acceleration.
+            let start_velocity = bar.velocity.unwrap_or(bar_speed);
+            let end_velocity =
+                (start_velocity + self.acceleration * dt).max(bar_speed * MIN_VELOCITY_RATIO);
+            bar.y_position += (start_velocity + end_velocity) * 0.5 * dt;
+            bar.velocity = Some(end_velocity);
         }
 
         if self.is_held
@@ -77,15 +311,87 @@ impl BarColumn {
         }
     }
 
+    /// Presses per second over the trailing `window`, measured on the `update` clock.
+    ///
+    /// Windows longer than the retained history only see the last few seconds.
+    pub fn kps(&self, window: Duration) -> f32 {
+        let window_secs = window.as_secs_f32();
+        if window_secs <= 0.0 {
+            return 0.0;
+        }
+
+        let presses = self
+            .press_times
+            .iter()
+            .filter(|&&time| self.elapsed - time < window_secs)
+            .count();
+
+        presses as f32 / window_secs
+    }
+
+    /// Drops bars once their bottom edge (`y_position`) has passed
+    /// `window_height`, unless they are younger than the minimum lifetime.
+    ///
+    /// With a ghost trail, a released bar reaching the top of the window is
+    /// pinned there as a ghost instead, and dropped once the trail has elapsed.
     pub fn remove_offscreen(&mut self, window_height: f32) {
-        self.bars.retain(|bar| bar.y_position <= window_height);
+        let min_age = self
+            .min_lifetime
+            .map_or(0.0, |lifetime| lifetime.as_secs_f32());
+        if let Some(ghost_trail) = self.ghost_trail {
+            self.mark_ghosts(window_height);
+            let ghost_secs = ghost_trail.as_secs_f32();
+            self.bars.retain(|bar| {
+                bar.ghost_since
+                    .is_none_or(|ghost_since| bar.age - ghost_since < ghost_secs)
+                    || bar.age < min_age
+            });
+        }
+        self.bars
+            .retain(|bar| bar.y_position <= window_height || bar.age < min_age);
+    }
+
+    /// Turns released bars whose top edge reached `window_height` into ghosts,
+    /// pinned with their top edge at the top of the window.
+    fn mark_ghosts(&mut self, window_height: f32) {
+        let active_index = if self.is_held {
+            self.bars.len().checked_sub(1)
+        } else {
+            None
+        };
+        for (index, bar) in self.bars.iter_mut().enumerate() {
+            if Some(index) == active_index
+                || bar.ghost_since.is_some()
+                || bar.y_position + bar.height < window_height
+            {
+                continue;
+            }
+            bar.ghost_since = Some(bar.age);
+            bar.y_position = (window_height - bar.height).max(0.0);
+        }
     }
 }
 
+/// Captured animation state of every column, for replays.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BarSnapshot {
+    pub columns: BTreeMap<String, BarColumn>,
+}
+
 #[derive(Debug, Default)]
 pub struct BarManager {
     pub columns: HashMap<String, BarColumn>,
     pub bar_speed: f32,
+    pub max_bars_per_column: Option<usize>,
+    pub min_bar_lifetime: Option<Duration>,
+    pub ghost_trail: Option<Duration>,
+    pub bar_acceleration: f32,
+    /// Member key names of each configured combo, in config order.
+    pub combos: Vec<Vec<String>>,
+    /// Manager-wide clock advanced by `update`, in seconds.
+    elapsed: f32,
+    /// Clock readings of recent presses on any key, oldest first.
+    press_times: VecDeque<f32>,
 }
 
 impl BarManager {
@@ -93,15 +399,83 @@ impl BarManager {
         Self {
             columns: HashMap::new(),
             bar_speed,
+            max_bars_per_column: None,
+            min_bar_lifetime: None,
+            ghost_trail: None,
+            bar_acceleration: 0.0,
+            combos: Vec::new(),
+            elapsed: 0.0,
+            press_times: VecDeque::new(),
         }
     }
 
     pub fn on_key_press(&mut self, key: &str, color: Color) {
+        self.on_key_press_with_pressed_color(key, color, None);
+    }
+
+    /// Like [`BarManager::on_key_press`], with an explicit held-bar color for new columns.
+    pub fn on_key_press_with_pressed_color(
+        &mut self,
+        key: &str,
+        color: Color,
+        pressed_color: Option<Color>,
+    ) {
         let column = self
             .columns
             .entry(key.to_string())
-            .or_insert_with(|| BarColumn::new(color));
+            .or_insert_with(|| BarColumn::with_pressed_color(color, pressed_color));
+        column.set_max_bars(self.max_bars_per_column);
+        column.set_min_lifetime(self.min_bar_lifetime);
+        column.set_ghost_trail(self.ghost_trail);
         column.on_key_press();
+        self.press_times.push_back(self.elapsed);
+    }
+
+    /// Presses `key`, creating its column with the key's colors and bar speed.
+    pub fn on_configured_key_press(&mut self, key: &KeyConfig) {
+        self.on_key_press_with_pressed_color(
+            &key.key_name,
+            key.color.clone(),
+            key.pressed_color.clone(),
+        );
+        if let Some(column) = self.columns.get_mut(&key.key_name) {
+            column.set_bar_speed(key.bar_speed);
+        }
+    }
+
+    /// Creates columns for keys with an `initial_count` that have none yet,
+    /// starting their press count there without spawning a bar.
+    pub fn seed_counts(&mut self, keys: &[KeyConfig]) {
+        for key in keys {
+            let Some(count) = key.initial_count else {
+                continue;
+            };
+            self.columns.entry(key.key_name.clone()).or_insert_with(|| {
+                let mut column =
+                    BarColumn::with_pressed_color(key.color.clone(), key.pressed_color.clone());
+                column.set_bar_speed(key.bar_speed);
+                column.press_count = count;
+                column
+            });
+        }
+    }
+
+    /// Reconciles columns with a new key list after a config change.
+    ///
+    /// Columns for keys that are still configured keep their press count and
+    /// in-flight bars and pick up the new colors and bar speed; columns for
+    /// removed keys are dropped.
+    pub fn sync_keys(&mut self, keys: &[KeyConfig]) {
+        self.columns.retain(|key_name, column| {
+            match keys.iter().find(|key| key.key_name == *key_name) {
+                Some(key) => {
+                    column.set_colors(key.color.clone(), key.pressed_color.clone());
+                    column.set_bar_speed(key.bar_speed);
+                    true
+                }
+                None => false,
+            }
+        });
     }
 
     pub fn on_key_release(&mut self, key: &str) {
@@ -111,13 +485,139 @@ impl BarManager {
     }
 
     pub fn update(&mut self, dt: f32) {
+        self.elapsed += dt;
+        while self
+            .press_times
+            .front()
+            .is_some_and(|&time| self.elapsed - time > APM_HISTORY_SECS)
+        {
+            self.press_times.pop_front();
+        }
+
+        for column in self.columns.values_mut() {
+            column.set_acceleration(self.bar_acceleration);
+            let bar_speed = column.bar_speed_or(self.bar_speed);
+            column.update(dt, bar_speed);
+        }
+    }
+
+    /// Presses per minute across all keys over the trailing `window`,
+    /// measured on the `update` clock.
+    ///
+    /// Windows longer than the retained history only see the last minute.
+    pub fn apm(&self, window: Duration) -> f32 {
+        let window_secs = window.as_secs_f32();
+        if window_secs <= 0.0 {
+            return 0.0;
+        }
+
+        let presses = self
+            .press_times
+            .iter()
+            .filter(|&&time| self.elapsed - time < window_secs)
+            .count();
+
+        presses as f32 * 60.0 / window_secs
+    }
+
+    /// Combined presses per second across all columns.
+    pub fn total_kps(&self, window: Duration) -> f32 {
+        self.columns.values().map(|column| column.kps(window)).sum()
+    }
+
+    /// Presses counted across all columns.
+    pub fn total_presses(&self) -> u64 {
+        self.columns.values().map(|column| column.press_count).sum()
+    }
+
+    /// Number of keys currently held down.
+    pub fn held_count(&self) -> usize {
+        self.columns
+            .values()
+            .filter(|column| column.is_held)
+            .count()
+    }
+
+    /// Names of the keys currently held down, sorted.
+    pub fn held_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self
+            .columns
+            .iter()
+            .filter(|(_, column)| column.is_held)
+            .map(|(key, _)| key.clone())
+            .collect();
+        keys.sort();
+        keys
+    }
+
+    /// Whether nothing is animating: no bars on screen and no key held.
+    pub fn is_idle(&self) -> bool {
+        self.columns
+            .values()
+            .all(|column| column.bars.is_empty() && !column.is_held)
+    }
+
+    /// Zeroes every column's press count; bars and held state are kept.
+    pub fn reset_counters(&mut self) {
         for column in self.columns.values_mut() {
-            column.update(dt, self.bar_speed);
+            column.press_count = 0;
+        }
+    }
+
+    /// Removes all bars from every column, keeping counts.
+    pub fn clear_bars(&mut self) {
+        for column in self.columns.values_mut() {
+            column.bars.clear();
+        }
+    }
+
+    /// Indices into `combos` whose member keys are all currently held.
+    pub fn active_combos(&self) -> Vec<usize> {
+        self.combos
+            .iter()
+            .enumerate()
+            .filter(|(_, members)| {
+                !members.is_empty()
+                    && members
+                        .iter()
+                        .all(|key| self.columns.get(key).is_some_and(|column| column.is_held))
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Press count of every column, sorted by key name.
+    pub fn per_key_counts(&self) -> Vec<(String, u64)> {
+        let mut counts: Vec<(String, u64)> = self
+            .columns
+            .iter()
+            .map(|(key, column)| (key.clone(), column.press_count))
+            .collect();
+        counts.sort_by(|a, b| a.0.cmp(&b.0));
+        counts
+    }
+
+    /// Captures bars, press counts and held flags of every column.
+    pub fn snapshot(&self) -> BarSnapshot {
+        BarSnapshot {
+            columns: self
+                .columns
+                .iter()
+                .map(|(key, column)| (key.clone(), column.clone()))
+                .collect(),
         }
     }
 
+    /// Replaces all columns with a previously captured snapshot.
+    pub fn restore(&mut self, snapshot: BarSnapshot) {
+        self.columns = snapshot.columns.into_iter().collect();
+    }
+
+    /// Drops bars that have fully left the window in every column.
     pub fn remove_offscreen(&mut self, window_height: f32) {
         for column in self.columns.values_mut() {
+            column.set_min_lifetime(self.min_bar_lifetime);
+            column.set_ghost_trail(self.ghost_trail);
             column.remove_offscreen(window_height);
         }
     }
@@ -125,8 +625,13 @@ impl BarManager {
 
 #[cfg(test)]
 mod tests {
-    use super::{Bar, BarColumn, BarManager};
-    use crate::types::Color;
+    use std::time::Duration;
+
+    use super::{
+        Bar, BarColumn, BarManager, MIN_VELOCITY_RATIO, MergedRect, PRESS_HISTORY_SECS,
+        enforce_bar_gaps, merge_bar_rects,
+    };
+    use crate::types::{Color, KeyConfig};
 
     const EPSILON: f32 = 1e-6;
 
@@ -141,6 +646,121 @@ mod tests {
         Color::from_rgba_u8(255, 64, 32, 255)
     }
 
+    fn mk_bar(y_position: f32, height: f32, color: Color) -> Bar {
+        Bar {
+            y_position,
+            height,
+            color: color.clone(),
+            pressed_color: color,
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
+        }
+    }
+
+    #[test]
+    fn test_enforce_bar_gaps_shortens_the_lower_of_two_close_bars() {
+        let mut bars = [
+            mk_bar(32.0, 10.0, mk_color()),
+            mk_bar(0.0, 30.0, mk_color()),
+        ];
+
+        enforce_bar_gaps(&mut bars, 4.0);
+
+        assert_f32_eq(bars[0].y_position, 32.0);
+        assert_f32_eq(bars[0].height, 10.0);
+        assert_f32_eq(bars[1].y_position, 0.0);
+        assert_f32_eq(bars[1].height, 28.0);
+    }
+
+    #[test]
+    fn test_enforce_bar_gaps_keeps_bars_already_far_enough_apart() {
+        let mut bars = [
+            mk_bar(50.0, 10.0, mk_color()),
+            mk_bar(0.0, 30.0, mk_color()),
+        ];
+        let before = bars.clone();
+
+        enforce_bar_gaps(&mut bars, 4.0);
+        assert_eq!(bars, before);
+
+        let mut touching = [
+            mk_bar(30.0, 10.0, mk_color()),
+            mk_bar(0.0, 30.0, mk_color()),
+        ];
+        enforce_bar_gaps(&mut touching, 0.0);
+        assert_f32_eq(touching[1].height, 30.0);
+    }
+
+    #[test]
+    fn test_enforce_bar_gaps_squeezes_a_bar_inside_the_gap_to_zero() {
+        let mut bars = [
+            mk_bar(6.0, 10.0, mk_color()),
+            mk_bar(3.0, 1.0, mk_color()),
+            mk_bar(0.0, 2.0, mk_color()),
+        ];
+
+        enforce_bar_gaps(&mut bars, 4.0);
+
+        assert_f32_eq(bars[0].height, 10.0);
+        assert_f32_eq(bars[1].height, 0.0);
+        assert_f32_eq(bars[2].height, 0.0);
+    }
+
+    #[test]
+    fn test_merge_bar_rects_joins_adjacent_same_color_bars() {
+        let bars = [
+            mk_bar(30.0, 10.0, mk_color()),
+            mk_bar(0.0, 30.0, mk_color()),
+        ];
+
+        assert_eq!(
+            merge_bar_rects(&bars),
+            vec![MergedRect {
+                y_position: 0.0,
+                height: 40.0,
+                color: mk_color(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_merge_bar_rects_joins_overlapping_bars_up_to_highest_top() {
+        let bars = [
+            mk_bar(0.0, 50.0, mk_color()),
+            mk_bar(20.0, 10.0, mk_color()),
+            mk_bar(40.0, 30.0, mk_color()),
+        ];
+
+        let merged = merge_bar_rects(&bars);
+
+        assert_eq!(merged.len(), 1);
+        assert_f32_eq(merged[0].y_position, 0.0);
+        assert_f32_eq(merged[0].height, 70.0);
+    }
+
+    #[test]
+    fn test_merge_bar_rects_keeps_gapped_or_differently_colored_bars_apart() {
+        let other = Color::from_rgba_u8(0, 0, 255, 128);
+        let bars = [
+            mk_bar(0.0, 10.0, mk_color()),
+            mk_bar(15.0, 10.0, mk_color()),
+            mk_bar(20.0, 10.0, other.clone()),
+        ];
+
+        let merged = merge_bar_rects(&bars);
+
+        assert_eq!(
+            merged
+                .iter()
+                .map(|rect| (rect.y_position, rect.color.clone()))
+                .collect::<Vec<_>>(),
+            vec![(0.0, mk_color()), (15.0, mk_color()), (20.0, other)]
+        );
+    }
+
     #[test]
     fn test_bar_column_key_press_creates_new_bar_at_origin() {
         let mut column = BarColumn::new(mk_color());
@@ -186,6 +806,21 @@ mod tests {
         assert!(!column.is_held);
     }
 
+    #[test]
+    fn test_bar_column_release_records_time_since_release() {
+        let mut column = BarColumn::new(mk_color());
+        column.on_key_press();
+        column.update(0.25, 60.0);
+        assert_eq!(column.bars[0].since_release(), None);
+
+        column.on_key_release();
+        column.update(0.1, 60.0);
+        column.on_key_release();
+
+        let since_release = column.bars[0].since_release().expect("released bar");
+        assert_f32_eq(since_release, 0.1);
+    }
+
     #[test]
     fn test_bar_column_hold_stretches_last_bar_height_with_delta_time() {
         let mut column = BarColumn::new(mk_color());
@@ -217,12 +852,22 @@ mod tests {
             height: 3.0,
             color: mk_color(),
             pressed_color: mk_color().pressed(),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
         });
         column.bars.push(Bar {
             y_position: 50.0,
             height: 5.0,
             color: mk_color(),
             pressed_color: mk_color().pressed(),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
         });
 
         column.update(0.25, 100.0);
@@ -250,20 +895,101 @@ mod tests {
             height: 10.0,
             color: mk_color(),
             pressed_color: mk_color().pressed(),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
         });
         column.bars.push(Bar {
             y_position: 120.0,
             height: 5.0,
             color: mk_color(),
             pressed_color: mk_color().pressed(),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
         });
 
         column.remove_offscreen(100.0);
 
+        // The second bar's bottom edge at 120 is past the window top at 100.
         assert_eq!(column.bars.len(), 1);
         assert_f32_eq(column.bars[0].y_position, 25.0);
     }
 
+    #[test]
+    fn test_bar_column_remove_offscreen_keeps_bar_while_bottom_edge_is_on_screen() {
+        let mut column = BarColumn::new(mk_color());
+        column.bars.push(Bar {
+            y_position: 90.0,
+            height: 50.0,
+            color: mk_color(),
+            pressed_color: mk_color().pressed(),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
+        });
+
+        column.remove_offscreen(100.0);
+
+        // The top edge at 140 is past the window, but the bottom is still visible.
+        assert_eq!(column.bars.len(), 1);
+
+        column.bars[0].y_position = 101.0;
+        column.remove_offscreen(100.0);
+
+        assert!(column.bars.is_empty());
+    }
+
+    #[test]
+    fn test_bar_column_max_bars_caps_vector_and_keeps_counting() {
+        let mut column = BarColumn::new(mk_color());
+        column.set_max_bars(Some(3));
+
+        for _ in 0..20 {
+            column.on_key_press();
+            assert!(column.bars.len() <= 3);
+            column.on_key_release();
+        }
+
+        assert_eq!(column.bars.len(), 3);
+        assert_eq!(column.press_count, 20);
+    }
+
+    #[test]
+    fn test_bar_column_max_bars_never_drops_held_bar() {
+        let mut column = BarColumn::new(mk_color());
+        column.set_max_bars(Some(0));
+
+        column.on_key_press();
+        column.on_key_release();
+        column.update(0.5, 100.0);
+        column.on_key_press();
+
+        assert_eq!(column.bars.len(), 1);
+        assert!(column.is_held);
+        assert_f32_eq(column.bars[0].y_position, 0.0);
+    }
+
+    #[test]
+    fn test_bar_manager_applies_max_bars_per_column() {
+        let mut manager = BarManager::new(600.0);
+        manager.max_bars_per_column = Some(2);
+
+        for _ in 0..5 {
+            manager.on_key_press("Z", mk_color());
+            manager.on_key_release("Z");
+        }
+
+        assert_eq!(manager.columns["Z"].bars.len(), 2);
+        assert_eq!(manager.columns["Z"].press_count, 5);
+    }
+
     #[test]
     fn test_key_press_assigns_pressed_color_using_color_pressed() {
         let base = mk_color();
@@ -291,6 +1017,39 @@ mod tests {
         assert_f32_eq(manager.columns["X"].bars[0].height, 101.0);
     }
 
+    fn mk_key(name: &str, bar_speed: Option<f32>) -> KeyConfig {
+        KeyConfig {
+            bar_speed,
+            ..KeyConfig::new(name, mk_color())
+        }
+    }
+
+    #[test]
+    fn test_bar_manager_update_moves_columns_at_their_own_speed() {
+        let mut manager = BarManager::new(100.0);
+        manager.on_configured_key_press(&mk_key("Z", Some(300.0)));
+        manager.on_configured_key_press(&mk_key("X", None));
+        manager.on_key_release("Z");
+        manager.on_key_release("X");
+
+        manager.update(0.5);
+
+        assert_f32_eq(manager.columns["Z"].bars[0].y_position, 150.0);
+        assert_f32_eq(manager.columns["X"].bars[0].y_position, 50.0);
+    }
+
+    #[test]
+    fn test_bar_manager_sync_keys_updates_bar_speed_override() {
+        let mut manager = BarManager::new(100.0);
+        manager.on_configured_key_press(&mk_key("Z", Some(300.0)));
+        manager.on_key_release("Z");
+
+        manager.sync_keys(&[mk_key("Z", None)]);
+        manager.update(0.5);
+
+        assert_f32_eq(manager.columns["Z"].bars[0].y_position, 50.0);
+    }
+
     #[test]
     fn test_bar_manager_release_and_remove_offscreen_affects_each_column() {
         let mut manager = BarManager::new(100.0);
@@ -300,6 +1059,7 @@ mod tests {
         manager.on_key_press("X", color.clone());
         manager.on_key_release("Z");
         manager.update(2.0);
+        // Z's released bar sits at y=200, past the window top at 150.
         manager.remove_offscreen(150.0);
 
         assert!(!manager.columns["Z"].is_held);
@@ -309,4 +1069,426 @@ mod tests {
         assert_f32_eq(manager.columns["X"].bars[0].y_position, 0.0);
         assert_f32_eq(manager.columns["X"].bars[0].height, 201.0);
     }
+
+    #[test]
+    fn test_bar_column_kps_counts_presses_within_window() {
+        let mut column = BarColumn::new(mk_color());
+
+        // Eight presses, one every 0.125s, over one second of frames.
+        for _ in 0..8 {
+            column.update(0.0625, 100.0);
+            column.on_key_press();
+            column.update(0.0625, 100.0);
+            column.on_key_release();
+        }
+
+        assert_f32_eq(column.kps(Duration::from_secs(1)), 8.0);
+        assert_f32_eq(column.kps(Duration::from_millis(500)), 8.0);
+        assert_f32_eq(column.kps(Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_bar_column_records_interval_since_previous_press() {
+        let mut column = BarColumn::new(mk_color());
+
+        column.on_key_press();
+        column.update(0.25, 100.0);
+        column.on_key_release();
+        column.on_key_press();
+
+        assert_eq!(column.bars[0].press_interval, None);
+        assert_f32_eq(column.bars[1].press_interval.expect("interval"), 0.25);
+    }
+
+    #[test]
+    fn test_bar_column_kps_decays_once_presses_leave_window() {
+        let mut column = BarColumn::new(mk_color());
+
+        for _ in 0..4 {
+            column.on_key_press();
+            column.on_key_release();
+            column.update(0.1, 100.0);
+        }
+        column.update(0.75, 100.0);
+
+        // Presses happened at 0.0, 0.1, 0.2 and 0.3; now is 1.15.
+        assert_f32_eq(column.kps(Duration::from_secs(1)), 2.0);
+
+        column.update(PRESS_HISTORY_SECS, 100.0);
+
+        assert_f32_eq(column.kps(Duration::from_secs(1)), 0.0);
+        assert!(column.press_times.is_empty());
+    }
+
+    #[test]
+    fn test_bar_manager_total_kps_sums_columns() {
+        let mut manager = BarManager::new(100.0);
+
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_press("X", mk_color());
+        manager.on_key_release("Z");
+        manager.on_key_press("Z", mk_color());
+        manager.update(0.5);
+
+        assert_f32_eq(manager.total_kps(Duration::from_secs(1)), 3.0);
+    }
+
+    #[test]
+    fn test_bar_manager_apm_counts_presses_across_keys_within_window() {
+        let mut manager = BarManager::new(100.0);
+        let timeline: [&[&str]; 4] = [&["Z", "X"], &["Z"], &["X", "C"], &["Z"]];
+        for (step, keys) in timeline.iter().enumerate() {
+            if step > 0 {
+                manager.update(10.0);
+            }
+            for key in *keys {
+                manager.on_key_press(key, mk_color());
+                manager.on_key_release(key);
+            }
+        }
+        manager.update(0.5);
+
+        // Six presses over the last 30.5s, three of them in the last 15s.
+        assert_f32_eq(manager.apm(Duration::from_secs(60)), 6.0);
+        assert_f32_eq(manager.apm(Duration::from_secs(15)), 12.0);
+        assert_f32_eq(manager.apm(Duration::from_secs(5)), 12.0);
+        assert_f32_eq(manager.apm(Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_bar_manager_apm_forgets_presses_older_than_history() {
+        let mut manager = BarManager::new(100.0);
+        manager.on_key_press("Z", mk_color());
+        manager.update(30.0);
+        manager.on_key_press("X", mk_color());
+        manager.update(35.0);
+
+        assert_f32_eq(manager.apm(Duration::from_secs(120)), 0.5);
+        assert_eq!(manager.press_times.len(), 1);
+    }
+
+    #[test]
+    fn test_bar_manager_restore_returns_to_snapshot_state() {
+        let mut manager = BarManager::new(100.0);
+        manager.on_key_press("Z", mk_color());
+        manager.update(0.2);
+        manager.on_key_release("Z");
+        manager.on_key_press("X", mk_color());
+        manager.update(0.1);
+
+        let snapshot = manager.snapshot();
+        let json = serde_json::to_string(&snapshot).expect("snapshot should serialize");
+
+        manager.update(0.5);
+        manager.on_key_release("X");
+        manager.on_key_press("C", mk_color());
+        assert_ne!(manager.snapshot(), snapshot);
+
+        let decoded = serde_json::from_str(&json).expect("snapshot should deserialize");
+        manager.restore(decoded);
+
+        assert_eq!(manager.snapshot(), snapshot);
+        assert!(manager.columns["X"].is_held);
+        assert!(!manager.columns.contains_key("C"));
+    }
+
+    #[test]
+    fn test_bar_manager_is_idle_until_press_and_after_bars_leave() {
+        let mut manager = BarManager::new(1000.0);
+        assert!(manager.is_idle());
+
+        manager.on_key_press("Z", mk_color());
+        assert!(!manager.is_idle());
+
+        manager.on_key_release("Z");
+        assert!(!manager.is_idle(), "released bar is still moving");
+
+        manager.update(1.0);
+        manager.remove_offscreen(100.0);
+        assert!(manager.is_idle());
+    }
+
+    #[test]
+    fn test_bar_manager_reset_counters_zeroes_counts_and_keeps_bars() {
+        let mut manager = BarManager::new(100.0);
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_release("Z");
+        manager.on_key_press("X", mk_color());
+
+        manager.reset_counters();
+
+        assert_eq!(manager.total_presses(), 0);
+        assert_eq!(manager.columns["Z"].bars.len(), 1);
+        assert!(manager.columns["X"].is_held);
+
+        manager.clear_bars();
+        assert!(
+            manager
+                .columns
+                .values()
+                .all(|column| column.bars.is_empty())
+        );
+    }
+
+    #[test]
+    fn test_bar_manager_active_combos_requires_every_member_held() {
+        let mut manager = BarManager::new(100.0);
+        manager.combos = vec![
+            vec!["LControl".to_string(), "C".to_string()],
+            vec!["Z".to_string(), "X".to_string()],
+        ];
+
+        manager.on_key_press("LControl", mk_color());
+        assert!(manager.active_combos().is_empty());
+
+        manager.on_key_press("C", mk_color());
+        manager.on_key_press("Z", mk_color());
+        assert_eq!(manager.active_combos(), vec![0]);
+
+        manager.on_key_press("X", mk_color());
+        assert_eq!(manager.active_combos(), vec![0, 1]);
+
+        manager.on_key_release("LControl");
+        assert_eq!(manager.active_combos(), vec![1]);
+    }
+
+    #[test]
+    fn test_bar_manager_stats_for_empty_manager() {
+        let manager = BarManager::new(100.0);
+
+        assert_eq!(manager.total_presses(), 0);
+        assert_eq!(manager.held_count(), 0);
+        assert!(manager.per_key_counts().is_empty());
+    }
+
+    #[test]
+    fn test_bar_manager_stats_with_mixed_held_and_released_keys() {
+        let mut manager = BarManager::new(100.0);
+
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_release("Z");
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_press("X", mk_color());
+        manager.on_key_release("X");
+        manager.on_key_press("C", mk_color());
+
+        assert_eq!(manager.total_presses(), 4);
+        assert_eq!(manager.held_count(), 2);
+        assert_eq!(
+            manager.per_key_counts(),
+            vec![
+                ("C".to_string(), 1),
+                ("X".to_string(), 1),
+                ("Z".to_string(), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bar_manager_held_keys_lists_only_held_keys_sorted() {
+        let mut manager = BarManager::new(100.0);
+        assert!(manager.held_keys().is_empty());
+
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_press("X", mk_color());
+        manager.on_key_release("X");
+        manager.on_key_press("C", mk_color());
+        manager.on_key_press("V", mk_color());
+
+        assert_eq!(manager.held_keys(), vec!["C", "V", "Z"]);
+
+        manager.on_key_release("Z");
+        manager.on_key_press("X", mk_color());
+
+        assert_eq!(manager.held_keys(), vec!["C", "V", "X"]);
+    }
+
+    #[test]
+    fn test_bar_manager_seed_counts_only_creates_missing_columns() {
+        let mut manager = BarManager::new(100.0);
+        manager.on_key_press("X", mk_color());
+        let keys = [
+            KeyConfig {
+                initial_count: Some(50),
+                ..mk_key("Z", None)
+            },
+            KeyConfig {
+                initial_count: Some(50),
+                ..mk_key("X", None)
+            },
+            mk_key("C", None),
+        ];
+
+        manager.seed_counts(&keys);
+
+        assert_eq!(manager.columns["Z"].press_count, 50);
+        assert!(manager.columns["Z"].bars.is_empty());
+        assert!(!manager.columns["Z"].is_held);
+        assert_eq!(manager.columns["X"].press_count, 1, "running count is kept");
+        assert!(!manager.columns.contains_key("C"));
+    }
+
+    #[test]
+    fn test_bar_manager_total_presses_sums_counters() {
+        let mut manager = BarManager::new(100.0);
+
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_release("Z");
+        manager.on_key_press("Z", mk_color());
+        manager.on_key_press("X", mk_color());
+
+        assert_eq!(manager.total_presses(), 3);
+    }
+
+    #[test]
+    fn test_bar_column_remove_offscreen_keeps_bars_younger_than_min_lifetime() {
+        let mut column = BarColumn::new(mk_color());
+        column.set_min_lifetime(Some(Duration::from_millis(200)));
+
+        column.on_key_press();
+        column.on_key_release();
+        // A fast scroll pushes the tap fully offscreen within 0.1s.
+        column.update(0.1, 10_000.0);
+        column.remove_offscreen(700.0);
+
+        assert_eq!(column.bars.len(), 1);
+
+        column.update(0.15, 10_000.0);
+        column.remove_offscreen(700.0);
+
+        assert!(column.bars.is_empty());
+    }
+
+    #[test]
+    fn test_bar_column_ghost_trail_pins_bar_at_top_until_it_fades_out() {
+        let trail = Duration::from_millis(500);
+        let mut column = BarColumn::new(mk_color());
+        column.set_ghost_trail(Some(trail));
+        column.on_key_press();
+        column.update(0.125, 1_000.0);
+        column.on_key_release();
+        column.update(0.5, 1_000.0);
+
+        column.remove_offscreen(700.0);
+        assert!(
+            column.bars[0].ghost_since.is_none(),
+            "bar still below the top"
+        );
+
+        column.update(0.1, 1_000.0);
+        column.remove_offscreen(700.0);
+        assert_eq!(column.bars.len(), 1, "bar reaching the top becomes a ghost");
+        let ghost_since = column.bars[0].ghost_since.expect("ghost mark");
+        assert_f32_eq(ghost_since, column.bars[0].age);
+        assert_f32_eq(column.bars[0].y_position, 574.0);
+        assert_f32_eq(column.bars[0].ghost_alpha(trail), 1.0);
+
+        column.update(0.25, 1_000.0);
+        column.remove_offscreen(700.0);
+        assert_eq!(column.bars.len(), 1);
+        assert_f32_eq(column.bars[0].y_position, 574.0);
+        assert_f32_eq(column.bars[0].ghost_since.expect("ghost mark"), ghost_since);
+        assert_f32_eq(column.bars[0].ghost_alpha(trail), 0.5);
+
+        column.update(0.25, 1_000.0);
+        column.remove_offscreen(700.0);
+        assert!(column.bars.is_empty(), "ghost is dropped after the trail");
+    }
+
+    #[test]
+    fn test_bar_column_ghost_trail_does_not_pin_held_bar() {
+        let mut column = BarColumn::new(mk_color());
+        column.set_ghost_trail(Some(Duration::from_millis(500)));
+        column.on_key_press();
+        column.update(1.0, 1_000.0);
+
+        column.remove_offscreen(700.0);
+
+        assert!(column.bars[0].ghost_since.is_none());
+        assert_f32_eq(column.bars[0].height, 1_001.0);
+    }
+
+    #[test]
+    fn test_bar_ghost_alpha_decays_linearly_and_is_opaque_before_ghosting() {
+        let trail = Duration::from_millis(400);
+        let mut bar = mk_bar(0.0, 10.0, mk_color());
+        bar.age = 1.0;
+        assert_f32_eq(bar.ghost_alpha(trail), 1.0);
+
+        bar.ghost_since = Some(1.0);
+        for (age, alpha) in [(1.0, 1.0), (1.1, 0.75), (1.3, 0.25), (1.4, 0.0), (2.0, 0.0)] {
+            bar.age = age;
+            assert_f32_eq(bar.ghost_alpha(trail), alpha);
+        }
+        assert_f32_eq(bar.ghost_alpha(Duration::ZERO), 0.0);
+    }
+
+    #[test]
+    fn test_bar_column_without_ghost_trail_drops_offscreen_bars_immediately() {
+        let mut column = BarColumn::new(mk_color());
+        column.on_key_press();
+        column.on_key_release();
+        column.update(0.1, 10_000.0);
+
+        column.remove_offscreen(700.0);
+
+        assert!(column.bars.is_empty());
+    }
+
+    #[test]
+    fn test_bar_column_held_bar_uses_explicit_pressed_color() {
+        let pressed = Color::from_rgba_u8(255, 255, 255, 255);
+        let mut column = BarColumn::with_pressed_color(mk_color(), Some(pressed.clone()));
+
+        column.on_key_press();
+
+        assert_eq!(column.bars[0].pressed_color, pressed);
+        assert_eq!(column.bars[0].color, mk_color());
+    }
+
+    #[test]
+    fn test_bar_column_without_pressed_color_dims_base_color() {
+        let mut column = BarColumn::new(mk_color());
+
+        column.on_key_press();
+
+        assert_eq!(column.bars[0].pressed_color, mk_color().pressed());
+    }
+
+    #[test]
+    fn test_bar_column_acceleration_changes_displacement() {
+        let mut constant = BarColumn::new(mk_color());
+        let mut accelerating = BarColumn::new(mk_color());
+        let mut decelerating = BarColumn::new(mk_color());
+        accelerating.set_acceleration(400.0);
+        decelerating.set_acceleration(-400.0);
+
+        for column in [&mut constant, &mut accelerating, &mut decelerating] {
+            column.on_key_press();
+            column.on_key_release();
+            for _ in 0..10 {
+                column.update(0.05, 600.0);
+            }
+        }
+
+        // 0.5s at 600px/s, plus or minus a*t^2/2 = 400 * 0.25 / 2 = 50px.
+        assert_f32_eq(constant.bars[0].y_position, 300.0);
+        assert!((accelerating.bars[0].y_position - 350.0).abs() < 1e-3);
+        assert!((decelerating.bars[0].y_position - 250.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_bar_column_deceleration_never_stops_bars() {
+        let mut column = BarColumn::new(mk_color());
+        column.set_acceleration(-10_000.0);
+
+        column.on_key_press();
+        column.on_key_release();
+        for _ in 0..100 {
+            column.update(0.1, 600.0);
+        }
+
+        let velocity = column.bars[0].velocity.expect("bar should be moving");
+        assert_f32_eq(velocity, 600.0 * MIN_VELOCITY_RATIO);
+    }
 }
@@ -7,9 +7,50 @@ use std::path::PathBuf;
 #[derive(Parser, Debug)]
 #[command(name = "key-overlay", about = "Key press overlay for osu!", version)]
 pub struct Args {
-    /// Path to config file
-    #[arg(short, long, default_value = "config.toml")]
+    /// Path to config file [default: platform config directory]
+    #[arg(short, long, default_value_os_t = default_config_path(), hide_default_value = true)]
     pub config: PathBuf,
+
+    /// Draw alignment gridlines and the window size for capture setup
+    #[arg(long)]
+    pub setup_grid: bool,
+
+    /// Validate the config, print any warnings and exit without opening a window
+    #[arg(long)]
+    pub check: bool,
+
+    /// Print every supported key name and exit
+    #[arg(long)]
+    pub list_keys: bool,
+
+    /// Print the fully resolved config as TOML and exit
+    #[arg(long)]
+    pub print_config: bool,
+
+    /// Print a JSON schema of the config file for editor autocomplete and exit
+    #[arg(long)]
+    pub emit_schema: bool,
+
+    /// Record every input event to this file for later replay
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<PathBuf>,
+}
+
+/// File name of the config inside the config directory.
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// Default config path: `key-overlay/config.toml` in the platform config
+/// directory (e.g. `~/.config` on Linux, `%APPDATA%` on Windows), or
+/// `config.toml` in the current directory when there is none.
+pub fn default_config_path() -> PathBuf {
+    config_path_in(dirs::config_dir())
+}
+
+fn config_path_in(config_dir: Option<PathBuf>) -> PathBuf {
+    match config_dir {
+        Some(dir) => dir.join("key-overlay").join(CONFIG_FILE_NAME),
+        None => PathBuf::from(CONFIG_FILE_NAME),
+    }
 }
 
 /// Parse command line arguments
@@ -27,6 +68,12 @@ mod tests {
         // Simulate: cargo run (no args)
         let args = Args {
             config: PathBuf::from("config.toml"),
+            setup_grid: false,
+            check: false,
+            list_keys: false,
+            print_config: false,
+            emit_schema: false,
+            record: None,
         };
         assert_eq!(args.config, PathBuf::from("config.toml"));
     }
@@ -36,6 +83,12 @@ mod tests {
         // Simulate: cargo run --config custom.toml
         let args = Args {
             config: PathBuf::from("custom.toml"),
+            setup_grid: false,
+            check: false,
+            list_keys: false,
+            print_config: false,
+            emit_schema: false,
+            record: None,
         };
         assert_eq!(args.config, PathBuf::from("custom.toml"));
     }
@@ -45,7 +98,113 @@ mod tests {
         // Simulate: cargo run --config /path/to/custom.toml
         let args = Args {
             config: PathBuf::from("/path/to/custom.toml"),
+            setup_grid: false,
+            check: false,
+            list_keys: false,
+            print_config: false,
+            emit_schema: false,
+            record: None,
         };
         assert_eq!(args.config, PathBuf::from("/path/to/custom.toml"));
     }
+
+    #[test]
+    fn test_default_config_path_ends_in_config_toml() {
+        assert!(default_config_path().ends_with("config.toml"));
+
+        let args = Args::try_parse_from(["key-overlay"]).expect("no args should parse");
+        assert_eq!(args.config, default_config_path());
+    }
+
+    #[test]
+    fn test_config_path_in_uses_app_subdirectory() {
+        let path = config_path_in(Some(PathBuf::from("/home/user/.config")));
+
+        assert_eq!(
+            path,
+            PathBuf::from("/home/user/.config/key-overlay/config.toml")
+        );
+    }
+
+    #[test]
+    fn test_config_path_in_falls_back_to_current_directory() {
+        assert_eq!(config_path_in(None), PathBuf::from("config.toml"));
+    }
+
+    #[test]
+    fn test_setup_grid_flag_defaults_off() {
+        let args = Args::try_parse_from(["key-overlay"]).expect("no args should parse");
+        assert!(!args.setup_grid);
+
+        let args =
+            Args::try_parse_from(["key-overlay", "--setup-grid"]).expect("flag should parse");
+        assert!(args.setup_grid);
+    }
+
+    #[test]
+    fn test_check_flag_parses() {
+        let args = Args::try_parse_from(["key-overlay", "--config", "foo.toml", "--check"])
+            .expect("check flag should parse");
+
+        assert!(args.check);
+        assert_eq!(args.config, PathBuf::from("foo.toml"));
+        assert!(
+            !Args::try_parse_from(["key-overlay"])
+                .expect("no args")
+                .check
+        );
+    }
+
+    #[test]
+    fn test_list_keys_flag_parses() {
+        let args = Args::try_parse_from(["key-overlay", "--list-keys"]).expect("flag should parse");
+        assert!(args.list_keys);
+
+        assert!(
+            !Args::try_parse_from(["key-overlay"])
+                .expect("no args")
+                .list_keys
+        );
+    }
+
+    #[test]
+    fn test_print_config_flag_parses() {
+        let args = Args::try_parse_from(["key-overlay", "--config", "x.toml", "--print-config"])
+            .expect("print-config flag should parse");
+
+        assert!(args.print_config);
+        assert_eq!(args.config, PathBuf::from("x.toml"));
+        assert!(
+            !Args::try_parse_from(["key-overlay"])
+                .expect("no args")
+                .print_config
+        );
+    }
+
+    #[test]
+    fn test_emit_schema_flag_parses() {
+        let args =
+            Args::try_parse_from(["key-overlay", "--emit-schema"]).expect("flag should parse");
+        assert!(args.emit_schema);
+
+        assert!(
+            !Args::try_parse_from(["key-overlay"])
+                .expect("no args")
+                .emit_schema
+        );
+    }
+
+    #[test]
+    fn test_record_flag_takes_a_path() {
+        let args = Args::try_parse_from(["key-overlay", "--record", "events.jsonl"])
+            .expect("record flag should parse");
+
+        assert_eq!(args.record, Some(PathBuf::from("events.jsonl")));
+        assert!(
+            Args::try_parse_from(["key-overlay"])
+                .expect("no args")
+                .record
+                .is_none()
+        );
+    }
 }
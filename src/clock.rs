@@ -0,0 +1,82 @@
+//! Source of the current time for the orchestrator.
+//!
+//! Production code uses [`SystemClock`]; tests drive a [`MockClock`] by hand
+//! so time-dependent logic (double-Escape, idle hide, auto-save) runs without
+//! sleeping.
+
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Reads the current instant.
+pub trait Clock: Debug + Send + Sync {
+    /// Current instant according to this clock.
+    fn now(&self) -> Instant;
+}
+
+/// Clock backed by [`Instant::now`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually advanced clock; clones share the same time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    now: Arc<Mutex<Instant>>,
+}
+
+impl MockClock {
+    /// Creates a clock stopped at `start`.
+    pub fn new(start: Instant) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(start)),
+        }
+    }
+
+    /// Moves the clock forward by `by`.
+    pub fn advance(&self, by: Duration) {
+        *self.lock() += by;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Instant> {
+        self.now
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new(Instant::now())
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        *self.lock()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::{Clock, MockClock};
+
+    #[test]
+    fn test_mock_clock_only_moves_when_advanced() {
+        let start = Instant::now();
+        let clock = MockClock::new(start);
+        let shared = clock.clone();
+
+        assert_eq!(clock.now(), start);
+        shared.advance(Duration::from_millis(250));
+
+        assert_eq!(clock.now(), start + Duration::from_millis(250));
+    }
+}
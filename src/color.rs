@@ -2,6 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 
+use crate::types;
+
 /// Represents a color in RGBA format with u8 components (0-255)
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Color {
@@ -73,6 +75,23 @@ pub fn parse_color(s: &str) -> Result<Color, ColorError> {
     Ok(Color { r, g, b, a })
 }
 
+/// Parse a color string like [`parse_color`], returning the normalized
+/// [`types::Color`] used by the config and renderer.
+///
+/// # Examples
+/// ```
+/// use key_overlay_rs::color::parse_color_normalized;
+/// use key_overlay_rs::types::Color;
+/// assert_eq!(parse_color_normalized("255,0,0"), Ok(Color::new(1.0, 0.0, 0.0, 1.0)));
+/// assert!(parse_color_normalized("invalid").is_err());
+/// ```
+pub fn parse_color_normalized(s: &str) -> Result<types::Color, ColorError> {
+    let parsed = parse_color(s)?;
+    Ok(types::Color::from_rgba_u8(
+        parsed.r, parsed.g, parsed.b, parsed.a,
+    ))
+}
+
 /// Parse a color string with a default fallback on error
 ///
 /// # Examples
@@ -86,6 +105,15 @@ pub fn parse_color_or_default(s: &str, default: Color) -> Color {
     parse_color(s).unwrap_or(default)
 }
 
+/// Linearly interpolates each channel from `a` (at `t = 0`) to `b` (at `t = 1`).
+///
+/// `t` is clamped to `[0, 1]`.
+pub fn lerp_color(a: &types::Color, b: &types::Color, t: f32) -> types::Color {
+    let t = t.clamp(0.0, 1.0);
+    let mix = |from: f32, to: f32| from + (to - from) * t;
+    types::Color::new(mix(a.r, b.r), mix(a.g, b.g), mix(a.b, b.b), mix(a.a, b.a))
+}
+
 /// Helper: parse a u8 value, clamping values > 255 to 255, returning None for non-numeric
 fn parse_u8_clamped(s: &str) -> Result<Option<u8>, ColorError> {
     let trimmed = s.trim();
@@ -109,6 +137,37 @@ fn parse_u8_clamped(s: &str) -> Result<Option<u8>, ColorError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_color_normalized_matches_two_step_conversion() {
+        for input in [
+            "255,0,128,200",
+            "0,0,0",
+            " 12 , 34 , 56 ",
+            "300,255,255,255",
+            "1,2,3,0",
+        ] {
+            let parsed = parse_color(input).expect("valid color");
+            let two_step = types::Color::from_rgba_u8(parsed.r, parsed.g, parsed.b, parsed.a);
+
+            assert_eq!(
+                parse_color_normalized(input),
+                Ok(two_step),
+                "input {input:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_color_normalized_reports_the_same_errors() {
+        for input in ["", "1,2", "a,b,c", "1,2,3,4,5"] {
+            assert_eq!(
+                parse_color_normalized(input),
+                Err(parse_color(input).expect_err("invalid color")),
+                "input {input:?}"
+            );
+        }
+    }
+
     #[test]
     fn test_parse_color_valid_rgba() {
         let result = parse_color("255,0,128,200");
@@ -293,6 +352,30 @@ mod tests {
         assert_eq!(c1, c2);
     }
 
+    #[test]
+    fn test_lerp_color_endpoints_and_midpoint() {
+        let a = types::Color::new(0.0, 0.2, 1.0, 1.0);
+        let b = types::Color::new(1.0, 0.6, 0.0, 0.5);
+
+        assert_eq!(lerp_color(&a, &b, 0.0), a);
+        assert_eq!(lerp_color(&a, &b, 1.0), b);
+
+        let mid = lerp_color(&a, &b, 0.5);
+        assert!((mid.r - 0.5).abs() < 1e-6);
+        assert!((mid.g - 0.4).abs() < 1e-6);
+        assert!((mid.b - 0.5).abs() < 1e-6);
+        assert!((mid.a - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_lerp_color_clamps_t_outside_unit_range() {
+        let a = types::Color::new(0.0, 0.0, 0.0, 1.0);
+        let b = types::Color::new(1.0, 1.0, 1.0, 1.0);
+
+        assert_eq!(lerp_color(&a, &b, -2.0), a);
+        assert_eq!(lerp_color(&a, &b, 3.5), b);
+    }
+
     #[test]
     fn test_color_inequality() {
         let c1 = Color {
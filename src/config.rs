@@ -1,59 +1,283 @@
-//! Configuration loading and validation from TOML.
+//! Configuration loading and validation from TOML, JSON or RON.
 
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
+use schemars::JsonSchema;
 use serde::Deserialize;
+use tracing::{info, warn};
 
-use crate::color::parse_color;
-use crate::types::{AppConfig, AppError, Color, KeyConfig};
+use crate::color::parse_color_normalized;
+use crate::input::{BackendKind, KeyId};
+use crate::layout::calculate_window_size;
+use crate::types::{
+    AppConfig, AppError, BarPattern, Color, Combo, GoalBarPosition, KeyAxis, KeyConfig, KeyGroup,
+    KeyLabelAlign, MAX_FPS, Theme,
+};
+
+/// Smallest usable `keySize`; smaller values are clamped up to it.
+const MIN_KEY_SIZE: f32 = 10.0;
+
+/// Allowed range for `keyLabelScale` and `counterScale`.
+const FONT_SCALE_RANGE: std::ops::RangeInclusive<f32> = 0.05..=1.0;
+
+/// Current config schema version, written as `[general] version`.
+pub const CONFIG_VERSION: u32 = 1;
 
 /// Raw TOML configuration with optional fields for graceful fallback to defaults.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 #[serde(default)]
 pub struct RawConfig {
     pub general: RawGeneral,
     pub key: Vec<RawKeyConfig>,
+    pub theme: Vec<RawTheme>,
+    pub combo: Vec<RawCombo>,
+    pub group: Vec<RawGroup>,
 }
 
 /// Raw `[general]` TOML section.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 #[serde(default, rename_all = "camelCase")]
 pub struct RawGeneral {
+    pub version: Option<u32>,
     pub height: Option<f32>,
     pub key_size: Option<f32>,
     pub bar_speed: Option<f32>,
     pub background_color: Option<String>,
     pub margin: Option<f32>,
     pub outline_thickness: Option<f32>,
+    pub bar_pattern: Option<BarPattern>,
+    pub max_bars_per_column: Option<usize>,
     pub fading: Option<bool>,
     pub counter: Option<bool>,
     pub fps: Option<u32>,
     pub log_to_file: Option<bool>,
+    pub input_backend: Option<BackendKind>,
+    pub show_peak_kps: Option<bool>,
+    pub window_x: Option<i32>,
+    pub window_y: Option<i32>,
+    pub show_kps: Option<bool>,
+    pub toggle_theme_key: Option<String>,
+    pub key_label_align: Option<KeyLabelAlign>,
+    pub min_bar_lifetime_ms: Option<u64>,
+    pub fallback_fonts: Option<Vec<PathBuf>>,
+    pub idle_hide_ms: Option<u64>,
+    pub strict_key_names: Option<bool>,
+    pub bar_acceleration: Option<f32>,
+    pub recenter_on_reload: Option<bool>,
+    pub show_event_log: Option<bool>,
+    pub event_log_size: Option<usize>,
+    pub session_goal: Option<u64>,
+    pub session_goal_position: Option<GoalBarPosition>,
+    pub bar_width_ratio: Option<f32>,
+    pub move_key_left_key: Option<String>,
+    pub move_key_right_key: Option<String>,
+    pub auto_save_tweaks: Option<bool>,
+    pub counter_format: Option<String>,
+    pub font_path: Option<PathBuf>,
+    pub corner_radius: Option<f32>,
+    pub monitor_index: Option<usize>,
+    pub transparent_background: Option<bool>,
+    pub reset_key: Option<String>,
+    pub reset_clears_bars: Option<bool>,
+    pub dt_smoothing: Option<f32>,
+    pub key_label_scale: Option<f32>,
+    pub counter_scale: Option<f32>,
+    pub key_axis: Option<KeyAxis>,
+    pub replay_file: Option<PathBuf>,
+    pub idle_fps: Option<u32>,
+    pub toggle_passthrough_key: Option<String>,
+    pub release_decay_ms: Option<u64>,
+    pub show_anchor_line: Option<bool>,
+    pub anchor_line_color: Option<String>,
+    pub show_help_on_startup: Option<bool>,
+    pub help_duration_ms: Option<u64>,
+    pub merge_bars: Option<bool>,
+    pub min_bar_height: Option<f32>,
+    pub max_window_width: Option<f32>,
+    pub max_window_height: Option<f32>,
+    pub color_transition_ms: Option<u64>,
+    pub toggle_counter_key: Option<String>,
+    pub stats_export_path: Option<PathBuf>,
+    pub edge_padding: Option<f32>,
+    pub key_spacing: Option<f32>,
+    pub pause_when_unfocused: Option<bool>,
+    pub bar_outline_color: Option<String>,
+    pub velocity_coloring: Option<bool>,
+    pub reverse_key_order: Option<bool>,
+    pub bar_cap_fade_px: Option<f32>,
+    pub ui_scale: Option<f32>,
+    pub bar_gap_px: Option<f32>,
+    pub ghost_trail_ms: Option<u64>,
+    pub show_apm: Option<bool>,
 }
 
 /// Raw `[[key]]` TOML section.
-#[derive(Debug, Default, Deserialize)]
+#[derive(Debug, Default, Deserialize, JsonSchema)]
 #[serde(default, rename_all = "camelCase")]
 pub struct RawKeyConfig {
     pub name: Option<String>,
+    pub display_name: Option<String>,
     pub color: Option<String>,
     pub size: Option<f32>,
+    pub spacer_before: Option<f32>,
+    pub pressed_color: Option<String>,
+    pub fading: Option<bool>,
+    pub trail_color: Option<String>,
+    pub bar_speed: Option<f32>,
+    pub icon_path: Option<PathBuf>,
+    pub initial_count: Option<u64>,
+    pub aliases: Vec<String>,
+}
+
+/// Raw `[[theme]]` TOML section.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RawTheme {
+    pub name: Option<String>,
+    pub background_color: Option<String>,
+    pub key_colors: BTreeMap<String, String>,
+}
+
+/// Raw `[[combo]]` TOML section.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RawCombo {
+    pub keys: Vec<String>,
+    pub color: Option<String>,
+}
+
+/// Raw `[[group]]` TOML section.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(default, rename_all = "camelCase")]
+pub struct RawGroup {
+    pub label: Option<String>,
+    pub color: Option<String>,
+    pub keys: Vec<String>,
+}
+
+/// On-disk configuration format, selected by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Ron,
+}
+
+impl ConfigFormat {
+    /// Picks the format from the path's extension, defaulting to TOML.
+    pub fn from_path(path: &Path) -> Self {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+
+        match extension.as_deref() {
+            Some("json") => Self::Json,
+            Some("ron") => Self::Ron,
+            _ => Self::Toml,
+        }
+    }
 }
 
-/// Loads and parses configuration from disk.
+/// Loads and parses configuration from disk, using the file extension to pick the format.
 pub fn load_config(path: &Path) -> Result<AppConfig, AppError> {
-    let toml_str = std::fs::read_to_string(path)?;
-    load_from_str(&toml_str)
+    let contents = std::fs::read_to_string(path)?;
+    resolve_raw_config(parse_raw_config(&contents, ConfigFormat::from_path(path))?)
+}
+
+/// Loads and validates a config file without applying it, returning the warnings.
+///
+/// Unlike [`ensure_config_exists`], a missing file is an error rather than created.
+pub fn check_config(path: &Path) -> Result<Vec<String>, AppError> {
+    let contents = std::fs::read_to_string(path)?;
+    let raw = parse_raw_config(&contents, ConfigFormat::from_path(path))?;
+    resolve_raw_config_with_warnings(raw).map(|(_, warnings)| warnings)
+}
+
+/// JSON schema of the config file, for editor validation and autocomplete.
+///
+/// Generated from the raw config structs, so every field is optional and
+/// named as in the file. TOML, JSON and RON configs share the same schema.
+pub fn json_schema() -> Result<String, AppError> {
+    let schema = schemars::schema_for!(RawConfig);
+    serde_json::to_string_pretty(&schema)
+        .map_err(|err| AppError::Config(format!("failed to serialize config schema: {err}")))
 }
 
 /// Loads and parses configuration from TOML text.
 pub fn load_from_str(toml_str: &str) -> Result<AppConfig, AppError> {
-    let raw: RawConfig = toml::from_str(toml_str)
-        .map_err(|err| AppError::Config(format!("failed to parse TOML: {err}")))?;
+    resolve_raw_config(parse_raw_config(toml_str, ConfigFormat::Toml)?)
+}
+
+/// Loads and parses configuration from JSON text using the same schema as TOML.
+pub fn load_from_json_str(json_str: &str) -> Result<AppConfig, AppError> {
+    resolve_raw_config(parse_raw_config(json_str, ConfigFormat::Json)?)
+}
+
+/// Loads and parses configuration from RON text using the same schema as TOML.
+///
+/// Optional fields may be written without `Some(..)`.
+pub fn load_from_ron_str(ron_str: &str) -> Result<AppConfig, AppError> {
+    resolve_raw_config(parse_raw_config(ron_str, ConfigFormat::Ron)?)
+}
+
+fn parse_raw_config(contents: &str, format: ConfigFormat) -> Result<RawConfig, AppError> {
+    let mut raw: RawConfig = match format {
+        ConfigFormat::Toml => toml::from_str(contents)
+            .map_err(|err| AppError::Config(format!("failed to parse TOML: {err}")))?,
+        ConfigFormat::Json => serde_json::from_str(contents)
+            .map_err(|err| AppError::Config(format!("failed to parse JSON: {err}")))?,
+        ConfigFormat::Ron => ron_options()
+            .from_str(contents)
+            .map_err(|err| AppError::Config(format!("failed to parse RON: {err}")))?,
+    };
+
+    for note in migrate_raw_config(&mut raw) {
+        info!("config: {note}");
+    }
+    Ok(raw)
+}
+
+/// Upgrades `raw` from an older schema version in place, returning one note
+/// per applied step.
+///
+/// A missing `version` counts as version 0. Each step transforms the config
+/// written for `version` into the next one; none currently changes any values.
+fn migrate_raw_config(raw: &mut RawConfig) -> Vec<String> {
+    let from = raw.general.version.unwrap_or(0);
+    if from > CONFIG_VERSION {
+        warn!(
+            "config: version {from} is newer than supported version {CONFIG_VERSION}; unknown fields are ignored"
+        );
+        return Vec::new();
+    }
+
+    let notes = (from..CONFIG_VERSION)
+        .map(|version| format!("migrated config from version {version} to {}", version + 1))
+        .collect();
+    raw.general.version = Some(CONFIG_VERSION);
+    notes
+}
+
+fn ron_options() -> ron::Options {
+    ron::Options::default().with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+}
+
+fn resolve_raw_config(raw: RawConfig) -> Result<AppConfig, AppError> {
+    let (config, warnings) = resolve_raw_config_with_warnings(raw)?;
+    for warning in &warnings {
+        warn!("config: {warning}");
+    }
+    Ok(config)
+}
 
+/// Resolves raw config against defaults, fixing up invalid values and
+/// returning the warnings that describe each fix.
+fn resolve_raw_config_with_warnings(raw: RawConfig) -> Result<(AppConfig, Vec<String>), AppError> {
     let defaults = AppConfig::default();
-    let mut config = AppConfig {
+    let config = AppConfig {
         height: raw.general.height.unwrap_or(defaults.height),
         key_size: raw.general.key_size.unwrap_or(defaults.key_size),
         bar_speed: raw.general.bar_speed.unwrap_or(defaults.bar_speed),
@@ -66,39 +290,553 @@ pub fn load_from_str(toml_str: &str) -> Result<AppConfig, AppError> {
             .general
             .outline_thickness
             .unwrap_or(defaults.outline_thickness),
+        bar_pattern: raw.general.bar_pattern.unwrap_or(defaults.bar_pattern),
+        max_bars_per_column: raw
+            .general
+            .max_bars_per_column
+            .or(defaults.max_bars_per_column),
         fading: raw.general.fading.unwrap_or(defaults.fading),
         counter: raw.general.counter.unwrap_or(defaults.counter),
         fps: raw.general.fps.unwrap_or(defaults.fps),
         log_to_file: raw.general.log_to_file.unwrap_or(defaults.log_to_file),
+        input_backend: raw.general.input_backend.unwrap_or(defaults.input_backend),
+        show_peak_kps: raw.general.show_peak_kps.unwrap_or(defaults.show_peak_kps),
+        window_x: raw.general.window_x.or(defaults.window_x),
+        window_y: raw.general.window_y.or(defaults.window_y),
+        show_kps: raw.general.show_kps.unwrap_or(defaults.show_kps),
+        toggle_theme_key: raw
+            .general
+            .toggle_theme_key
+            .map(|key| canonical_key_name(&key))
+            .or(defaults.toggle_theme_key),
+        key_label_align: raw
+            .general
+            .key_label_align
+            .unwrap_or(defaults.key_label_align),
+        min_bar_lifetime_ms: raw
+            .general
+            .min_bar_lifetime_ms
+            .or(defaults.min_bar_lifetime_ms),
+        fallback_fonts: raw
+            .general
+            .fallback_fonts
+            .unwrap_or(defaults.fallback_fonts),
+        idle_hide_ms: raw.general.idle_hide_ms.unwrap_or(defaults.idle_hide_ms),
+        strict_key_names: raw
+            .general
+            .strict_key_names
+            .unwrap_or(defaults.strict_key_names),
+        bar_acceleration: raw
+            .general
+            .bar_acceleration
+            .unwrap_or(defaults.bar_acceleration),
+        recenter_on_reload: raw
+            .general
+            .recenter_on_reload
+            .unwrap_or(defaults.recenter_on_reload),
+        show_event_log: raw
+            .general
+            .show_event_log
+            .unwrap_or(defaults.show_event_log),
+        event_log_size: raw
+            .general
+            .event_log_size
+            .unwrap_or(defaults.event_log_size),
+        session_goal: raw.general.session_goal.or(defaults.session_goal),
+        session_goal_position: raw
+            .general
+            .session_goal_position
+            .unwrap_or(defaults.session_goal_position),
+        bar_width_ratio: raw
+            .general
+            .bar_width_ratio
+            .unwrap_or(defaults.bar_width_ratio),
+        move_key_left_key: raw
+            .general
+            .move_key_left_key
+            .map(|key| canonical_key_name(&key))
+            .or(defaults.move_key_left_key),
+        move_key_right_key: raw
+            .general
+            .move_key_right_key
+            .map(|key| canonical_key_name(&key))
+            .or(defaults.move_key_right_key),
+        auto_save_tweaks: raw
+            .general
+            .auto_save_tweaks
+            .unwrap_or(defaults.auto_save_tweaks),
+        counter_format: raw.general.counter_format.or(defaults.counter_format),
+        font_path: raw.general.font_path.or(defaults.font_path),
+        corner_radius: raw.general.corner_radius.unwrap_or(defaults.corner_radius),
+        monitor_index: raw.general.monitor_index.or(defaults.monitor_index),
+        transparent_background: raw
+            .general
+            .transparent_background
+            .unwrap_or(defaults.transparent_background),
+        reset_key: raw
+            .general
+            .reset_key
+            .map(|key| canonical_key_name(&key))
+            .or(defaults.reset_key),
+        reset_clears_bars: raw
+            .general
+            .reset_clears_bars
+            .unwrap_or(defaults.reset_clears_bars),
+        dt_smoothing: raw.general.dt_smoothing.unwrap_or(defaults.dt_smoothing),
+        key_label_scale: raw
+            .general
+            .key_label_scale
+            .unwrap_or(defaults.key_label_scale),
+        counter_scale: raw.general.counter_scale.unwrap_or(defaults.counter_scale),
+        key_axis: raw.general.key_axis.unwrap_or(defaults.key_axis),
+        replay_file: raw.general.replay_file.or(defaults.replay_file),
+        idle_fps: raw.general.idle_fps.or(defaults.idle_fps),
+        toggle_passthrough_key: raw
+            .general
+            .toggle_passthrough_key
+            .map(|key| canonical_key_name(&key))
+            .or(defaults.toggle_passthrough_key),
+        release_decay_ms: raw
+            .general
+            .release_decay_ms
+            .unwrap_or(defaults.release_decay_ms),
+        show_anchor_line: raw
+            .general
+            .show_anchor_line
+            .unwrap_or(defaults.show_anchor_line),
+        anchor_line_color: match raw.general.anchor_line_color {
+            Some(value) => parse_app_color(&value, "anchorLineColor")?,
+            None => defaults.anchor_line_color,
+        },
+        show_help_on_startup: raw
+            .general
+            .show_help_on_startup
+            .unwrap_or(defaults.show_help_on_startup),
+        help_duration_ms: raw
+            .general
+            .help_duration_ms
+            .unwrap_or(defaults.help_duration_ms),
+        merge_bars: raw.general.merge_bars.unwrap_or(defaults.merge_bars),
+        min_bar_height: raw
+            .general
+            .min_bar_height
+            .unwrap_or(defaults.min_bar_height),
+        max_window_width: raw.general.max_window_width.or(defaults.max_window_width),
+        max_window_height: raw.general.max_window_height.or(defaults.max_window_height),
+        color_transition_ms: raw
+            .general
+            .color_transition_ms
+            .unwrap_or(defaults.color_transition_ms),
+        toggle_counter_key: raw
+            .general
+            .toggle_counter_key
+            .map(|key| canonical_key_name(&key))
+            .or(defaults.toggle_counter_key),
+        stats_export_path: raw.general.stats_export_path.or(defaults.stats_export_path),
+        edge_padding: raw.general.edge_padding.or(defaults.edge_padding),
+        key_spacing: raw.general.key_spacing.or(defaults.key_spacing),
+        pause_when_unfocused: raw
+            .general
+            .pause_when_unfocused
+            .unwrap_or(defaults.pause_when_unfocused),
+        bar_outline_color: match raw.general.bar_outline_color {
+            Some(value) => parse_app_color(&value, "barOutlineColor")?,
+            None => defaults.bar_outline_color,
+        },
+        velocity_coloring: raw
+            .general
+            .velocity_coloring
+            .unwrap_or(defaults.velocity_coloring),
+        reverse_key_order: raw
+            .general
+            .reverse_key_order
+            .unwrap_or(defaults.reverse_key_order),
+        bar_cap_fade_px: raw
+            .general
+            .bar_cap_fade_px
+            .unwrap_or(defaults.bar_cap_fade_px),
+        ui_scale: raw.general.ui_scale.unwrap_or(defaults.ui_scale),
+        bar_gap_px: raw.general.bar_gap_px.unwrap_or(defaults.bar_gap_px),
+        ghost_trail_ms: raw
+            .general
+            .ghost_trail_ms
+            .unwrap_or(defaults.ghost_trail_ms),
+        show_apm: raw.general.show_apm.unwrap_or(defaults.show_apm),
         keys: if raw.key.is_empty() {
             defaults.keys
         } else {
             parse_raw_keys(raw.key)?
         },
+        themes: parse_raw_themes(raw.theme)?,
+        combos: parse_raw_combos(raw.combo)?,
+        groups: parse_raw_groups(raw.group)?,
     };
 
-    for warning in validate_config(&config) {
-        if warning.contains("bar_speed") {
-            config.bar_speed = defaults.bar_speed;
+    sanitize_config_with_warnings(config)
+}
+
+/// Checks a config built in code the same way as one loaded from a file:
+/// unknown key names are rejected under `strictKeyNames`, and invalid values
+/// are fixed with a logged warning.
+pub fn sanitize_config(config: AppConfig) -> Result<AppConfig, AppError> {
+    let (config, warnings) = sanitize_config_with_warnings(config)?;
+    for warning in &warnings {
+        warn!("config: {warning}");
+    }
+    Ok(config)
+}
+
+/// Validates `config`, fixing up invalid values and returning the warnings
+/// that describe each fix.
+fn sanitize_config_with_warnings(
+    mut config: AppConfig,
+) -> Result<(AppConfig, Vec<String>), AppError> {
+    if config.strict_key_names {
+        let unknown = unrecognized_key_names(&config);
+        if !unknown.is_empty() {
+            return Err(AppError::Config(format!(
+                "unrecognized key names: {}",
+                unknown.join(", ")
+            )));
         }
     }
 
-    Ok(config)
+    let issues = config_issues(&config);
+    for fix in issues.iter().filter_map(|issue| issue.fix) {
+        fix.apply(&mut config);
+    }
+
+    Ok((
+        config,
+        issues.into_iter().map(|issue| issue.message).collect(),
+    ))
+}
+
+/// An invalid config value, and how `sanitize_config` fixes it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ConfigFix {
+    BarSpeed,
+    KeyBarSpeeds,
+    Height,
+    Margin,
+    UiScale,
+    Fps,
+    IdleFps,
+    EdgePadding,
+    KeySpacing,
+    OutlineThickness,
+    KeySize,
+    BarWidthRatio,
+    KeyLabelScale,
+    CounterScale,
+    DtSmoothing,
+    CounterFormat,
+    MaxWindowWidth,
+    MaxWindowHeight,
+}
+
+impl ConfigFix {
+    fn apply(self, config: &mut AppConfig) {
+        let defaults = AppConfig::default();
+        match self {
+            Self::BarSpeed => config.bar_speed = defaults.bar_speed,
+            Self::KeyBarSpeeds => {
+                for key in &mut config.keys {
+                    key.bar_speed = key.bar_speed.filter(|speed| *speed > 0.0);
+                }
+            }
+            Self::Height => config.height = defaults.height,
+            Self::Margin => config.margin = defaults.margin,
+            Self::UiScale => config.ui_scale = defaults.ui_scale,
+            Self::Fps => config.fps = config.fps.clamp(1, MAX_FPS),
+            Self::IdleFps => config.idle_fps = config.idle_fps.map(|fps| fps.clamp(1, MAX_FPS)),
+            Self::EdgePadding => config.edge_padding = None,
+            Self::KeySpacing => config.key_spacing = None,
+            Self::OutlineThickness => config.outline_thickness = defaults.outline_thickness,
+            Self::KeySize => config.key_size = MIN_KEY_SIZE,
+            Self::BarWidthRatio => config.bar_width_ratio = config.bar_width_ratio.clamp(0.0, 1.0),
+            Self::KeyLabelScale => {
+                config.key_label_scale = clamp_font_scale(config.key_label_scale)
+            }
+            Self::CounterScale => config.counter_scale = clamp_font_scale(config.counter_scale),
+            Self::DtSmoothing => config.dt_smoothing = config.dt_smoothing.clamp(0.0, 1.0),
+            Self::CounterFormat => config.counter_format = None,
+            Self::MaxWindowWidth => config.max_window_width = None,
+            Self::MaxWindowHeight => config.max_window_height = None,
+        }
+    }
+}
+
+/// A validation warning, with the fix `sanitize_config` applies for it, if any.
+#[derive(Debug)]
+struct ConfigIssue {
+    fix: Option<ConfigFix>,
+    message: String,
+}
+
+impl ConfigIssue {
+    /// A warning about a value that `fix` repairs.
+    fn fixed(fix: ConfigFix, message: impl Into<String>) -> Self {
+        Self {
+            fix: Some(fix),
+            message: message.into(),
+        }
+    }
+
+    /// A warning that leaves the config as is.
+    fn note(message: impl Into<String>) -> Self {
+        Self {
+            fix: None,
+            message: message.into(),
+        }
+    }
+}
+
+/// Clamps a font scale into `FONT_SCALE_RANGE`, mapping NaN to the lower bound.
+fn clamp_font_scale(scale: f32) -> f32 {
+    if scale.is_nan() {
+        return *FONT_SCALE_RANGE.start();
+    }
+    scale.clamp(*FONT_SCALE_RANGE.start(), *FONT_SCALE_RANGE.end())
 }
 
 /// Validates an already-resolved app config and returns non-fatal warnings.
 pub fn validate_config(config: &AppConfig) -> Vec<String> {
-    let mut warnings = Vec::new();
+    config_issues(config)
+        .into_iter()
+        .map(|issue| issue.message)
+        .collect()
+}
+
+fn config_issues(config: &AppConfig) -> Vec<ConfigIssue> {
+    let mut issues = Vec::new();
 
     if config.bar_speed <= 0.0 {
-        warnings.push("bar_speed must be positive; using default 600".to_string());
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::BarSpeed,
+            "bar_speed must be positive; using default 600",
+        ));
+    }
+
+    let has_custom_font = config.font_path.is_some() || !config.fallback_fonts.is_empty();
+    for key in &config.keys {
+        if !has_custom_font && !key.display_name.is_ascii() {
+            issues.push(ConfigIssue::note(format!(
+                "display name '{}' of key '{}' has non-ASCII characters the bundled font may lack; set fontPath or fallbackFonts if it renders as boxes",
+                key.display_name, key.key_name
+            )));
+        }
+        if key
+            .bar_speed
+            .is_some_and(|speed| speed <= 0.0 || speed.is_nan())
+        {
+            issues.push(ConfigIssue::fixed(
+                ConfigFix::KeyBarSpeeds,
+                format!(
+                    "bar speed override for key '{}' must be positive; using the global barSpeed",
+                    key.key_name
+                ),
+            ));
+        }
+    }
+
+    let defaults = AppConfig::default();
+    if config.height <= 0.0 || config.height.is_nan() {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::Height,
+            format!("height must be positive; using default {}", defaults.height),
+        ));
+    }
+
+    if config.margin < 0.0 || config.margin.is_nan() {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::Margin,
+            format!(
+                "margin must not be negative; using default {}",
+                defaults.margin
+            ),
+        ));
+    }
+
+    if config.ui_scale <= 0.0 || !config.ui_scale.is_finite() {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::UiScale,
+            format!(
+                "uiScale must be positive; using default {}",
+                defaults.ui_scale
+            ),
+        ));
+    }
+
+    for (fix, name, value) in [
+        (ConfigFix::Fps, "fps", Some(config.fps)),
+        (ConfigFix::IdleFps, "idleFps", config.idle_fps),
+    ] {
+        match value {
+            Some(0) => issues.push(ConfigIssue::fixed(
+                fix,
+                format!("{name} must be at least 1; clamping to 1"),
+            )),
+            Some(fps) if fps > MAX_FPS => issues.push(ConfigIssue::fixed(
+                fix,
+                format!("{name} {fps} exceeds the maximum {MAX_FPS}; clamping to {MAX_FPS}"),
+            )),
+            _ => {}
+        }
+    }
+
+    for (fix, name, value) in [
+        (ConfigFix::EdgePadding, "edgePadding", config.edge_padding),
+        (ConfigFix::KeySpacing, "keySpacing", config.key_spacing),
+    ] {
+        if value.is_some_and(|value| value < 0.0 || value.is_nan()) {
+            issues.push(ConfigIssue::fixed(
+                fix,
+                format!("{name} must not be negative; using margin"),
+            ));
+        }
+    }
+
+    if config.outline_thickness < 0.0 || config.outline_thickness.is_nan() {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::OutlineThickness,
+            format!(
+                "outline_thickness must not be negative; using default {}",
+                defaults.outline_thickness
+            ),
+        ));
+    }
+
+    if config.key_size < MIN_KEY_SIZE || config.key_size.is_nan() {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::KeySize,
+            format!("key_size must be at least {MIN_KEY_SIZE}; clamping to {MIN_KEY_SIZE}"),
+        ));
+    }
+
+    if !(0.0..=1.0).contains(&config.bar_width_ratio) {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::BarWidthRatio,
+            "bar_width_ratio must be between 0.0 and 1.0; clamping",
+        ));
+    }
+
+    for (fix, name, scale) in [
+        (
+            ConfigFix::KeyLabelScale,
+            "key_label_scale",
+            config.key_label_scale,
+        ),
+        (
+            ConfigFix::CounterScale,
+            "counter_scale",
+            config.counter_scale,
+        ),
+    ] {
+        if !FONT_SCALE_RANGE.contains(&scale) {
+            issues.push(ConfigIssue::fixed(
+                fix,
+                format!(
+                    "{name} must be between {} and {}; clamping",
+                    FONT_SCALE_RANGE.start(),
+                    FONT_SCALE_RANGE.end()
+                ),
+            ));
+        }
+    }
+
+    if !(0.0..=1.0).contains(&config.dt_smoothing) {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::DtSmoothing,
+            "dt_smoothing must be between 0.0 and 1.0; clamping",
+        ));
+    }
+
+    if let Some(format) = &config.counter_format
+        && format.matches("{}").count() != 1
+    {
+        issues.push(ConfigIssue::fixed(
+            ConfigFix::CounterFormat,
+            format!(
+                "counter_format '{format}' must contain exactly one {{}} placeholder; showing the bare count"
+            ),
+        ));
     }
 
     if config.keys.is_empty() {
-        warnings.push("keys list is empty; using defaults is recommended".to_string());
+        issues.push(ConfigIssue::note(
+            "keys list is empty; using defaults is recommended",
+        ));
+    }
+
+    let unknown = unrecognized_key_names(config);
+    if !unknown.is_empty() {
+        issues.push(ConfigIssue::note(format!(
+            "unrecognized key names will never receive input: {}",
+            unknown.join(", ")
+        )));
+    }
+
+    for (control, hotkey) in config.control_hotkeys() {
+        if config.keys.iter().any(|key| key.key_name == hotkey) {
+            issues.push(ConfigIssue::note(format!(
+                "key '{hotkey}' is also the {control} hotkey; pressing it shows a bar and triggers the action"
+            )));
+        }
+    }
+
+    let [width, height] = calculate_window_size(config);
+    for (fix, name, limit, size) in [
+        (
+            ConfigFix::MaxWindowWidth,
+            "maxWindowWidth",
+            config.max_window_width,
+            width,
+        ),
+        (
+            ConfigFix::MaxWindowHeight,
+            "maxWindowHeight",
+            config.max_window_height,
+            height,
+        ),
+    ] {
+        match limit {
+            Some(limit) if limit <= 0.0 || limit.is_nan() => {
+                issues.push(ConfigIssue::fixed(
+                    fix,
+                    format!("{name} must be positive; ignoring it"),
+                ));
+            }
+            Some(limit) if size > limit => {
+                issues.push(ConfigIssue::note(format!(
+                    "window size {size} exceeds {name} {limit}; clipping the overlay"
+                )));
+            }
+            _ => {}
+        }
     }
 
-    warnings
+    issues
+}
+
+/// Returns the canonical `KeyId` spelling of `name`, or the trimmed name if unrecognized.
+///
+/// Incoming events are labeled with `KeyId`'s display form, so `"lcontrol"` in a
+/// config must become `"LControl"` to match.
+pub fn canonical_key_name(name: &str) -> String {
+    name.parse::<KeyId>()
+        .map(|key| key.to_string())
+        .unwrap_or_else(|_| name.trim().to_string())
+}
+
+fn unrecognized_key_names(config: &AppConfig) -> Vec<&str> {
+    config
+        .keys
+        .iter()
+        .flat_map(|key| std::iter::once(&key.key_name).chain(&key.aliases))
+        .filter(|name| name.parse::<KeyId>().is_err())
+        .map(String::as_str)
+        .collect()
 }
 
 fn parse_raw_keys(raw_keys: Vec<RawKeyConfig>) -> Result<Vec<KeyConfig>, AppError> {
@@ -109,6 +847,7 @@ fn parse_raw_keys(raw_keys: Vec<RawKeyConfig>) -> Result<Vec<KeyConfig>, AppErro
             .name
             .map(|value| value.trim().to_string())
             .filter(|value| !value.is_empty())
+            .map(|value| canonical_key_name(&value))
             .ok_or_else(|| AppError::Config("key entry missing required name".to_string()))?;
 
         let color = match raw_key.color {
@@ -116,25 +855,145 @@ fn parse_raw_keys(raw_keys: Vec<RawKeyConfig>) -> Result<Vec<KeyConfig>, AppErro
             None => Color::from_rgba_u8(255, 255, 255, 255),
         };
 
+        let pressed_color = match raw_key.pressed_color {
+            Some(value) => Some(parse_app_color(&value, "key pressedColor")?),
+            None => None,
+        };
+
+        let trail_color = match raw_key.trail_color {
+            Some(value) => Some(parse_app_color(&value, "key trailColor")?),
+            None => None,
+        };
+
+        let display_name = raw_key
+            .display_name
+            .filter(|value| !value.trim().is_empty())
+            .unwrap_or_else(|| key_name.clone());
+
         parsed_keys.push(KeyConfig {
-            key_name: key_name.clone(),
-            display_name: key_name,
+            key_name,
+            display_name,
             color,
             size: raw_key.size.unwrap_or(1.0),
+            spacer_before: raw_key.spacer_before.unwrap_or(0.0).max(0.0),
+            pressed_color,
+            fading: raw_key.fading,
+            trail_color,
+            bar_speed: raw_key.bar_speed,
+            icon_path: raw_key.icon_path,
+            initial_count: raw_key.initial_count,
+            aliases: raw_key
+                .aliases
+                .iter()
+                .map(|alias| alias.trim())
+                .filter(|alias| !alias.is_empty())
+                .map(canonical_key_name)
+                .collect(),
         });
     }
 
     Ok(parsed_keys)
 }
 
+fn parse_raw_themes(raw_themes: Vec<RawTheme>) -> Result<Vec<Theme>, AppError> {
+    let mut themes = Vec::with_capacity(raw_themes.len());
+
+    for raw_theme in raw_themes {
+        let name = raw_theme
+            .name
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| AppError::Config("theme entry missing required name".to_string()))?;
+
+        let background_color = match raw_theme.background_color {
+            Some(value) => Some(parse_app_color(&value, "theme backgroundColor")?),
+            None => None,
+        };
+
+        let mut key_colors = Vec::with_capacity(raw_theme.key_colors.len());
+        for (key_name, value) in raw_theme.key_colors {
+            key_colors.push((
+                canonical_key_name(&key_name),
+                parse_app_color(&value, "theme key color")?,
+            ));
+        }
+
+        themes.push(Theme {
+            name,
+            background_color,
+            key_colors,
+        });
+    }
+
+    Ok(themes)
+}
+
+fn parse_raw_combos(raw_combos: Vec<RawCombo>) -> Result<Vec<Combo>, AppError> {
+    raw_combos
+        .into_iter()
+        .map(|raw_combo| {
+            if raw_combo.keys.len() < 2 {
+                return Err(AppError::Config(
+                    "combo entry needs at least two keys".to_string(),
+                ));
+            }
+
+            let color = match raw_combo.color {
+                Some(value) => parse_app_color(&value, "combo color")?,
+                None => Color::from_rgba_u8(255, 255, 255, 255),
+            };
+
+            Ok(Combo {
+                keys: raw_combo
+                    .keys
+                    .iter()
+                    .map(|key_name| canonical_key_name(key_name))
+                    .collect(),
+                color,
+            })
+        })
+        .collect()
+}
+
+fn parse_raw_groups(raw_groups: Vec<RawGroup>) -> Result<Vec<KeyGroup>, AppError> {
+    raw_groups
+        .into_iter()
+        .map(|raw_group| {
+            let label = raw_group.label.ok_or_else(|| {
+                AppError::Config("group entry missing required label".to_string())
+            })?;
+            if raw_group.keys.is_empty() {
+                return Err(AppError::Config(format!(
+                    "group '{label}' needs at least one key"
+                )));
+            }
+
+            let color = match raw_group.color {
+                Some(value) => parse_app_color(&value, "group color")?,
+                None => Color::from_rgba_u8(255, 255, 255, 255),
+            };
+
+            Ok(KeyGroup {
+                label,
+                color,
+                keys: raw_group
+                    .keys
+                    .iter()
+                    .map(|key_name| canonical_key_name(key_name))
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
 fn parse_app_color(raw: &str, field_name: &str) -> Result<Color, AppError> {
-    let parsed =
-        parse_color(raw).map_err(|err| AppError::Config(format!("invalid {field_name}: {err}")))?;
-    Ok(Color::from_rgba_u8(parsed.r, parsed.g, parsed.b, parsed.a))
+    parse_color_normalized(raw)
+        .map_err(|err| AppError::Config(format!("invalid {field_name}: {err}")))
 }
 
 /// Ensures config exists at the given path.
-/// If the file doesn't exist, creates it with default config.
+/// If the file doesn't exist, creates it with default config in the format
+/// matching the path's extension.
 /// If it exists, loads it.
 /// Returns the loaded or default config.
 pub fn ensure_config_exists(path: &Path) -> Result<AppConfig, AppError> {
@@ -149,34 +1008,67 @@ pub fn ensure_config_exists(path: &Path) -> Result<AppConfig, AppError> {
             fs::create_dir_all(parent)?;
         }
 
-        // Serialize default config to TOML string
-        let default_config = AppConfig::default();
-        let toml_string = serialize_config(&default_config)?;
-
-        // Write to file
-        fs::write(path, toml_string)?;
-
-        // Load and return
+        // Write default config in the requested format, then load it back
+        save_config(path, &AppConfig::default())?;
         load_config(path)
     }
 }
 
+/// Writes `config` to `path` in the format matching the path's extension.
+///
+/// The file is regenerated from scratch, so comments and formatting are not kept.
+pub fn save_config(path: &Path, config: &AppConfig) -> Result<(), AppError> {
+    let contents = match ConfigFormat::from_path(path) {
+        ConfigFormat::Toml => serialize_config(config)?,
+        ConfigFormat::Json => serialize_config_json(config)?,
+        ConfigFormat::Ron => serialize_config_ron(config)?,
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
+
 /// Serializes AppConfig to TOML string using pretty formatting.
-fn serialize_config(config: &AppConfig) -> Result<String, AppError> {
+///
+/// This is the format [`save_config`] writes for `.toml` paths.
+pub fn serialize_config(config: &AppConfig) -> Result<String, AppError> {
     let raw = RawConfigBuilder::from_app_config(config);
     toml::to_string_pretty(&raw)
         .map_err(|err| AppError::Config(format!("failed to serialize config: {err}")))
 }
 
+/// Serializes AppConfig to pretty-printed JSON.
+fn serialize_config_json(config: &AppConfig) -> Result<String, AppError> {
+    let raw = RawConfigBuilder::from_app_config(config);
+    serde_json::to_string_pretty(&raw)
+        .map_err(|err| AppError::Config(format!("failed to serialize config: {err}")))
+}
+
+/// Serializes AppConfig to pretty-printed RON.
+fn serialize_config_ron(config: &AppConfig) -> Result<String, AppError> {
+    let raw = RawConfigBuilder::from_app_config(config);
+    let pretty =
+        ron::ser::PrettyConfig::new().extensions(ron::extensions::Extensions::IMPLICIT_SOME);
+    ron::ser::to_string_pretty(&raw, pretty)
+        .map_err(|err| AppError::Config(format!("failed to serialize config: {err}")))
+}
+
 /// Helper struct to build raw config from AppConfig for serialization.
 #[derive(serde::Serialize)]
 struct RawConfigBuilder {
     general: RawGeneralForSerialize,
     key: Vec<RawKeyConfigForSerialize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    theme: Vec<RawThemeForSerialize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    combo: Vec<RawComboForSerialize>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    group: Vec<RawGroupForSerialize>,
 }
 
 #[derive(serde::Serialize)]
 struct RawGeneralForSerialize {
+    #[serde(rename = "version")]
+    version: u32,
     #[serde(rename = "height")]
     height: f32,
     #[serde(rename = "keySize")]
@@ -189,6 +1081,10 @@ struct RawGeneralForSerialize {
     margin: f32,
     #[serde(rename = "outlineThickness")]
     outline_thickness: f32,
+    #[serde(rename = "barPattern")]
+    bar_pattern: BarPattern,
+    #[serde(rename = "maxBarsPerColumn", skip_serializing_if = "Option::is_none")]
+    max_bars_per_column: Option<usize>,
     #[serde(rename = "fading")]
     fading: bool,
     #[serde(rename = "counter")]
@@ -197,66 +1093,330 @@ struct RawGeneralForSerialize {
     fps: u32,
     #[serde(rename = "logToFile")]
     log_to_file: bool,
+    #[serde(rename = "inputBackend")]
+    input_backend: BackendKind,
+    #[serde(rename = "showPeakKps")]
+    show_peak_kps: bool,
+    #[serde(rename = "windowX", skip_serializing_if = "Option::is_none")]
+    window_x: Option<i32>,
+    #[serde(rename = "windowY", skip_serializing_if = "Option::is_none")]
+    window_y: Option<i32>,
+    #[serde(rename = "showKps")]
+    show_kps: bool,
+    #[serde(rename = "toggleThemeKey", skip_serializing_if = "Option::is_none")]
+    toggle_theme_key: Option<String>,
+    #[serde(rename = "keyLabelAlign")]
+    key_label_align: KeyLabelAlign,
+    #[serde(rename = "minBarLifetimeMs", skip_serializing_if = "Option::is_none")]
+    min_bar_lifetime_ms: Option<u64>,
+    #[serde(rename = "fallbackFonts", skip_serializing_if = "Vec::is_empty")]
+    fallback_fonts: Vec<PathBuf>,
+    #[serde(rename = "idleHideMs")]
+    idle_hide_ms: u64,
+    #[serde(rename = "strictKeyNames")]
+    strict_key_names: bool,
+    #[serde(rename = "barAcceleration")]
+    bar_acceleration: f32,
+    #[serde(rename = "recenterOnReload")]
+    recenter_on_reload: bool,
+    #[serde(rename = "showEventLog")]
+    show_event_log: bool,
+    #[serde(rename = "eventLogSize")]
+    event_log_size: usize,
+    #[serde(rename = "sessionGoal", skip_serializing_if = "Option::is_none")]
+    session_goal: Option<u64>,
+    #[serde(rename = "sessionGoalPosition")]
+    session_goal_position: GoalBarPosition,
+    #[serde(rename = "barWidthRatio")]
+    bar_width_ratio: f32,
+    #[serde(rename = "moveKeyLeftKey", skip_serializing_if = "Option::is_none")]
+    move_key_left_key: Option<String>,
+    #[serde(rename = "moveKeyRightKey", skip_serializing_if = "Option::is_none")]
+    move_key_right_key: Option<String>,
+    #[serde(rename = "autoSaveTweaks")]
+    auto_save_tweaks: bool,
+    #[serde(rename = "counterFormat", skip_serializing_if = "Option::is_none")]
+    counter_format: Option<String>,
+    #[serde(rename = "fontPath", skip_serializing_if = "Option::is_none")]
+    font_path: Option<PathBuf>,
+    #[serde(rename = "cornerRadius")]
+    corner_radius: f32,
+    #[serde(rename = "monitorIndex", skip_serializing_if = "Option::is_none")]
+    monitor_index: Option<usize>,
+    #[serde(rename = "transparentBackground")]
+    transparent_background: bool,
+    #[serde(rename = "resetKey", skip_serializing_if = "Option::is_none")]
+    reset_key: Option<String>,
+    #[serde(rename = "resetClearsBars")]
+    reset_clears_bars: bool,
+    #[serde(rename = "dtSmoothing")]
+    dt_smoothing: f32,
+    #[serde(rename = "keyLabelScale")]
+    key_label_scale: f32,
+    #[serde(rename = "counterScale")]
+    counter_scale: f32,
+    #[serde(rename = "keyAxis")]
+    key_axis: KeyAxis,
+    #[serde(rename = "replayFile", skip_serializing_if = "Option::is_none")]
+    replay_file: Option<PathBuf>,
+    #[serde(rename = "idleFps", skip_serializing_if = "Option::is_none")]
+    idle_fps: Option<u32>,
+    #[serde(
+        rename = "togglePassthroughKey",
+        skip_serializing_if = "Option::is_none"
+    )]
+    toggle_passthrough_key: Option<String>,
+    #[serde(rename = "releaseDecayMs")]
+    release_decay_ms: u64,
+    #[serde(rename = "showAnchorLine")]
+    show_anchor_line: bool,
+    #[serde(rename = "anchorLineColor")]
+    anchor_line_color: String,
+    #[serde(rename = "showHelpOnStartup")]
+    show_help_on_startup: bool,
+    #[serde(rename = "helpDurationMs")]
+    help_duration_ms: u64,
+    #[serde(rename = "mergeBars")]
+    merge_bars: bool,
+    #[serde(rename = "minBarHeight")]
+    min_bar_height: f32,
+    #[serde(rename = "maxWindowWidth", skip_serializing_if = "Option::is_none")]
+    max_window_width: Option<f32>,
+    #[serde(rename = "maxWindowHeight", skip_serializing_if = "Option::is_none")]
+    max_window_height: Option<f32>,
+    #[serde(rename = "colorTransitionMs")]
+    color_transition_ms: u64,
+    #[serde(rename = "toggleCounterKey", skip_serializing_if = "Option::is_none")]
+    toggle_counter_key: Option<String>,
+    #[serde(rename = "statsExportPath", skip_serializing_if = "Option::is_none")]
+    stats_export_path: Option<PathBuf>,
+    #[serde(rename = "edgePadding", skip_serializing_if = "Option::is_none")]
+    edge_padding: Option<f32>,
+    #[serde(rename = "keySpacing", skip_serializing_if = "Option::is_none")]
+    key_spacing: Option<f32>,
+    #[serde(rename = "pauseWhenUnfocused")]
+    pause_when_unfocused: bool,
+    #[serde(rename = "barOutlineColor")]
+    bar_outline_color: String,
+    #[serde(rename = "velocityColoring")]
+    velocity_coloring: bool,
+    #[serde(rename = "reverseKeyOrder")]
+    reverse_key_order: bool,
+    #[serde(rename = "barCapFadePx")]
+    bar_cap_fade_px: f32,
+    #[serde(rename = "uiScale")]
+    ui_scale: f32,
+    #[serde(rename = "barGapPx")]
+    bar_gap_px: f32,
+    #[serde(rename = "ghostTrailMs")]
+    ghost_trail_ms: u64,
+    #[serde(rename = "showApm")]
+    show_apm: bool,
 }
 
 #[derive(serde::Serialize)]
 struct RawKeyConfigForSerialize {
     #[serde(rename = "name")]
     name: String,
+    #[serde(rename = "displayName", skip_serializing_if = "Option::is_none")]
+    display_name: Option<String>,
     #[serde(rename = "color")]
     color: String,
     #[serde(rename = "size")]
     size: f32,
+    #[serde(rename = "spacerBefore", skip_serializing_if = "is_zero")]
+    spacer_before: f32,
+    #[serde(rename = "pressedColor", skip_serializing_if = "Option::is_none")]
+    pressed_color: Option<String>,
+    #[serde(rename = "fading", skip_serializing_if = "Option::is_none")]
+    fading: Option<bool>,
+    #[serde(rename = "trailColor", skip_serializing_if = "Option::is_none")]
+    trail_color: Option<String>,
+    #[serde(rename = "barSpeed", skip_serializing_if = "Option::is_none")]
+    bar_speed: Option<f32>,
+    #[serde(rename = "iconPath", skip_serializing_if = "Option::is_none")]
+    icon_path: Option<PathBuf>,
+    #[serde(rename = "initialCount", skip_serializing_if = "Option::is_none")]
+    initial_count: Option<u64>,
+    #[serde(rename = "aliases", skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+}
+
+fn is_zero(value: &f32) -> bool {
+    *value == 0.0
+}
+
+#[derive(serde::Serialize)]
+struct RawThemeForSerialize {
+    #[serde(rename = "name")]
+    name: String,
+    #[serde(rename = "backgroundColor", skip_serializing_if = "Option::is_none")]
+    background_color: Option<String>,
+    #[serde(rename = "keyColors", skip_serializing_if = "BTreeMap::is_empty")]
+    key_colors: BTreeMap<String, String>,
+}
+
+#[derive(serde::Serialize)]
+struct RawComboForSerialize {
+    #[serde(rename = "keys")]
+    keys: Vec<String>,
+    #[serde(rename = "color")]
+    color: String,
+}
+
+#[derive(serde::Serialize)]
+struct RawGroupForSerialize {
+    #[serde(rename = "label")]
+    label: String,
+    #[serde(rename = "color")]
+    color: String,
+    #[serde(rename = "keys")]
+    keys: Vec<String>,
 }
 
 impl RawConfigBuilder {
     fn from_app_config(config: &AppConfig) -> Self {
-        let background_color_str = format!(
-            "{},{},{},{}",
-            (config.background_color.r * 255.0).round() as u8,
-            (config.background_color.g * 255.0).round() as u8,
-            (config.background_color.b * 255.0).round() as u8,
-            (config.background_color.a * 255.0).round() as u8,
-        );
+        let background_color_str = config.background_color.to_config_string();
 
         let key_configs = config
             .keys
             .iter()
             .map(|k| RawKeyConfigForSerialize {
                 name: k.key_name.clone(),
-                color: format!(
-                    "{},{},{},{}",
-                    (k.color.r * 255.0).round() as u8,
-                    (k.color.g * 255.0).round() as u8,
-                    (k.color.b * 255.0).round() as u8,
-                    (k.color.a * 255.0).round() as u8,
-                ),
+                display_name: Some(k.display_name.clone()).filter(|name| *name != k.key_name),
+                color: k.color.to_config_string(),
                 size: k.size,
+                spacer_before: k.spacer_before,
+                pressed_color: k.pressed_color.as_ref().map(Color::to_config_string),
+                fading: k.fading,
+                trail_color: k.trail_color.as_ref().map(Color::to_config_string),
+                bar_speed: k.bar_speed,
+                icon_path: k.icon_path.clone(),
+                initial_count: k.initial_count,
+                aliases: k.aliases.clone(),
             })
             .collect();
 
         RawConfigBuilder {
             general: RawGeneralForSerialize {
+                version: CONFIG_VERSION,
                 height: config.height,
                 key_size: config.key_size,
                 bar_speed: config.bar_speed,
                 background_color: background_color_str,
                 margin: config.margin,
                 outline_thickness: config.outline_thickness,
+                bar_pattern: config.bar_pattern,
+                max_bars_per_column: config.max_bars_per_column,
                 fading: config.fading,
                 counter: config.counter,
                 fps: config.fps,
                 log_to_file: config.log_to_file,
+                input_backend: config.input_backend,
+                show_peak_kps: config.show_peak_kps,
+                window_x: config.window_x,
+                window_y: config.window_y,
+                show_kps: config.show_kps,
+                toggle_theme_key: config.toggle_theme_key.clone(),
+                key_label_align: config.key_label_align,
+                min_bar_lifetime_ms: config.min_bar_lifetime_ms,
+                fallback_fonts: config.fallback_fonts.clone(),
+                idle_hide_ms: config.idle_hide_ms,
+                strict_key_names: config.strict_key_names,
+                bar_acceleration: config.bar_acceleration,
+                recenter_on_reload: config.recenter_on_reload,
+                show_event_log: config.show_event_log,
+                event_log_size: config.event_log_size,
+                session_goal: config.session_goal,
+                session_goal_position: config.session_goal_position,
+                bar_width_ratio: config.bar_width_ratio,
+                move_key_left_key: config.move_key_left_key.clone(),
+                move_key_right_key: config.move_key_right_key.clone(),
+                auto_save_tweaks: config.auto_save_tweaks,
+                counter_format: config.counter_format.clone(),
+                font_path: config.font_path.clone(),
+                corner_radius: config.corner_radius,
+                monitor_index: config.monitor_index,
+                transparent_background: config.transparent_background,
+                reset_key: config.reset_key.clone(),
+                reset_clears_bars: config.reset_clears_bars,
+                dt_smoothing: config.dt_smoothing,
+                key_label_scale: config.key_label_scale,
+                counter_scale: config.counter_scale,
+                key_axis: config.key_axis,
+                replay_file: config.replay_file.clone(),
+                idle_fps: config.idle_fps,
+                toggle_passthrough_key: config.toggle_passthrough_key.clone(),
+                release_decay_ms: config.release_decay_ms,
+                show_anchor_line: config.show_anchor_line,
+                anchor_line_color: config.anchor_line_color.to_config_string(),
+                show_help_on_startup: config.show_help_on_startup,
+                help_duration_ms: config.help_duration_ms,
+                merge_bars: config.merge_bars,
+                min_bar_height: config.min_bar_height,
+                max_window_width: config.max_window_width,
+                max_window_height: config.max_window_height,
+                color_transition_ms: config.color_transition_ms,
+                toggle_counter_key: config.toggle_counter_key.clone(),
+                stats_export_path: config.stats_export_path.clone(),
+                edge_padding: config.edge_padding,
+                key_spacing: config.key_spacing,
+                pause_when_unfocused: config.pause_when_unfocused,
+                bar_outline_color: config.bar_outline_color.to_config_string(),
+                velocity_coloring: config.velocity_coloring,
+                reverse_key_order: config.reverse_key_order,
+                bar_cap_fade_px: config.bar_cap_fade_px,
+                ui_scale: config.ui_scale,
+                bar_gap_px: config.bar_gap_px,
+                ghost_trail_ms: config.ghost_trail_ms,
+                show_apm: config.show_apm,
             },
             key: key_configs,
+            theme: config
+                .themes
+                .iter()
+                .map(|theme| RawThemeForSerialize {
+                    name: theme.name.clone(),
+                    background_color: theme.background_color.as_ref().map(Color::to_config_string),
+                    key_colors: theme
+                        .key_colors
+                        .iter()
+                        .map(|(key_name, color)| (key_name.clone(), color.to_config_string()))
+                        .collect(),
+                })
+                .collect(),
+            combo: config
+                .combos
+                .iter()
+                .map(|combo| RawComboForSerialize {
+                    keys: combo.keys.clone(),
+                    color: combo.color.to_config_string(),
+                })
+                .collect(),
+            group: config
+                .groups
+                .iter()
+                .map(|group| RawGroupForSerialize {
+                    label: group.label.clone(),
+                    color: group.color.to_config_string(),
+                    keys: group.keys.clone(),
+                })
+                .collect(),
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{ensure_config_exists, load_from_str, validate_config};
-    use crate::types::{AppConfig, Color};
+    use std::path::Path;
+
+    use super::{
+        ConfigFormat, check_config, ensure_config_exists, load_config, load_from_json_str,
+        load_from_ron_str, load_from_str, parse_raw_config, resolve_raw_config_with_warnings,
+        sanitize_config_with_warnings, save_config, validate_config,
+    };
+    use crate::input::BackendKind;
+    use crate::types::{AppConfig, BarPattern, Color, GoalBarPosition, KeyLabelAlign};
 
     fn full_valid_toml() -> &'static str {
         r#"
@@ -284,6 +1444,29 @@ size = 1.0
 "#
     }
 
+    fn full_valid_json() -> &'static str {
+        r#"
+{
+  "general": {
+    "height": 700,
+    "keySize": 70,
+    "barSpeed": 600,
+    "backgroundColor": "0,0,0,255",
+    "margin": 25,
+    "outlineThickness": 5,
+    "fading": true,
+    "counter": true,
+    "fps": 60,
+    "logToFile": false
+  },
+  "key": [
+    { "name": "Z", "color": "255,0,0,255", "size": 1.0 },
+    { "name": "X", "color": "0,255,255,255", "size": 1.0 }
+  ]
+}
+"#
+    }
+
     #[test]
     fn test_config_load_from_str_full_valid_toml() {
         let parsed = load_from_str(full_valid_toml()).expect("full config should parse");
@@ -341,6 +1524,86 @@ size = 1.0
         assert_eq!(parsed.keys[1].display_name, "X");
     }
 
+    #[test]
+    fn test_config_load_from_str_without_version_migrates_to_current() {
+        let mut raw: super::RawConfig =
+            toml::from_str("[general]\nkeySize = 55\n").expect("raw config should parse");
+
+        let notes = super::migrate_raw_config(&mut raw);
+
+        assert_eq!(
+            notes,
+            vec!["migrated config from version 0 to 1".to_string()]
+        );
+        assert_eq!(raw.general.version, Some(super::CONFIG_VERSION));
+        let parsed = load_from_str("[general]\nkeySize = 55\n").expect("versionless config");
+        assert_eq!(parsed.key_size, 55.0);
+    }
+
+    #[test]
+    fn test_migrate_raw_config_current_version_is_noop() {
+        let mut raw: super::RawConfig = toml::from_str("[general]\nversion = 1\n").expect("raw");
+
+        assert!(super::migrate_raw_config(&mut raw).is_empty());
+    }
+
+    #[test]
+    fn test_json_schema_is_valid_json_and_lists_config_fields() {
+        let schema: serde_json::Value =
+            serde_json::from_str(&super::json_schema().expect("schema should serialize"))
+                .expect("schema should be valid JSON");
+
+        let general = &schema["$defs"]["RawGeneral"]["properties"];
+        for field in ["barSpeed", "keySize", "backgroundColor", "uiScale"] {
+            assert!(general.get(field).is_some(), "missing {field} in {general}");
+        }
+        assert!(schema["properties"]["key"].is_object());
+        assert!(schema["$defs"]["RawKeyConfig"]["properties"]["displayName"].is_object());
+        assert!(
+            schema["$defs"]["BarPattern"]
+                .to_string()
+                .contains("stripes"),
+            "enum values should use config spelling"
+        );
+    }
+
+    #[test]
+    fn test_serialize_config_includes_overridden_fields() {
+        let config = AppConfig {
+            height: 512.0,
+            bar_speed: 900.0,
+            reverse_key_order: true,
+            background_color: Color::new(0.0, 0.0, 1.0, 1.0),
+            ..AppConfig::default()
+        };
+
+        let serialized = super::serialize_config(&config).expect("serialize");
+
+        for line in [
+            "height = 512.0",
+            "barSpeed = 900.0",
+            "reverseKeyOrder = true",
+            "backgroundColor = \"0,0,255,255\"",
+        ] {
+            assert!(
+                serialized.contains(line),
+                "missing {line:?} in {serialized}"
+            );
+        }
+        assert_eq!(load_from_str(&serialized).expect("reload"), config);
+    }
+
+    #[test]
+    fn test_serialize_config_writes_current_version() {
+        let serialized = super::serialize_config(&AppConfig::default()).expect("serialize");
+
+        assert!(
+            serialized.contains(&format!("version = {}", super::CONFIG_VERSION)),
+            "missing version in {serialized}"
+        );
+        assert!(load_from_str(&serialized).is_ok());
+    }
+
     #[test]
     fn test_config_load_from_str_negative_bar_speed_warns_and_uses_default() {
         let input = r#"
@@ -353,9 +1616,161 @@ barSpeed = -25
     }
 
     #[test]
-    fn test_validate_config_negative_bar_speed_reports_warning() {
+    fn test_config_load_from_str_zero_key_size_clamps_to_minimum() {
+        let parsed = load_from_str("[general]\nkeySize = 0\n").expect("zero key size should load");
+
+        assert_eq!(parsed.key_size, super::MIN_KEY_SIZE);
+    }
+
+    #[test]
+    fn test_validate_config_zero_key_size_reports_warning() {
         let config = AppConfig {
-            bar_speed: -10.0,
+            key_size: 0.0,
+            ..Default::default()
+        };
+
+        let warnings = validate_config(&config);
+        assert!(warnings.iter().any(|w| w.contains("key_size")));
+    }
+
+    #[test]
+    fn test_validate_config_default_config_has_no_warnings() {
+        assert!(validate_config(&AppConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_config_load_from_str_non_positive_height_uses_default() {
+        let parsed = load_from_str("[general]\nheight = 0\n").expect("zero height should load");
+
+        assert_eq!(parsed.height, AppConfig::default().height);
+    }
+
+    #[test]
+    fn test_config_load_from_str_negative_margin_uses_default() {
+        let parsed =
+            load_from_str("[general]\nmargin = -5\n").expect("negative margin should load");
+
+        assert_eq!(parsed.margin, AppConfig::default().margin);
+    }
+
+    #[test]
+    fn test_config_load_from_str_negative_outline_thickness_uses_default() {
+        let parsed = load_from_str("[general]\noutlineThickness = -1\n")
+            .expect("negative outline should load");
+
+        assert_eq!(
+            parsed.outline_thickness,
+            AppConfig::default().outline_thickness
+        );
+    }
+
+    #[test]
+    fn test_fps_is_clamped_at_both_bounds_with_warnings() {
+        for (input, expected_fps, expected_idle) in [
+            ("fps = 1000\nidleFps = 500", 240, 240),
+            ("fps = 0\nidleFps = 0", 1, 1),
+        ] {
+            let raw = parse_raw_config(&format!("[general]\n{input}\n"), ConfigFormat::Toml)
+                .expect("parse");
+
+            let (config, warnings) = resolve_raw_config_with_warnings(raw).expect("resolve");
+
+            assert_eq!(config.fps, expected_fps, "{input}");
+            assert_eq!(config.idle_fps, Some(expected_idle), "{input}");
+            assert_eq!(warnings.len(), 2, "{warnings:?}");
+        }
+    }
+
+    #[test]
+    fn test_non_positive_ui_scale_warns_and_uses_default() {
+        let raw =
+            parse_raw_config("[general]\nuiScale = 0.0\n", ConfigFormat::Toml).expect("parse");
+
+        let (config, warnings) = resolve_raw_config_with_warnings(raw).expect("resolve");
+
+        assert_eq!(config.ui_scale, 1.0);
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+    }
+
+    #[test]
+    fn test_fps_within_bounds_is_unchanged() {
+        let config = AppConfig {
+            fps: super::MAX_FPS,
+            idle_fps: Some(1),
+            ..AppConfig::default()
+        };
+
+        let (sanitized, warnings) =
+            sanitize_config_with_warnings(config).expect("config should sanitize");
+
+        assert_eq!(sanitized.fps, super::MAX_FPS);
+        assert_eq!(sanitized.idle_fps, Some(1));
+        assert!(warnings.is_empty(), "{warnings:?}");
+    }
+
+    #[test]
+    fn test_sanitize_config_resets_invalid_dimensions() {
+        let config = AppConfig {
+            height: -1.0,
+            margin: -2.0,
+            outline_thickness: -3.0,
+            ..Default::default()
+        };
+
+        let (sanitized, warnings) =
+            sanitize_config_with_warnings(config).expect("config should sanitize");
+
+        let defaults = AppConfig::default();
+        assert_eq!(sanitized.height, defaults.height);
+        assert_eq!(sanitized.margin, defaults.margin);
+        assert_eq!(sanitized.outline_thickness, defaults.outline_thickness);
+        assert_eq!(warnings.len(), 3, "{warnings:?}");
+    }
+
+    #[test]
+    fn test_config_load_from_str_max_window_size_warns_on_overflow_and_drops_invalid_limits() {
+        let raw = parse_raw_config(
+            "[general]\nmaxWindowWidth = 100\nmaxWindowHeight = 0\n",
+            ConfigFormat::Toml,
+        )
+        .expect("config should parse");
+        let (config, warnings) =
+            resolve_raw_config_with_warnings(raw).expect("config should resolve");
+
+        assert_eq!(config.max_window_width, Some(100.0));
+        assert_eq!(config.max_window_height, None);
+        assert!(
+            warnings
+                .iter()
+                .any(|w| w.contains("exceeds maxWindowWidth 100")),
+            "missing overflow warning in {warnings:?}"
+        );
+        assert_eq!(warnings.len(), 2, "{warnings:?}");
+    }
+
+    #[test]
+    fn test_config_load_from_str_negative_edge_padding_and_key_spacing_fall_back_to_margin() {
+        let parsed = load_from_str("[general]\nedgePadding = -1\nkeySpacing = -2\n")
+            .expect("config should load");
+
+        assert_eq!(parsed.edge_padding, None);
+        assert_eq!(parsed.key_spacing, None);
+        assert_eq!(parsed.effective_key_spacing(), parsed.margin);
+    }
+
+    #[test]
+    fn test_config_load_from_str_zero_margin_and_outline_are_valid() {
+        let parsed = load_from_str("[general]\nmargin = 0\noutlineThickness = 0\n")
+            .expect("zero margin should load");
+
+        assert_eq!(parsed.margin, 0.0);
+        assert_eq!(parsed.outline_thickness, 0.0);
+    }
+
+    #[test]
+    fn test_validate_config_negative_bar_speed_reports_warning() {
+        let config = AppConfig {
+            bar_speed: -10.0,
             ..Default::default()
         };
 
@@ -381,6 +1796,481 @@ name = "C"
         );
     }
 
+    #[test]
+    fn test_config_load_from_str_parses_bar_pattern() {
+        let parsed = load_from_str("[general]\nbarPattern = \"stripes\"\n")
+            .expect("bar pattern should parse");
+
+        assert_eq!(parsed.bar_pattern, BarPattern::Stripes);
+    }
+
+    #[test]
+    fn test_config_load_from_str_unknown_bar_pattern_returns_error() {
+        let err = load_from_str("[general]\nbarPattern = \"zigzag\"\n")
+            .expect_err("unknown bar pattern should error");
+
+        assert!(err.to_string().contains("failed to parse TOML"));
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_max_bars_per_column() {
+        let parsed =
+            load_from_str("[general]\nmaxBarsPerColumn = 32\n").expect("bar cap should parse");
+
+        assert_eq!(parsed.max_bars_per_column, Some(32));
+        assert_eq!(AppConfig::default().max_bars_per_column, None);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_input_backend() {
+        let parsed = load_from_str("[general]\ninputBackend = \"mock\"\n")
+            .expect("input backend should parse");
+
+        assert_eq!(parsed.input_backend, BackendKind::Mock);
+        assert_eq!(AppConfig::default().input_backend, BackendKind::Auto);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_negative_window_position() {
+        let parsed = load_from_str("[general]\nwindowX = -1920\nwindowY = 40\n")
+            .expect("window position should parse");
+
+        assert_eq!(parsed.window_x, Some(-1920));
+        assert_eq!(parsed.window_y, Some(40));
+        assert_eq!(AppConfig::default().window_x, None);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_themes() {
+        let input = r#"
+[general]
+toggleThemeKey = "F8"
+
+[[theme]]
+name = "night"
+backgroundColor = "10,10,40,255"
+keyColors = { Z = "0,0,255,255" }
+"#;
+
+        let parsed = load_from_str(input).expect("themes should parse");
+
+        assert_eq!(parsed.toggle_theme_key.as_deref(), Some("F8"));
+        assert_eq!(parsed.themes.len(), 1);
+        let theme = &parsed.themes[0];
+        assert_eq!(theme.name, "night");
+        assert_eq!(
+            theme.background_color,
+            Some(Color::from_rgba_u8(10, 10, 40, 255))
+        );
+
+        let themed = theme.apply(&parsed);
+        assert_eq!(themed.keys[0].color, Color::from_rgba_u8(0, 0, 255, 255));
+        assert_eq!(themed.keys[1].color, parsed.keys[1].color);
+    }
+
+    #[test]
+    fn test_config_load_from_str_theme_without_name_returns_error() {
+        let err = load_from_str("[[theme]]\nbackgroundColor = \"0,0,0,255\"\n")
+            .expect_err("nameless theme should error");
+
+        assert!(
+            err.to_string()
+                .contains("theme entry missing required name")
+        );
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_spacer_before() {
+        let input = r#"
+[[key]]
+name = "Z"
+
+[[key]]
+name = "X"
+spacerBefore = 30
+"#;
+
+        let parsed = load_from_str(input).expect("spacer should parse");
+
+        assert_eq!(parsed.keys[0].spacer_before, 0.0);
+        assert_eq!(parsed.keys[1].spacer_before, 30.0);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_key_label_align() {
+        let parsed = load_from_str("[general]\nkeyLabelAlign = \"bottom\"\n")
+            .expect("label align should parse");
+
+        assert_eq!(parsed.key_label_align, KeyLabelAlign::Bottom);
+        assert_eq!(AppConfig::default().key_label_align, KeyLabelAlign::Center);
+    }
+
+    #[test]
+    fn test_config_load_from_str_clamps_bar_width_ratio() {
+        let parsed = load_from_str("[general]\nbarWidthRatio = 1.5\n").expect("config should load");
+
+        assert_eq!(parsed.bar_width_ratio, 1.0);
+    }
+
+    #[test]
+    fn test_config_load_from_str_keeps_counter_format_with_one_placeholder() {
+        let parsed = load_from_str("[general]\ncounterFormat = \"{} hits\"\n")
+            .expect("counter format should parse");
+
+        assert_eq!(parsed.counter_format.as_deref(), Some("{} hits"));
+    }
+
+    #[test]
+    fn test_config_load_from_str_rejects_counter_format_without_placeholder() {
+        let parsed = load_from_str("[general]\ncounterFormat = \"hits\"\n")
+            .expect("bad counter format should only warn");
+
+        assert_eq!(parsed.counter_format, None);
+        let warnings = validate_config(&AppConfig {
+            counter_format: Some("{} / {}".to_string()),
+            ..AppConfig::default()
+        });
+        assert!(warnings.iter().any(|w| w.contains("counter_format")));
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_session_goal() {
+        let parsed =
+            load_from_str("[general]\nsessionGoal = 10000\nsessionGoalPosition = \"bottom\"\n")
+                .expect("session goal should parse");
+
+        assert_eq!(parsed.session_goal, Some(10_000));
+        assert_eq!(parsed.session_goal_position, GoalBarPosition::Bottom);
+        assert_eq!(AppConfig::default().session_goal, None);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_min_bar_lifetime() {
+        let parsed = load_from_str("[general]\nminBarLifetimeMs = 150\n")
+            .expect("min bar lifetime should parse");
+
+        assert_eq!(parsed.min_bar_lifetime_ms, Some(150));
+        assert_eq!(AppConfig::default().min_bar_lifetime_ms, None);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_pressed_color() {
+        let input = r#"
+[[key]]
+name = "Z"
+color = "255,0,0,255"
+pressedColor = "255,200,200,255"
+"#;
+
+        let parsed = load_from_str(input).expect("pressed color should parse");
+
+        assert_eq!(
+            parsed.keys[0].pressed_color,
+            Some(Color::from_rgba_u8(255, 200, 200, 255))
+        );
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_trail_color() {
+        let parsed = load_from_str(
+            "[[key]]\nname = \"Z\"\ntrailColor = \"0,0,255,255\"\n\n[[key]]\nname = \"X\"\n",
+        )
+        .expect("trail color should parse");
+
+        assert_eq!(
+            parsed.keys[0].trail_color,
+            Some(Color::from_rgba_u8(0, 0, 255, 255))
+        );
+        assert_eq!(parsed.keys[1].trail_color, None);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_per_key_bar_speed() {
+        let parsed = load_from_str(
+            "[[key]]\nname = \"Z\"\nbarSpeed = 300\n\n[[key]]\nname = \"X\"\nbarSpeed = -5\n\n[[key]]\nname = \"C\"\n",
+        )
+        .expect("per-key bar speed should parse");
+
+        assert_eq!(parsed.keys[0].bar_speed, Some(300.0));
+        assert_eq!(parsed.keys[1].bar_speed, None);
+        assert_eq!(parsed.keys[2].bar_speed, None);
+        assert_eq!(parsed.bar_speed, AppConfig::default().bar_speed);
+
+        let serialized = super::serialize_config(&parsed).expect("serialize");
+        assert_eq!(serialized.matches("barSpeed = 300").count(), 1);
+    }
+
+    #[test]
+    fn test_config_scancode_key_names_are_canonicalized_and_recognized() {
+        let raw = parse_raw_config(
+            "[[key]]\nname = \"sc:26\"\ndisplayName = \"[\"\n",
+            ConfigFormat::Toml,
+        )
+        .expect("parse");
+
+        let (config, warnings) = resolve_raw_config_with_warnings(raw).expect("resolve");
+
+        assert_eq!(config.keys[0].key_name, "sc:0x1A");
+        assert_eq!(config.keys[0].display_name, "[");
+        assert!(
+            warnings.iter().all(|w| !w.contains("sc:")),
+            "scancode key should be recognized: {warnings:?}"
+        );
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_initial_count() {
+        let parsed =
+            load_from_str("[[key]]\nname = \"Z\"\ninitialCount = 1234\n\n[[key]]\nname = \"X\"\n")
+                .expect("initial count should parse");
+
+        assert_eq!(parsed.keys[0].initial_count, Some(1234));
+        assert_eq!(parsed.keys[1].initial_count, None);
+
+        let serialized = super::serialize_config(&parsed).expect("serialize");
+        assert_eq!(serialized.matches("initialCount = 1234").count(), 1);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_display_name() {
+        let parsed =
+            load_from_str("[[key]]\nname = \"Z\"\ndisplayName = \"↑\"\n\n[[key]]\nname = \"X\"\n")
+                .expect("display name should parse");
+
+        assert_eq!(parsed.keys[0].display_name, "↑");
+        assert_eq!(parsed.keys[1].display_name, "X");
+
+        let serialized = super::serialize_config(&parsed).expect("serialize");
+        assert_eq!(serialized.matches("displayName").count(), 1);
+        assert_eq!(
+            load_from_str(&serialized).expect("reload").keys,
+            parsed.keys
+        );
+    }
+
+    #[test]
+    fn test_validate_config_warns_on_non_ascii_display_name_without_custom_font() {
+        let mut config = AppConfig::default();
+        config.keys[0].display_name = "🎹".to_string();
+
+        let warnings = validate_config(&config);
+        assert!(
+            warnings.iter().any(|w| w.contains("fontPath")),
+            "missing font warning in {warnings:?}"
+        );
+
+        config.fallback_fonts = vec![std::path::PathBuf::from("emoji.ttf")];
+        assert!(
+            !validate_config(&config)
+                .iter()
+                .any(|w| w.contains("fontPath"))
+        );
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_combos() {
+        let input = r#"
+[[combo]]
+keys = ["lctrl", "C"]
+color = "255,200,0,255"
+"#;
+
+        let parsed = load_from_str(input).expect("combo should parse");
+
+        assert_eq!(parsed.combos.len(), 1);
+        assert_eq!(parsed.combos[0].keys, vec!["LControl", "C"]);
+        assert_eq!(
+            parsed.combos[0].color,
+            Color::from_rgba_u8(255, 200, 0, 255)
+        );
+
+        let reloaded = load_from_str(&super::serialize_config(&parsed).expect("serialize"))
+            .expect("serialized combo should parse");
+        assert_eq!(reloaded.combos, parsed.combos);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_groups() {
+        let input = r#"
+[[group]]
+label = "move"
+color = "0,255,0,255"
+keys = ["lctrl", "Z"]
+"#;
+
+        let parsed = load_from_str(input).expect("group should parse");
+
+        assert_eq!(parsed.groups.len(), 1);
+        assert_eq!(parsed.groups[0].label, "move");
+        assert_eq!(parsed.groups[0].keys, vec!["LControl", "Z"]);
+        assert_eq!(parsed.groups[0].color, Color::from_rgba_u8(0, 255, 0, 255));
+
+        let reloaded = load_from_str(&super::serialize_config(&parsed).expect("serialize"))
+            .expect("serialized group should parse");
+        assert_eq!(reloaded.groups, parsed.groups);
+    }
+
+    #[test]
+    fn test_config_load_from_str_rejects_group_without_label_or_keys() {
+        assert!(load_from_str("[[group]]\nkeys = [\"Z\"]\n").is_err());
+        assert!(load_from_str("[[group]]\nlabel = \"empty\"\n").is_err());
+    }
+
+    #[test]
+    fn test_config_load_from_str_rejects_single_key_combo() {
+        let err = load_from_str("[[combo]]\nkeys = [\"Z\"]\n").expect_err("combo should error");
+
+        assert!(err.to_string().contains("at least two keys"));
+    }
+
+    #[test]
+    fn test_color_serde_matches_config_serializer_output() {
+        let config = AppConfig {
+            background_color: Color::from_rgba_u8(1, 2, 3, 4),
+            ..AppConfig::default()
+        };
+        let toml = super::serialize_config(&config).expect("config should serialize");
+        let json = serde_json::to_string(&config.background_color).expect("color should serialize");
+
+        assert!(toml.contains("backgroundColor = \"1,2,3,4\""));
+        assert_eq!(json, "\"1,2,3,4\"");
+    }
+
+    #[test]
+    fn test_config_load_from_str_clamps_font_scales() {
+        let parsed = load_from_str("[general]\nkeyLabelScale = 2.0\ncounterScale = 0.0\n")
+            .expect("config should load");
+
+        assert!((parsed.key_label_scale - 1.0).abs() < f32::EPSILON);
+        assert!((parsed.counter_scale - 0.05).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn test_serialize_config_round_trips_pressed_color() {
+        let mut config = AppConfig::default();
+        config.keys[0].pressed_color = Some(Color::from_rgba_u8(10, 20, 30, 255));
+
+        let toml = super::serialize_config(&config).expect("config should serialize");
+        let reloaded = load_from_str(&toml).expect("serialized config should parse");
+
+        assert_eq!(reloaded.keys[0].pressed_color, config.keys[0].pressed_color);
+        assert_eq!(reloaded.keys[1].pressed_color, None);
+    }
+
+    #[test]
+    fn test_config_load_from_str_parses_per_key_fading() {
+        let input = "[[key]]\nname = \"Z\"\nfading = false\n\n[[key]]\nname = \"X\"\n";
+
+        let parsed = load_from_str(input).expect("per-key fading should parse");
+
+        assert_eq!(parsed.keys[0].fading, Some(false));
+        assert_eq!(parsed.keys[1].fading, None);
+    }
+
+    #[test]
+    fn test_validate_config_warns_when_key_collides_with_close_hotkey() {
+        let config = load_from_str("[[key]]\nname = \"Escape\"\n\n[[key]]\nname = \"Z\"\n")
+            .expect("config should parse");
+
+        let warnings = validate_config(&config);
+
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("'Escape'"));
+        assert!(warnings[0].contains("close hotkey"));
+    }
+
+    #[test]
+    fn test_validate_config_warns_when_key_collides_with_toggle_theme_key() {
+        let config =
+            load_from_str("[general]\ntoggleThemeKey = \"Z\"\n").expect("config should parse");
+
+        let warnings = validate_config(&config);
+
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("'Z'") && warning.contains("toggleThemeKey"))
+        );
+        assert!(validate_config(&AppConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_config_load_from_str_normalizes_key_names_to_key_id_display() {
+        let input = r#"
+[general]
+toggleThemeKey = "f8"
+
+[[key]]
+name = "lcontrol"
+
+[[key]]
+name = "z"
+"#;
+
+        let parsed = load_from_str(input).expect("config should parse");
+
+        assert_eq!(parsed.keys[0].key_name, "LControl");
+        assert_eq!(parsed.keys[0].display_name, "LControl");
+        assert_eq!(parsed.keys[1].key_name, "Z");
+        assert_eq!(parsed.toggle_theme_key.as_deref(), Some("F8"));
+    }
+
+    #[test]
+    fn test_validate_config_warns_about_unrecognized_key_names() {
+        let parsed = load_from_str("[[key]]\nname = \"Shfit\"\n\n[[key]]\nname = \"Z\"\n")
+            .expect("unknown names only warn by default");
+
+        assert_eq!(parsed.keys[0].key_name, "Shfit");
+        let warnings = validate_config(&parsed);
+        assert!(
+            warnings
+                .iter()
+                .any(|warning| warning.contains("unrecognized key names")
+                    && warning.contains("Shfit"))
+        );
+    }
+
+    #[test]
+    fn test_config_load_from_str_canonicalizes_aliases_and_warns_on_unknown() {
+        let parsed = load_from_str(
+            "[[key]]\nname = \"Up\"\naliases = [\"w\", \" \", \"Upp\"]\n\n[[key]]\nname = \"X\"\n",
+        )
+        .expect("unknown aliases only warn by default");
+
+        assert_eq!(parsed.keys[0].aliases, vec!["W", "Upp"]);
+        assert!(parsed.keys[1].aliases.is_empty());
+        let warnings = validate_config(&parsed);
+        assert!(
+            warnings.iter().any(
+                |warning| warning.contains("unrecognized key names") && warning.contains("Upp")
+            )
+        );
+    }
+
+    #[test]
+    fn test_display_name_quoted_in_warning_does_not_trigger_clamps() {
+        let raw = parse_raw_config(
+            "[general]\nkeySize = 80.0\ndtSmoothing = 0.5\nbarSpeed = 900.0\n\n[[key]]\nname = \"Z\"\ndisplayName = \"key_size dt_smoothing bar_speed é\"\n",
+            ConfigFormat::Toml,
+        )
+        .expect("parse");
+
+        let (config, warnings) = resolve_raw_config_with_warnings(raw).expect("resolve");
+
+        assert!(warnings.iter().any(|warning| warning.contains("non-ASCII")));
+        assert_eq!(config.key_size, 80.0);
+        assert_eq!(config.dt_smoothing, 0.5);
+        assert_eq!(config.bar_speed, 900.0);
+    }
+
+    #[test]
+    fn test_config_load_from_str_strict_key_names_rejects_unknown_names() {
+        let err = load_from_str("[general]\nstrictKeyNames = true\n\n[[key]]\nname = \"Shfit\"\n")
+            .expect_err("strict mode should reject unknown names");
+
+        assert!(err.to_string().contains("unrecognized key names: Shfit"));
+    }
+
     #[test]
     fn test_config_load_from_str_invalid_key_color_returns_error() {
         let input = r#"
@@ -518,4 +2408,165 @@ size = 1.5
         // Clean up
         let _ = std::fs::remove_file(&config_path);
     }
+
+    #[test]
+    fn test_config_load_from_json_str_matches_equivalent_toml() {
+        let from_json = load_from_json_str(full_valid_json()).expect("json config should parse");
+        let from_toml = load_from_str(full_valid_toml()).expect("toml config should parse");
+
+        assert_eq!(from_json, from_toml);
+    }
+
+    #[test]
+    fn test_config_load_from_json_str_invalid_json_returns_error() {
+        let err = load_from_json_str("{ not json").expect_err("invalid json should error");
+
+        assert!(err.to_string().contains("failed to parse JSON"));
+    }
+
+    #[test]
+    fn test_config_format_from_path_uses_extension() {
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.json")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.JSON")),
+            ConfigFormat::Json
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config.toml")),
+            ConfigFormat::Toml
+        );
+        assert_eq!(
+            ConfigFormat::from_path(Path::new("config")),
+            ConfigFormat::Toml
+        );
+    }
+
+    #[test]
+    fn test_load_config_dispatches_on_json_extension() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, full_valid_json()).expect("write json config");
+
+        let config = load_config(&path).expect("json file should load");
+
+        assert_eq!(
+            config,
+            load_from_str(full_valid_toml()).expect("toml config should parse")
+        );
+    }
+
+    #[test]
+    fn test_ensure_config_exists_writes_json_for_json_path() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.json");
+
+        let config = ensure_config_exists(&path).expect("ensure_config_exists failed");
+        let content = std::fs::read_to_string(&path).expect("read config failed");
+
+        assert_eq!(config, AppConfig::default());
+        assert!(content.trim_start().starts_with('{'));
+        assert!(content.contains("\"keySize\""));
+    }
+
+    #[test]
+    fn test_config_load_from_ron_str_matches_equivalent_toml() {
+        let ron = r#"
+(
+    general: (
+        height: 700,
+        keySize: 70,
+        barSpeed: 600,
+        backgroundColor: "0,0,0,255",
+        margin: 25,
+        outlineThickness: 5,
+        fading: true,
+        counter: true,
+        fps: 60,
+        logToFile: false,
+    ),
+    key: [
+        (name: "Z", color: "255,0,0,255", size: 1.0),
+        (name: "X", color: "0,255,255,255", size: 1.0),
+    ],
+)
+"#;
+
+        let from_ron = load_from_ron_str(ron).expect("ron config should parse");
+        let from_toml = load_from_str(full_valid_toml()).expect("toml config should parse");
+
+        assert_eq!(from_ron, from_toml);
+    }
+
+    #[test]
+    fn test_serialize_config_ron_round_trips() {
+        let mut config = AppConfig {
+            bar_pattern: BarPattern::Dots,
+            max_bars_per_column: Some(16),
+            ..AppConfig::default()
+        };
+        config.keys[0].pressed_color = Some(Color::from_rgba_u8(1, 2, 3, 4));
+
+        let ron = super::serialize_config_ron(&config).expect("config should serialize");
+        let reloaded = load_from_ron_str(&ron).expect("serialized ron should parse");
+
+        assert_eq!(reloaded, config);
+    }
+
+    #[test]
+    fn test_config_load_from_ron_str_malformed_returns_config_error() {
+        let err =
+            load_from_ron_str("(general: (keySize: ").expect_err("malformed ron should error");
+
+        assert!(matches!(err, crate::types::AppError::Config(_)));
+        assert!(err.to_string().contains("failed to parse RON"));
+    }
+
+    #[test]
+    fn test_check_config_reports_warnings_and_errors_without_creating_files() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[general]\nkeySize = 0\n").expect("write config");
+
+        let warnings = check_config(&path).expect("clamped config should pass the check");
+        assert!(warnings.iter().any(|w| w.contains("key_size")));
+
+        std::fs::write(&path, "[general\n").expect("write broken config");
+        assert!(check_config(&path).is_err());
+
+        let missing = dir.path().join("missing.toml");
+        assert!(check_config(&missing).is_err());
+        assert!(!missing.exists());
+    }
+
+    #[test]
+    fn test_save_config_overwrites_file_and_round_trips() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.toml");
+        ensure_config_exists(&path).expect("create default config");
+
+        let mut config = AppConfig::default();
+        config.keys.reverse();
+        save_config(&path, &config).expect("save config");
+
+        assert_eq!(load_config(&path).expect("reload config"), config);
+    }
+
+    #[test]
+    fn test_ensure_config_exists_writes_ron_for_ron_path() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.ron");
+
+        let config = ensure_config_exists(&path).expect("ensure_config_exists failed");
+
+        assert_eq!(config, AppConfig::default());
+        assert_eq!(ConfigFormat::from_path(&path), ConfigFormat::Ron);
+        assert!(
+            std::fs::read_to_string(&path)
+                .expect("read config")
+                .contains("keySize")
+        );
+    }
 }
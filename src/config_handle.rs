@@ -0,0 +1,146 @@
+//! Shared config handle for host applications that embed the overlay.
+//!
+//! Updates pushed through a [`ConfigHandle`] are validated like config files
+//! and travel over the same channel as file watcher reloads, so the
+//! orchestrator applies both the same way.
+
+use std::sync::{Arc, PoisonError, RwLock};
+
+use crossbeam_channel::{Receiver, Sender, unbounded};
+
+use crate::config::sanitize_config;
+use crate::types::{AppConfig, AppError};
+
+/// Cloneable handle to the running overlay's config.
+///
+/// [`ConfigHandle::current`] reflects the last config sent through the handle
+/// or applied from a file reload.
+#[derive(Debug, Clone)]
+pub struct ConfigHandle {
+    current: Arc<RwLock<AppConfig>>,
+    update_tx: Sender<AppConfig>,
+    update_rx: Receiver<AppConfig>,
+}
+
+impl ConfigHandle {
+    /// Creates a handle whose current config is `config`; nothing is queued yet.
+    pub fn new(config: AppConfig) -> Self {
+        let (update_tx, update_rx) = unbounded();
+        Self {
+            current: Arc::new(RwLock::new(config)),
+            update_tx,
+            update_rx,
+        }
+    }
+
+    /// Returns a copy of the current config.
+    pub fn current(&self) -> AppConfig {
+        self.current
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Replaces the config and queues it for the overlay.
+    ///
+    /// See [`ConfigHandle::update`] for how invalid configs are handled.
+    pub fn set(&self, config: AppConfig) -> Result<(), AppError> {
+        self.update(|current| *current = config)
+    }
+
+    /// Edits the current config and queues the result for the overlay.
+    ///
+    /// Invalid values are fixed as in a config file. A config rejected by
+    /// `strictKeyNames` returns the error and leaves the current one in place.
+    pub fn update(&self, edit: impl FnOnce(&mut AppConfig)) -> Result<(), AppError> {
+        let mut current = self.current.write().unwrap_or_else(PoisonError::into_inner);
+        let mut edited = current.clone();
+        edit(&mut edited);
+        let sanitized = sanitize_config(edited)?;
+        *current = sanitized.clone();
+        // The handle owns a receiver, so the channel never disconnects.
+        let _ = self.update_tx.send(sanitized);
+        Ok(())
+    }
+
+    /// Records a config applied from elsewhere without queueing it again.
+    pub(crate) fn store(&self, config: AppConfig) {
+        *self.current.write().unwrap_or_else(PoisonError::into_inner) = config;
+    }
+
+    /// Sender shared with the file watcher.
+    pub(crate) fn sender(&self) -> Sender<AppConfig> {
+        self.update_tx.clone()
+    }
+
+    /// Receiver polled by the orchestrator for pending configs.
+    pub(crate) fn receiver(&self) -> Receiver<AppConfig> {
+        self.update_rx.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfigHandle;
+    use crate::types::AppConfig;
+
+    #[test]
+    fn test_update_edits_current_and_queues_the_result() {
+        let handle = ConfigHandle::new(AppConfig::default());
+        let receiver = handle.receiver();
+
+        handle
+            .update(|config| config.height = 640.0)
+            .expect("valid update");
+
+        assert_eq!(handle.current().height, 640.0);
+        assert_eq!(receiver.try_recv().expect("queued config").height, 640.0);
+    }
+
+    #[test]
+    fn test_update_fixes_invalid_values_like_a_config_file() {
+        let handle = ConfigHandle::new(AppConfig::default());
+        let receiver = handle.receiver();
+
+        handle
+            .set(AppConfig {
+                ui_scale: f32::NAN,
+                height: -5.0,
+                ..AppConfig::default()
+            })
+            .expect("invalid values are fixed, not rejected");
+
+        let defaults = AppConfig::default();
+        let queued = receiver.try_recv().expect("queued config");
+        assert_eq!(queued.ui_scale, defaults.ui_scale);
+        assert_eq!(queued.height, defaults.height);
+        assert_eq!(handle.current(), queued);
+    }
+
+    #[test]
+    fn test_update_rejected_by_strict_key_names_keeps_current() {
+        let handle = ConfigHandle::new(AppConfig::default());
+
+        let result = handle.update(|config| {
+            config.strict_key_names = true;
+            config.keys[0].key_name = "Shfit".to_string();
+        });
+
+        assert!(result.is_err());
+        assert_eq!(handle.current(), AppConfig::default());
+        assert!(handle.receiver().is_empty());
+    }
+
+    #[test]
+    fn test_store_updates_current_without_queueing() {
+        let handle = ConfigHandle::new(AppConfig::default());
+
+        handle.store(AppConfig {
+            height: 500.0,
+            ..AppConfig::default()
+        });
+
+        assert_eq!(handle.clone().current().height, 500.0);
+        assert!(handle.receiver().is_empty());
+    }
+}
@@ -0,0 +1,240 @@
+//! Backend-independent description of one overlay frame.
+//!
+//! [`Renderer::build_draw_list`](crate::renderer::Renderer::build_draw_list)
+//! turns layout and bar state into a [`DrawList`]; the egui backend paints it,
+//! and other backends (e.g. a headless exporter) can consume the same list.
+//! Geometry uses egui's `emath` types, which carry no rendering state.
+
+use std::path::PathBuf;
+
+use egui::{Align2, Pos2, Rect};
+
+use crate::types::Color;
+
+/// Ordered shapes for one frame, painted back to front.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DrawList {
+    /// Whole-overlay opacity multiplied into every shape (idle auto-hide).
+    pub opacity: f32,
+    pub shapes: Vec<DrawShape>,
+}
+
+/// A single primitive in a [`DrawList`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawShape {
+    FilledRect {
+        rect: Rect,
+        corner_radius: f32,
+        color: Color,
+    },
+    StrokedRect {
+        rect: Rect,
+        corner_radius: f32,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Pos2,
+        radius: f32,
+        color: Color,
+    },
+    Line {
+        from: Pos2,
+        to: Pos2,
+        thickness: f32,
+        color: Color,
+    },
+    /// Single-line monospace text anchored at `pos` by `align`.
+    Text {
+        pos: Pos2,
+        align: Align2,
+        text: String,
+        font_size: f32,
+        color: Color,
+        /// Box drawn behind the text; `pos` and `align` then anchor the box.
+        backdrop: Option<TextBackdrop>,
+    },
+    /// PNG file stretched over `rect`, e.g. a key icon.
+    Image { rect: Rect, path: PathBuf },
+}
+
+/// Filled box behind a [`DrawShape::Text`], sized to the text plus padding.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextBackdrop {
+    pub color: Color,
+    pub padding: f32,
+    pub corner_radius: f32,
+}
+
+impl DrawList {
+    /// Creates an empty list painted at `opacity`.
+    pub fn new(opacity: f32) -> Self {
+        Self {
+            opacity,
+            shapes: Vec::new(),
+        }
+    }
+
+    /// Appends `shape`, drawn above the shapes already in the list.
+    pub fn push(&mut self, shape: DrawShape) {
+        self.shapes.push(shape);
+    }
+
+    /// Scales every shape about `origin`, e.g. to apply `uiScale`.
+    ///
+    /// Sizes that are not positions (font sizes, stroke widths, radii and
+    /// paddings) are multiplied by `factor` too.
+    pub fn scale(&mut self, factor: f32, origin: Pos2) {
+        let pos = |pos: Pos2| origin + (pos - origin) * factor;
+        let rect = |rect: Rect| Rect::from_min_max(pos(rect.min), pos(rect.max));
+
+        for shape in &mut self.shapes {
+            match shape {
+                DrawShape::FilledRect {
+                    rect: r,
+                    corner_radius,
+                    ..
+                } => {
+                    *r = rect(*r);
+                    *corner_radius *= factor;
+                }
+                DrawShape::StrokedRect {
+                    rect: r,
+                    corner_radius,
+                    thickness,
+                    ..
+                } => {
+                    *r = rect(*r);
+                    *corner_radius *= factor;
+                    *thickness *= factor;
+                }
+                DrawShape::Circle { center, radius, .. } => {
+                    *center = pos(*center);
+                    *radius *= factor;
+                }
+                DrawShape::Line {
+                    from,
+                    to,
+                    thickness,
+                    ..
+                } => {
+                    *from = pos(*from);
+                    *to = pos(*to);
+                    *thickness *= factor;
+                }
+                DrawShape::Text {
+                    pos: p,
+                    font_size,
+                    backdrop,
+                    ..
+                } => {
+                    *p = pos(*p);
+                    *font_size *= factor;
+                    if let Some(backdrop) = backdrop {
+                        backdrop.padding *= factor;
+                        backdrop.corner_radius *= factor;
+                    }
+                }
+                DrawShape::Image { rect: r, .. } => *r = rect(*r),
+            }
+        }
+    }
+
+    /// Number of filled and stroked rectangles.
+    pub fn rect_count(&self) -> usize {
+        self.shapes
+            .iter()
+            .filter(|shape| {
+                matches!(
+                    shape,
+                    DrawShape::FilledRect { .. } | DrawShape::StrokedRect { .. }
+                )
+            })
+            .count()
+    }
+
+    /// Text of every text shape, in paint order.
+    pub fn texts(&self) -> impl Iterator<Item = &str> {
+        self.shapes.iter().filter_map(|shape| match shape {
+            DrawShape::Text { text, .. } => Some(text.as_str()),
+            _ => None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Align2, Pos2, Rect};
+
+    use super::{DrawList, DrawShape};
+    use crate::types::Color;
+
+    #[test]
+    fn test_draw_list_counts_rects_and_collects_text() {
+        let mut list = DrawList::new(1.0);
+        let rect = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(10.0, 10.0));
+        list.push(DrawShape::FilledRect {
+            rect,
+            corner_radius: 0.0,
+            color: Color::black(),
+        });
+        list.push(DrawShape::Circle {
+            center: rect.center(),
+            radius: 2.0,
+            color: Color::black(),
+        });
+        list.push(DrawShape::StrokedRect {
+            rect,
+            corner_radius: 0.0,
+            thickness: 1.0,
+            color: Color::black(),
+        });
+        list.push(DrawShape::Text {
+            pos: rect.center(),
+            align: Align2::CENTER_CENTER,
+            text: "Z".to_string(),
+            font_size: 12.0,
+            color: Color::black(),
+            backdrop: None,
+        });
+
+        assert_eq!(list.rect_count(), 2);
+        assert_eq!(list.texts().collect::<Vec<_>>(), vec!["Z"]);
+    }
+
+    #[test]
+    fn test_scale_multiplies_geometry_about_the_origin() {
+        let mut list = DrawList::new(1.0);
+        list.push(DrawShape::StrokedRect {
+            rect: Rect::from_min_max(Pos2::new(15.0, 20.0), Pos2::new(25.0, 40.0)),
+            corner_radius: 2.0,
+            thickness: 1.5,
+            color: Color::black(),
+        });
+        list.push(DrawShape::Text {
+            pos: Pos2::new(20.0, 30.0),
+            align: Align2::CENTER_CENTER,
+            text: "Z".to_string(),
+            font_size: 12.0,
+            color: Color::black(),
+            backdrop: None,
+        });
+
+        list.scale(2.0, Pos2::new(10.0, 10.0));
+
+        assert_eq!(
+            list.shapes[0],
+            DrawShape::StrokedRect {
+                rect: Rect::from_min_max(Pos2::new(20.0, 30.0), Pos2::new(40.0, 70.0)),
+                corner_radius: 4.0,
+                thickness: 3.0,
+                color: Color::black(),
+            }
+        );
+        let DrawShape::Text { pos, font_size, .. } = &list.shapes[1] else {
+            panic!("expected text");
+        };
+        assert_eq!(*pos, Pos2::new(30.0, 50.0));
+        assert_eq!(*font_size, 24.0);
+    }
+}
@@ -2,6 +2,13 @@
 //!
 //! Provides access to the bundled JetBrains Mono font. The font is embedded
 //! at compile time using `include_bytes!` for zero-dependency deployment.
+//! A custom primary font may replace it, and optional fallback fonts are read
+//! from disk to cover glyphs it lacks.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use tracing::warn;
 
 /// Returns a static reference to the bundled JetBrains Mono Regular font bytes.
 ///
@@ -21,10 +28,153 @@ pub fn load_font() -> &'static [u8] {
     include_bytes!("../assets/JetBrainsMono-Regular.ttf")
 }
 
+/// Returns the primary font: the file at `path` if given and readable, else the bundled font.
+///
+/// Files that cannot be read or do not look like TTF/OTF data are logged and skipped.
+pub fn load_primary_font(path: Option<&Path>) -> Cow<'static, [u8]> {
+    let Some(path) = path else {
+        return Cow::Borrowed(load_font());
+    };
+
+    match std::fs::read(path) {
+        Ok(bytes) if has_font_signature(&bytes) => Cow::Owned(bytes),
+        Ok(_) => {
+            warn!(
+                "font '{}' is not a TTF/OTF file; using bundled font",
+                path.display()
+            );
+            Cow::Borrowed(load_font())
+        }
+        Err(err) => {
+            warn!(
+                "failed to read font '{}': {err}; using bundled font",
+                path.display()
+            );
+            Cow::Borrowed(load_font())
+        }
+    }
+}
+
+/// Checks the leading tag of a TrueType, OpenType or font collection file.
+fn has_font_signature(bytes: &[u8]) -> bool {
+    matches!(
+        bytes.get(..4),
+        Some([0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" | b"ttcf")
+    )
+}
+
+/// Reads fallback font files, skipping (and logging) any that cannot be read
+/// or do not look like TTF/OTF data.
+///
+/// Returns `(name, bytes)` pairs in configuration order, named after their path.
+pub fn load_fallback_fonts(paths: &[PathBuf]) -> Vec<(String, Vec<u8>)> {
+    paths
+        .iter()
+        .filter_map(|path| match std::fs::read(path) {
+            Ok(bytes) if has_font_signature(&bytes) => Some((fallback_font_name(path), bytes)),
+            Ok(_) => {
+                warn!(
+                    "skipping fallback font '{}': not a TTF/OTF file",
+                    path.display()
+                );
+                None
+            }
+            Err(err) => {
+                warn!("skipping fallback font '{}': {err}", path.display());
+                None
+            }
+        })
+        .collect()
+}
+
+fn fallback_font_name(path: &Path) -> String {
+    format!("fallback:{}", path.display())
+}
+
+/// Puts `chain` at the front of a font family, in order, ahead of the existing fonts.
+///
+/// Names already in the family are moved rather than duplicated.
+pub fn prepend_font_chain(family: &mut Vec<String>, chain: &[String]) {
+    family.retain(|name| !chain.contains(name));
+    family.splice(0..0, chain.iter().cloned());
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_prepend_font_chain_orders_primary_before_fallbacks() {
+        let mut family = vec!["Hack".to_string(), "NotoEmoji-Regular".to_string()];
+        let chain = vec![
+            "jetbrains-mono".to_string(),
+            "fallback:cjk.ttf".to_string(),
+            "NotoEmoji-Regular".to_string(),
+        ];
+
+        prepend_font_chain(&mut family, &chain);
+
+        assert_eq!(
+            family,
+            vec![
+                "jetbrains-mono".to_string(),
+                "fallback:cjk.ttf".to_string(),
+                "NotoEmoji-Regular".to_string(),
+                "Hack".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_fallback_fonts_skips_unreadable_paths() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let present = dir.path().join("present.ttf");
+        std::fs::write(&present, [0u8, 1, 0, 0]).expect("write font");
+
+        let fonts = load_fallback_fonts(&[dir.path().join("missing.ttf"), present.clone()]);
+
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].0, fallback_font_name(&present));
+        assert_eq!(fonts[0].1, vec![0u8, 1, 0, 0]);
+    }
+
+    #[test]
+    fn test_load_fallback_fonts_drops_non_font_files() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let image = dir.path().join("image.png");
+        std::fs::write(&image, b"\x89PNG\r\n\x1a\n").expect("write image");
+        let font = dir.path().join("font.otf");
+        std::fs::write(&font, b"OTTO rest of font").expect("write font");
+
+        let fonts = load_fallback_fonts(&[image, font.clone()]);
+
+        assert_eq!(fonts.len(), 1);
+        assert_eq!(fonts[0].0, fallback_font_name(&font));
+    }
+
+    #[test]
+    fn test_load_primary_font_falls_back_to_bundled_for_missing_path() {
+        let font = load_primary_font(Some(Path::new("/nonexistent/custom.ttf")));
+
+        assert_eq!(font.as_ref(), load_font());
+    }
+
+    #[test]
+    fn test_load_primary_font_reads_custom_font_and_rejects_non_fonts() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let custom = dir.path().join("custom.otf");
+        std::fs::write(&custom, b"OTTO rest of font").expect("write font");
+        let bogus = dir.path().join("bogus.ttf");
+        std::fs::write(&bogus, b"not a font").expect("write bogus font");
+
+        assert_eq!(
+            load_primary_font(Some(&custom)).as_ref(),
+            b"OTTO rest of font"
+        );
+        assert_eq!(load_primary_font(Some(&bogus)).as_ref(), load_font());
+        assert_eq!(load_primary_font(None).as_ref(), load_font());
+    }
+
     #[test]
     fn test_load_font_returns_non_empty() {
         let font_data = load_font();
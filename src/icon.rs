@@ -0,0 +1,137 @@
+//! Key icon loading and caching.
+//!
+//! `iconPath` images are decoded once per path when a config is applied, so
+//! the draw list can choose between icon and text label without an egui
+//! context. Textures are uploaded on first paint and cached by the same path.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use egui::{ColorImage, Context, TextureHandle, TextureId, TextureOptions};
+use tiny_skia::Pixmap;
+use tracing::warn;
+
+use crate::types::{AppError, KeyConfig};
+
+/// Decoded key icons and their egui textures, keyed by file path.
+#[derive(Default)]
+pub struct IconCache {
+    /// Decoded image per path; `None` records a failed load so it is not retried.
+    images: HashMap<PathBuf, Option<ColorImage>>,
+    textures: HashMap<PathBuf, TextureHandle>,
+}
+
+impl fmt::Debug for IconCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("IconCache")
+            .field("images", &self.images.keys().collect::<Vec<_>>())
+            .field("textures", &self.textures.len())
+            .finish()
+    }
+}
+
+impl IconCache {
+    /// Decodes every key's icon that is not cached yet.
+    pub fn load_keys(&mut self, keys: &[KeyConfig]) {
+        for path in keys.iter().filter_map(|key| key.icon_path.as_deref()) {
+            self.load(path);
+        }
+    }
+
+    /// Decodes the icon at `path` on first use, logging a warning on failure.
+    ///
+    /// Returns whether the icon is available.
+    pub fn load(&mut self, path: &Path) -> bool {
+        self.images
+            .entry(path.to_path_buf())
+            .or_insert_with(|| match load_icon_image(path) {
+                Ok(image) => Some(image),
+                Err(err) => {
+                    warn!("{err}; showing the text label instead");
+                    None
+                }
+            })
+            .is_some()
+    }
+
+    /// Whether the icon at `path` was decoded successfully.
+    pub fn is_loaded(&self, path: &Path) -> bool {
+        self.images.get(path).is_some_and(Option::is_some)
+    }
+
+    /// Texture for the icon at `path`, uploaded to `ctx` on first use.
+    pub fn texture_id(&mut self, ctx: &Context, path: &Path) -> Option<TextureId> {
+        if let Some(texture) = self.textures.get(path) {
+            return Some(texture.id());
+        }
+
+        let image = self.images.get(path)?.as_ref()?.clone();
+        let texture = ctx.load_texture(path.display().to_string(), image, TextureOptions::LINEAR);
+        let id = texture.id();
+        self.textures.insert(path.to_path_buf(), texture);
+        Some(id)
+    }
+}
+
+/// Reads and decodes the PNG at `path`.
+pub fn load_icon_image(path: &Path) -> Result<ColorImage, AppError> {
+    let pixmap = Pixmap::load_png(path).map_err(|err| {
+        AppError::Render(format!("failed to load icon '{}': {err}", path.display()))
+    })?;
+    Ok(ColorImage::from_rgba_premultiplied(
+        [pixmap.width() as usize, pixmap.height() as usize],
+        pixmap.data(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tiny_skia::Pixmap;
+
+    use super::IconCache;
+
+    fn write_icon(path: &Path) {
+        let mut pixmap = Pixmap::new(4, 4).expect("pixmap");
+        pixmap.fill(tiny_skia::Color::from_rgba8(255, 0, 0, 255));
+        pixmap.save_png(path).expect("write icon");
+    }
+
+    #[test]
+    fn test_load_caches_by_path_and_remembers_failures() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let icon = dir.path().join("icon.png");
+        let missing = dir.path().join("missing.png");
+        write_icon(&icon);
+        let mut cache = IconCache::default();
+
+        assert!(cache.load(&icon));
+        assert!(!cache.load(&missing));
+        std::fs::remove_file(&icon).expect("remove icon");
+        write_icon(&missing);
+
+        assert!(cache.load(&icon), "decoded icon stays cached");
+        assert!(!cache.load(&missing), "failed load is not retried");
+        assert!(cache.is_loaded(&icon));
+        assert!(!cache.is_loaded(&missing));
+    }
+
+    #[test]
+    fn test_texture_id_uploads_once_per_path() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let icon = dir.path().join("icon.png");
+        write_icon(&icon);
+        let ctx = egui::Context::default();
+        let mut cache = IconCache::default();
+
+        assert_eq!(cache.texture_id(&ctx, &icon), None, "not decoded yet");
+        cache.load(&icon);
+        let first = cache.texture_id(&ctx, &icon).expect("texture");
+        let second = cache.texture_id(&ctx, &icon).expect("texture");
+
+        assert_eq!(first, second);
+        assert_eq!(cache.textures.len(), 1);
+    }
+}
@@ -1,6 +1,14 @@
 //! Input backend abstraction and test backend.
 
-use crossbeam_channel::Sender;
+use std::path::Path;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::{RecvTimeoutError, Sender, bounded, unbounded};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 use crate::types::{AppError, InputEvent};
 
@@ -8,10 +16,54 @@ use crate::types::{AppError, InputEvent};
 pub trait InputBackend: Send + 'static {
     fn start(&mut self, tx: Sender<InputEvent>) -> Result<(), AppError>;
     fn stop(&mut self) -> Result<(), AppError>;
+    /// Short identifier of the backend implementation, used for logging.
+    fn name(&self) -> &'static str;
+    /// Channel for failures that happen after `start` returned, such as a
+    /// listener thread denied access by the OS. Backends that cannot fail
+    /// later ignore it.
+    fn set_status_sender(&mut self, _status_tx: Sender<BackendStatus>) {}
+}
+
+/// Health of the input backend, reported to the orchestrator.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BackendStatus {
+    /// Capturing input.
+    Running,
+    /// Capture failed or stopped; the message says why.
+    Failed(String),
 }
 
-/// Creates the default input backend for the current platform.
-pub fn create_backend() -> Box<dyn InputBackend> {
+/// Input backend selection, configurable via `inputBackend`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BackendKind {
+    /// Picks the platform backend for the current target OS.
+    #[default]
+    Auto,
+    Rdev,
+    Mock,
+    Replay,
+}
+
+/// Creates the input backend selected by `kind`.
+///
+/// `replay_file` is the recording played by [`BackendKind::Replay`].
+pub fn create_backend(kind: BackendKind, replay_file: Option<&Path>) -> Box<dyn InputBackend> {
+    match kind {
+        BackendKind::Auto => create_platform_backend(),
+        BackendKind::Rdev => Box::new(crate::input::rdev_backend::RdevBackend::new()),
+        BackendKind::Mock => Box::new(MockBackend::default()),
+        BackendKind::Replay => match replay_file {
+            Some(path) => Box::new(crate::input::replay::ReplayBackend::new(path)),
+            None => {
+                warn!("replay input backend needs replayFile; using mock backend");
+                Box::new(MockBackend::default())
+            }
+        },
+    }
+}
+
+fn create_platform_backend() -> Box<dyn InputBackend> {
     #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
     {
         Box::new(crate::input::rdev_backend::RdevBackend::new())
@@ -27,9 +79,12 @@ pub fn create_backend() -> Box<dyn InputBackend> {
 #[derive(Debug, Clone, Default)]
 pub struct MockBackend {
     scripted_events: Vec<InputEvent>,
+    timed_events: Vec<(Duration, InputEvent)>,
     fail_start: Option<String>,
     fail_stop: Option<String>,
     started: bool,
+    /// Dropping this wakes the timed emitter thread and makes it exit.
+    emitter_stop_tx: Option<Sender<()>>,
 }
 
 impl MockBackend {
@@ -40,6 +95,17 @@ impl MockBackend {
         }
     }
 
+    /// Emits each event from a background thread after waiting its delay.
+    ///
+    /// Delays are relative to the previous event. Pending events are
+    /// discarded once `stop` is called.
+    pub fn with_timed_events(timed_events: Vec<(Duration, InputEvent)>) -> Self {
+        Self {
+            timed_events,
+            ..Self::default()
+        }
+    }
+
     pub fn with_start_error(mut self, message: impl Into<String>) -> Self {
         self.fail_start = Some(message.into());
         self
@@ -66,6 +132,27 @@ impl InputBackend for MockBackend {
                 .map_err(|err| AppError::Input(format!("failed to send mock event: {err}")))?;
         }
 
+        if !self.timed_events.is_empty() {
+            let (stop_tx, stop_rx) = bounded::<()>(1);
+            let timed_events = self.timed_events.clone();
+            thread::Builder::new()
+                .name("mock-input".to_string())
+                .spawn(move || {
+                    for (delay, event) in timed_events {
+                        match stop_rx.recv_timeout(delay) {
+                            Err(RecvTimeoutError::Timeout) => {
+                                if tx.send(event).is_err() {
+                                    return;
+                                }
+                            }
+                            _ => return,
+                        }
+                    }
+                })
+                .map_err(|err| AppError::Input(format!("failed to spawn mock emitter: {err}")))?;
+            self.emitter_stop_tx = Some(stop_tx);
+        }
+
         self.started = true;
         Ok(())
     }
@@ -75,16 +162,90 @@ impl InputBackend for MockBackend {
             return Err(AppError::Input(message));
         }
 
+        self.emitter_stop_tx = None;
         self.started = false;
         Ok(())
     }
+
+    fn name(&self) -> &'static str {
+        "mock"
+    }
+}
+
+/// Wraps a backend and keeps a copy of every event it sends, for tests that
+/// assert exactly what reached the receiver.
+///
+/// Events pass through a forwarding thread, which is recorded before each
+/// event is forwarded and exits once the wrapped backend drops its sender.
+#[derive(Debug)]
+pub struct RecordingBackend<B> {
+    inner: B,
+    recorded: Arc<Mutex<Vec<InputEvent>>>,
+}
+
+impl<B: InputBackend> RecordingBackend<B> {
+    /// Wraps `inner` with an empty recording.
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            recorded: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Shared list of the events forwarded so far, in send order.
+    pub fn recorded(&self) -> Arc<Mutex<Vec<InputEvent>>> {
+        Arc::clone(&self.recorded)
+    }
+
+    /// The wrapped backend.
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+}
+
+impl<B: InputBackend> InputBackend for RecordingBackend<B> {
+    fn start(&mut self, tx: Sender<InputEvent>) -> Result<(), AppError> {
+        let (inner_tx, inner_rx) = unbounded::<InputEvent>();
+        let recorded = Arc::clone(&self.recorded);
+        thread::Builder::new()
+            .name("recording-input".to_string())
+            .spawn(move || {
+                for event in inner_rx {
+                    recorded
+                        .lock()
+                        .unwrap_or_else(PoisonError::into_inner)
+                        .push(event.clone());
+                    if tx.send(event).is_err() {
+                        return;
+                    }
+                }
+            })
+            .map_err(|err| AppError::Input(format!("failed to spawn event recorder: {err}")))?;
+
+        self.inner.start(inner_tx)
+    }
+
+    fn stop(&mut self) -> Result<(), AppError> {
+        self.inner.stop()
+    }
+
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    fn set_status_sender(&mut self, status_tx: Sender<BackendStatus>) {
+        self.inner.set_status_sender(status_tx);
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+    use std::time::Duration;
+
     use crossbeam_channel::unbounded;
 
-    use super::{InputBackend, MockBackend, create_backend};
+    use super::{BackendKind, InputBackend, MockBackend, RecordingBackend, create_backend};
     use crate::types::{AppError, InputEvent};
 
     #[test]
@@ -117,6 +278,52 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_mock_backend_timed_events_arrive_in_order_and_stop_halts_pending() {
+        let (tx, rx) = unbounded();
+        let mut backend = MockBackend::with_timed_events(vec![
+            (
+                Duration::from_millis(5),
+                InputEvent::KeyPress("A".to_string()),
+            ),
+            (
+                Duration::from_millis(5),
+                InputEvent::KeyRelease("A".to_string()),
+            ),
+            (
+                Duration::from_secs(30),
+                InputEvent::KeyPress("B".to_string()),
+            ),
+        ]);
+
+        backend
+            .start(tx)
+            .expect("mock backend start should succeed");
+        assert!(
+            rx.try_recv().is_err(),
+            "timed events should not be sent synchronously"
+        );
+
+        let timeout = Duration::from_secs(2);
+        assert_eq!(
+            rx.recv_timeout(timeout).expect("first timed event"),
+            InputEvent::KeyPress("A".to_string())
+        );
+        assert_eq!(
+            rx.recv_timeout(timeout).expect("second timed event"),
+            InputEvent::KeyRelease("A".to_string())
+        );
+
+        backend.stop().expect("mock backend stop should succeed");
+
+        // The emitter exits and drops its sender instead of waiting out the last delay.
+        assert!(matches!(
+            rx.recv_timeout(timeout),
+            Err(crossbeam_channel::RecvTimeoutError::Disconnected)
+        ));
+        assert!(!backend.is_started());
+    }
+
     #[test]
     fn test_mock_backend_supports_start_and_stop_failures() {
         let (tx, _) = unbounded();
@@ -135,8 +342,72 @@ mod tests {
         assert!(matches!(stop_err, AppError::Input(message) if message == "stop failed"));
     }
 
+    #[test]
+    fn test_recording_backend_records_and_forwards_scripted_events() {
+        let scripted = vec![
+            InputEvent::KeyPress("Z".to_string()),
+            InputEvent::MousePress("Mouse1".to_string()),
+            InputEvent::KeyRelease("Z".to_string()),
+            InputEvent::MouseRelease("Mouse1".to_string()),
+        ];
+        let (tx, rx) = unbounded();
+        let mut backend = RecordingBackend::new(MockBackend::new(scripted.clone()));
+        let recorded = backend.recorded();
+
+        backend
+            .start(tx)
+            .expect("recording backend start should succeed");
+
+        let received: Vec<InputEvent> = (0..scripted.len())
+            .map(|_| {
+                rx.recv_timeout(Duration::from_secs(2))
+                    .expect("forwarded event")
+            })
+            .collect();
+        assert_eq!(received, scripted);
+        assert_eq!(*recorded.lock().expect("recorded lock"), scripted);
+        assert_eq!(backend.name(), "mock");
+        assert!(backend.inner().is_started());
+
+        backend
+            .stop()
+            .expect("recording backend stop should succeed");
+        assert!(!backend.inner().is_started());
+    }
+
+    #[test]
+    fn test_recording_backend_passes_through_start_errors() {
+        let (tx, _rx) = unbounded();
+        let mut backend = RecordingBackend::new(MockBackend::default().with_start_error("denied"));
+
+        let err = backend.start(tx).expect_err("start should fail");
+
+        assert!(matches!(err, AppError::Input(message) if message == "denied"));
+        assert!(backend.recorded().lock().expect("recorded lock").is_empty());
+    }
+
     #[test]
     fn test_create_backend_returns_platform_backend() {
-        let _backend = create_backend();
+        let backend = create_backend(BackendKind::Auto, None);
+
+        #[cfg(any(target_os = "windows", target_os = "macos", target_os = "linux"))]
+        assert_eq!(backend.name(), "rdev");
+        #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "linux")))]
+        assert_eq!(backend.name(), "mock");
+    }
+
+    #[test]
+    fn test_create_backend_honors_forced_kinds() {
+        assert_eq!(create_backend(BackendKind::Rdev, None).name(), "rdev");
+        assert_eq!(create_backend(BackendKind::Mock, None).name(), "mock");
+        assert_eq!(
+            create_backend(BackendKind::Replay, Some(Path::new("events.jsonl"))).name(),
+            "replay"
+        );
+    }
+
+    #[test]
+    fn test_create_backend_replay_without_file_falls_back_to_mock() {
+        assert_eq!(create_backend(BackendKind::Replay, None).name(), "mock");
     }
 }
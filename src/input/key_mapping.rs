@@ -64,11 +64,114 @@ pub enum KeyId {
     RControl,
     LAlt,
     RAlt,
+    /// Either shift key; only used in config, backends report a side.
+    Shift,
+    /// Either control key; only used in config, backends report a side.
+    Control,
+    /// Either alt key; only used in config, backends report a side.
+    Alt,
     Mouse1,
     Mouse2,
     Mouse3,
     Mouse4,
     Mouse5,
+    /// Raw code the backend reports for a key without a name above, written
+    /// `sc:0x1A` (or `sc:26`) in config.
+    Scancode(u32),
+}
+
+/// Config prefix of [`KeyId::Scancode`] names.
+const SCANCODE_PREFIX: &str = "SC:";
+
+impl KeyId {
+    /// Every named key, in declaration order; [`KeyId::Scancode`] is not listed.
+    pub fn all() -> &'static [KeyId] {
+        const ALL: &[KeyId] = &[
+            KeyId::A,
+            KeyId::B,
+            KeyId::C,
+            KeyId::D,
+            KeyId::E,
+            KeyId::F,
+            KeyId::G,
+            KeyId::H,
+            KeyId::I,
+            KeyId::J,
+            KeyId::K,
+            KeyId::L,
+            KeyId::M,
+            KeyId::N,
+            KeyId::O,
+            KeyId::P,
+            KeyId::Q,
+            KeyId::R,
+            KeyId::S,
+            KeyId::T,
+            KeyId::U,
+            KeyId::V,
+            KeyId::W,
+            KeyId::X,
+            KeyId::Y,
+            KeyId::Z,
+            KeyId::D0,
+            KeyId::D1,
+            KeyId::D2,
+            KeyId::D3,
+            KeyId::D4,
+            KeyId::D5,
+            KeyId::D6,
+            KeyId::D7,
+            KeyId::D8,
+            KeyId::D9,
+            KeyId::F1,
+            KeyId::F2,
+            KeyId::F3,
+            KeyId::F4,
+            KeyId::F5,
+            KeyId::F6,
+            KeyId::F7,
+            KeyId::F8,
+            KeyId::F9,
+            KeyId::F10,
+            KeyId::F11,
+            KeyId::F12,
+            KeyId::Space,
+            KeyId::Enter,
+            KeyId::Tab,
+            KeyId::Backspace,
+            KeyId::Escape,
+            KeyId::LShift,
+            KeyId::RShift,
+            KeyId::LControl,
+            KeyId::RControl,
+            KeyId::LAlt,
+            KeyId::RAlt,
+            KeyId::Shift,
+            KeyId::Control,
+            KeyId::Alt,
+            KeyId::Mouse1,
+            KeyId::Mouse2,
+            KeyId::Mouse3,
+            KeyId::Mouse4,
+            KeyId::Mouse5,
+        ];
+        ALL
+    }
+
+    /// Canonical config spelling of every key, in [`KeyId::all`] order.
+    pub fn all_names() -> Vec<String> {
+        Self::all().iter().map(ToString::to_string).collect()
+    }
+
+    /// Side-agnostic modifier matching this physical key, e.g. `Control` for `LControl`.
+    pub fn generic_modifier(self) -> Option<Self> {
+        match self {
+            Self::LShift | Self::RShift => Some(Self::Shift),
+            Self::LControl | Self::RControl => Some(Self::Control),
+            Self::LAlt | Self::RAlt => Some(Self::Alt),
+            _ => None,
+        }
+    }
 }
 
 impl FromStr for KeyId {
@@ -76,6 +179,11 @@ impl FromStr for KeyId {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let normalized = s.trim().to_ascii_uppercase();
+        if let Some(code) = normalized.strip_prefix(SCANCODE_PREFIX) {
+            return parse_scancode(code).map(Self::Scancode).ok_or_else(|| {
+                format!("invalid scancode in key name '{s}' (examples: sc:0x1A, sc:26)")
+            });
+        }
 
         match normalized.as_str() {
             "A" => Ok(Self::A),
@@ -137,6 +245,9 @@ impl FromStr for KeyId {
             "RCONTROL" | "RCTRL" => Ok(Self::RControl),
             "LALT" => Ok(Self::LAlt),
             "RALT" | "ALTGR" => Ok(Self::RAlt),
+            "SHIFT" => Ok(Self::Shift),
+            "CONTROL" | "CTRL" => Ok(Self::Control),
+            "ALT" => Ok(Self::Alt),
             "MOUSE1" => Ok(Self::Mouse1),
             "MOUSE2" => Ok(Self::Mouse2),
             "MOUSE3" => Ok(Self::Mouse3),
@@ -211,11 +322,15 @@ impl fmt::Display for KeyId {
             Self::RControl => "RControl",
             Self::LAlt => "LAlt",
             Self::RAlt => "RAlt",
+            Self::Shift => "Shift",
+            Self::Control => "Control",
+            Self::Alt => "Alt",
             Self::Mouse1 => "Mouse1",
             Self::Mouse2 => "Mouse2",
             Self::Mouse3 => "Mouse3",
             Self::Mouse4 => "Mouse4",
             Self::Mouse5 => "Mouse5",
+            Self::Scancode(code) => return write!(f, "sc:0x{code:X}"),
         };
 
         f.write_str(label)
@@ -353,15 +468,45 @@ impl From<KeyId> for rdev::Key {
             KeyId::RControl => rdev::Key::ControlRight,
             KeyId::LAlt => rdev::Key::Alt,
             KeyId::RAlt => rdev::Key::AltGr,
+            // Either-side modifiers map to their left key.
+            KeyId::Shift => rdev::Key::ShiftLeft,
+            KeyId::Control => rdev::Key::ControlLeft,
+            KeyId::Alt => rdev::Key::Alt,
             KeyId::Mouse1 => rdev::Key::Unknown(0xF001),
             KeyId::Mouse2 => rdev::Key::Unknown(0xF002),
             KeyId::Mouse3 => rdev::Key::Unknown(0xF003),
             KeyId::Mouse4 => rdev::Key::Unknown(0xF004),
             KeyId::Mouse5 => rdev::Key::Unknown(0xF005),
+            KeyId::Scancode(code) => rdev::Key::Unknown(code),
         }
     }
 }
 
+impl KeyId {
+    /// Maps `key` like `TryFrom<rdev::Key>`, passing raw codes of unnamed
+    /// keys (`Unknown` or a `RawKey` scancode) through as [`KeyId::Scancode`].
+    ///
+    /// rdev keys without a `KeyId`, such as `SemiColon` or `LeftBracket`, become
+    /// the event's `platform_code` as a scancode; a zero code means none is known.
+    pub fn from_rdev_key_or_scancode(key: rdev::Key, platform_code: u32) -> Option<Self> {
+        Self::try_from(key).ok().or(match key {
+            rdev::Key::Unknown(code) | rdev::Key::RawKey(rdev::RawKey::ScanCode(code)) => {
+                Some(Self::Scancode(code))
+            }
+            _ if platform_code != 0 => Some(Self::Scancode(platform_code)),
+            _ => None,
+        })
+    }
+}
+
+/// Parses the code of an `sc:` key name, in hex with a `0x` prefix or decimal.
+fn parse_scancode(code: &str) -> Option<u32> {
+    match code.strip_prefix("0X") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => code.parse().ok(),
+    }
+}
+
 impl TryFrom<rdev::Button> for KeyId {
     type Error = String;
 
@@ -399,6 +544,44 @@ mod tests {
     use rdev::{Button, Key};
     use std::str::FromStr;
 
+    #[test]
+    fn test_all_names_round_trip_through_from_str() {
+        let names = KeyId::all_names();
+
+        assert_eq!(names.len(), KeyId::all().len());
+        for (name, key) in names.iter().zip(KeyId::all()) {
+            assert_eq!(KeyId::from_str(name).as_ref(), Ok(key), "name {name}");
+        }
+    }
+
+    #[test]
+    fn test_all_lists_each_key_once() {
+        let unique: std::collections::HashSet<&KeyId> = KeyId::all().iter().collect();
+
+        assert_eq!(unique.len(), KeyId::all().len());
+        assert!(KeyId::all().contains(&KeyId::Mouse5));
+        assert!(KeyId::all().contains(&KeyId::Control));
+    }
+
+    #[test]
+    fn test_key_mapping_parses_generic_modifiers() {
+        assert_eq!(KeyId::from_str("Ctrl"), Ok(KeyId::Control));
+        assert_eq!(KeyId::from_str("control"), Ok(KeyId::Control));
+        assert_eq!(KeyId::from_str("Shift"), Ok(KeyId::Shift));
+        assert_eq!(KeyId::from_str("ALT"), Ok(KeyId::Alt));
+        assert_eq!(KeyId::Control.to_string(), "Control");
+    }
+
+    #[test]
+    fn test_generic_modifier_covers_both_sides() {
+        assert_eq!(KeyId::LControl.generic_modifier(), Some(KeyId::Control));
+        assert_eq!(KeyId::RControl.generic_modifier(), Some(KeyId::Control));
+        assert_eq!(KeyId::LShift.generic_modifier(), Some(KeyId::Shift));
+        assert_eq!(KeyId::RAlt.generic_modifier(), Some(KeyId::Alt));
+        assert_eq!(KeyId::Control.generic_modifier(), None);
+        assert_eq!(KeyId::A.generic_modifier(), None);
+    }
+
     #[test]
     fn test_key_mapping_parse_original_names() {
         let cases = [
@@ -494,6 +677,48 @@ mod tests {
         assert!(error.contains("unsupported rdev key"));
     }
 
+    #[test]
+    fn test_key_mapping_parses_scancode_names() {
+        assert_eq!(KeyId::from_str("sc:0x1A"), Ok(KeyId::Scancode(0x1A)));
+        assert_eq!(KeyId::from_str(" SC:0x1a "), Ok(KeyId::Scancode(0x1A)));
+        assert_eq!(KeyId::from_str("sc:26"), Ok(KeyId::Scancode(26)));
+        assert_eq!(KeyId::Scancode(26).to_string(), "sc:0x1A");
+        assert_eq!(
+            KeyId::from_str(&KeyId::Scancode(0xBA).to_string()),
+            Ok(KeyId::Scancode(0xBA))
+        );
+
+        for invalid in ["sc:", "sc:0x", "sc:zz", "sc:-1", "sc:0x100000000"] {
+            let error = KeyId::from_str(invalid).expect_err("invalid scancode");
+            assert!(error.contains("invalid scancode"), "{invalid}: {error}");
+        }
+    }
+
+    #[test]
+    fn test_from_rdev_key_or_scancode_passes_raw_codes_through() {
+        assert_eq!(
+            KeyId::from_rdev_key_or_scancode(Key::Unknown(999), 0),
+            Some(KeyId::Scancode(999))
+        );
+        assert_eq!(
+            KeyId::from_rdev_key_or_scancode(Key::RawKey(rdev::RawKey::ScanCode(0x1A)), 0),
+            Some(KeyId::Scancode(0x1A))
+        );
+        assert_eq!(
+            KeyId::from_rdev_key_or_scancode(Key::KeyZ, 0x34),
+            Some(KeyId::Z)
+        );
+        assert_eq!(
+            KeyId::from_rdev_key_or_scancode(Key::SemiColon, 0xBA),
+            Some(KeyId::Scancode(0xBA))
+        );
+        assert_eq!(
+            KeyId::from_rdev_key_or_scancode(Key::LeftBracket, 0xDB),
+            Some(KeyId::Scancode(0xDB))
+        );
+        assert_eq!(KeyId::from_rdev_key_or_scancode(Key::SemiColon, 0), None);
+    }
+
     #[test]
     fn test_key_mapping_into_rdev_round_trips_keyboard_keys() {
         let keys = [
@@ -3,7 +3,11 @@
 pub mod backend;
 pub mod key_mapping;
 pub mod rdev_backend;
+pub mod replay;
 
-pub use backend::{InputBackend, MockBackend, create_backend};
+pub use backend::{
+    BackendKind, BackendStatus, InputBackend, MockBackend, RecordingBackend, create_backend,
+};
 pub use key_mapping::KeyId;
 pub use rdev_backend::RdevBackend;
+pub use replay::{EventRecorder, ReplayBackend};
@@ -1,5 +1,6 @@
 //! rdev-based input backend for Windows, macOS, and X11.
 
+use std::collections::HashSet;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread::{self, JoinHandle};
@@ -7,7 +8,7 @@ use std::thread::{self, JoinHandle};
 use crossbeam_channel::Sender;
 use rdev::EventType;
 
-use crate::input::backend::InputBackend;
+use crate::input::backend::{BackendStatus, InputBackend};
 use crate::input::key_mapping::KeyId;
 use crate::types::{AppError, InputEvent};
 
@@ -18,6 +19,7 @@ const LISTENER_THREAD_NAME: &str = "rdev-input-listener";
 pub struct RdevBackend {
     running: Arc<AtomicBool>,
     listener_thread: Option<JoinHandle<()>>,
+    status_tx: Option<Sender<BackendStatus>>,
 }
 
 impl Default for RdevBackend {
@@ -31,6 +33,7 @@ impl RdevBackend {
         Self {
             running: Arc::new(AtomicBool::new(false)),
             listener_thread: None,
+            status_tx: None,
         }
     }
 
@@ -69,17 +72,21 @@ impl InputBackend for RdevBackend {
 
         let running = Arc::clone(&self.running);
         let listener_tx = tx;
+        let status_tx = self.status_tx.clone();
         let builder = thread::Builder::new().name(LISTENER_THREAD_NAME.to_string());
 
         let handle = builder
             .spawn(move || {
                 let callback_running = Arc::clone(&running);
+                let mut repeat_filter = RepeatFilter::default();
                 let callback = move |event: rdev::Event| {
                     if !callback_running.load(Ordering::Relaxed) {
                         return;
                     }
 
-                    if let Some(input_event) = map_rdev_event_to_input_event(event.event_type)
+                    if let Some(input_event) =
+                        map_rdev_event_to_input_event(event.event_type, event.platform_code)
+                        && repeat_filter.accept(&input_event)
                         && listener_tx.send(input_event).is_err()
                     {
                         callback_running.store(false, Ordering::SeqCst);
@@ -89,6 +96,11 @@ impl InputBackend for RdevBackend {
                 if let Err(err) = rdev::listen(callback) {
                     tracing::error!("rdev listen loop stopped: {err:#?}");
                     running.store(false, Ordering::SeqCst);
+                    if let Some(status_tx) = status_tx {
+                        let _ = status_tx.send(BackendStatus::Failed(format!(
+                            "rdev listen failed: {err:?}"
+                        )));
+                    }
                 }
             })
             .map_err(|err| {
@@ -106,19 +118,57 @@ impl InputBackend for RdevBackend {
         self.cleanup_finished_listener_thread();
         Ok(())
     }
+
+    fn name(&self) -> &'static str {
+        "rdev"
+    }
+
+    fn set_status_sender(&mut self, status_tx: Sender<BackendStatus>) {
+        self.status_tx = Some(status_tx);
+    }
 }
 
-/// Maps rdev `EventType` to `InputEvent`.
-fn map_rdev_event_to_input_event(event: EventType) -> Option<InputEvent> {
+/// Drops OS auto-repeat presses so a held key yields a single `KeyPress`.
+///
+/// Mouse buttons do not auto-repeat and always pass through.
+#[derive(Debug, Default)]
+struct RepeatFilter {
+    held_keys: HashSet<String>,
+}
+
+impl RepeatFilter {
+    /// Returns `false` for a `KeyPress` of a key that has not been released yet.
+    fn accept(&mut self, event: &InputEvent) -> bool {
+        match event {
+            InputEvent::KeyPress(key) => self.held_keys.insert(key.clone()),
+            InputEvent::KeyRelease(key) => {
+                self.held_keys.remove(key);
+                true
+            }
+            InputEvent::MousePress(_) | InputEvent::MouseRelease(_) => true,
+        }
+    }
+}
+
+/// Maps rdev `EventType` to `InputEvent`; `platform_code` names keys without a `KeyId`.
+fn map_rdev_event_to_input_event(event: EventType, platform_code: u32) -> Option<InputEvent> {
     match event {
         EventType::KeyPress(key) => {
-            let key_id = KeyId::try_from(key).ok()?;
+            let key_id = KeyId::from_rdev_key_or_scancode(key, platform_code)?;
             Some(InputEvent::KeyPress(key_id.to_string()))
         }
         EventType::KeyRelease(key) => {
-            let key_id = KeyId::try_from(key).ok()?;
+            let key_id = KeyId::from_rdev_key_or_scancode(key, platform_code)?;
             Some(InputEvent::KeyRelease(key_id.to_string()))
         }
+        EventType::ButtonPress(button) => {
+            let key_id = KeyId::try_from(button).ok()?;
+            Some(InputEvent::MousePress(key_id.to_string()))
+        }
+        EventType::ButtonRelease(button) => {
+            let key_id = KeyId::try_from(button).ok()?;
+            Some(InputEvent::MouseRelease(key_id.to_string()))
+        }
         _ => None,
     }
 }
@@ -134,6 +184,109 @@ mod tests {
         assert!(backend.listener_thread.is_none());
     }
 
+    #[test]
+    fn test_map_rdev_event_maps_mouse_buttons() {
+        let cases = [
+            (rdev::Button::Left, "Mouse1"),
+            (rdev::Button::Right, "Mouse2"),
+            (rdev::Button::Middle, "Mouse3"),
+        ];
+
+        for (button, name) in cases {
+            assert_eq!(
+                map_rdev_event_to_input_event(EventType::ButtonPress(button), 0),
+                Some(InputEvent::MousePress(name.to_string()))
+            );
+            assert_eq!(
+                map_rdev_event_to_input_event(EventType::ButtonRelease(button), 0),
+                Some(InputEvent::MouseRelease(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeat_filter_emits_one_press_per_hold() {
+        let mut filter = RepeatFilter::default();
+        let events = [
+            EventType::KeyPress(rdev::Key::KeyZ),
+            EventType::KeyPress(rdev::Key::KeyZ),
+            EventType::KeyPress(rdev::Key::KeyZ),
+            EventType::KeyRelease(rdev::Key::KeyZ),
+            EventType::KeyPress(rdev::Key::KeyZ),
+        ];
+
+        let emitted: Vec<InputEvent> = events
+            .into_iter()
+            .filter_map(|event| map_rdev_event_to_input_event(event, 0))
+            .filter(|event| filter.accept(event))
+            .collect();
+
+        assert_eq!(
+            emitted,
+            vec![
+                InputEvent::KeyPress("Z".to_string()),
+                InputEvent::KeyRelease("Z".to_string()),
+                InputEvent::KeyPress("Z".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_map_rdev_event_passes_unmapped_raw_codes_as_scancodes() {
+        assert_eq!(
+            map_rdev_event_to_input_event(EventType::KeyPress(rdev::Key::Unknown(0x1A)), 0),
+            Some(InputEvent::KeyPress("sc:0x1A".to_string()))
+        );
+        assert_eq!(
+            map_rdev_event_to_input_event(
+                EventType::KeyRelease(rdev::Key::RawKey(rdev::RawKey::ScanCode(0x1A))),
+                0
+            ),
+            Some(InputEvent::KeyRelease("sc:0x1A".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_map_rdev_event_names_punctuation_keys_by_platform_code() {
+        for (key, code, name) in [
+            (rdev::Key::SemiColon, 0x2F, "sc:0x2F"),
+            (rdev::Key::LeftBracket, 0x22, "sc:0x22"),
+            (rdev::Key::RightBracket, 0x23, "sc:0x23"),
+        ] {
+            assert_eq!(
+                map_rdev_event_to_input_event(EventType::KeyPress(key), code),
+                Some(InputEvent::KeyPress(name.to_string()))
+            );
+            assert_eq!(
+                map_rdev_event_to_input_event(EventType::KeyRelease(key), code),
+                Some(InputEvent::KeyRelease(name.to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn test_repeat_filter_passes_mouse_presses() {
+        let mut filter = RepeatFilter::default();
+        let press = InputEvent::MousePress("Mouse1".to_string());
+
+        assert!(filter.accept(&press));
+        assert!(filter.accept(&press));
+    }
+
+    #[test]
+    fn test_map_rdev_event_ignores_unsupported_mouse_button() {
+        let button = rdev::Button::Unknown(9);
+
+        assert_eq!(
+            map_rdev_event_to_input_event(EventType::ButtonPress(button), 0),
+            None
+        );
+        assert_eq!(
+            map_rdev_event_to_input_event(EventType::ButtonRelease(button), 0),
+            None
+        );
+    }
+
     #[test]
     fn test_rdev_backend_default() {
         let backend = RdevBackend::default();
@@ -0,0 +1,272 @@
+//! Input event recording and replay.
+//!
+//! Recordings are JSON lines, one [`RecordedEvent`] per line, with each
+//! event's offset in milliseconds from the start of the recording.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde::{Deserialize, Serialize};
+
+use crate::input::{InputBackend, MockBackend};
+use crate::types::{AppError, InputEvent};
+
+/// One line of a recording.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedEvent {
+    /// Milliseconds since the recording started.
+    pub offset_ms: u64,
+    pub event: InputEvent,
+}
+
+/// Appends input events to a recording file as they arrive.
+#[derive(Debug)]
+pub struct EventRecorder {
+    writer: BufWriter<File>,
+    started_at: Instant,
+}
+
+impl EventRecorder {
+    /// Creates (or truncates) the recording at `path`; offsets count from `started_at`.
+    pub fn create(path: &Path, started_at: Instant) -> Result<Self, AppError> {
+        let file = File::create(path)?;
+        Ok(Self {
+            writer: BufWriter::new(file),
+            started_at,
+        })
+    }
+
+    /// Writes `event` as received at `now` and flushes, so a crash keeps earlier events.
+    pub fn record(&mut self, event: &InputEvent, now: Instant) -> Result<(), AppError> {
+        let offset = now.saturating_duration_since(self.started_at);
+        let line = serde_json::to_string(&RecordedEvent {
+            offset_ms: u64::try_from(offset.as_millis()).unwrap_or(u64::MAX),
+            event: event.clone(),
+        })
+        .map_err(|err| AppError::Input(format!("failed to encode recorded event: {err}")))?;
+
+        writeln!(self.writer, "{line}")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Reads a recording, skipping blank lines.
+pub fn read_event_log(path: &Path) -> Result<Vec<RecordedEvent>, AppError> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut events = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let event = serde_json::from_str(&line).map_err(|err| {
+            AppError::Input(format!(
+                "invalid event on line {} of '{}': {err}",
+                index + 1,
+                path.display()
+            ))
+        })?;
+        events.push(event);
+    }
+
+    Ok(events)
+}
+
+/// Converts absolute offsets into per-event delays, scaled by `time_scale`.
+///
+/// Out-of-order offsets are replayed back to back instead of waiting.
+fn replay_delays(events: Vec<RecordedEvent>, time_scale: f32) -> Vec<(Duration, InputEvent)> {
+    let mut previous_ms = 0;
+    events
+        .into_iter()
+        .map(|recorded| {
+            let gap_ms = recorded.offset_ms.saturating_sub(previous_ms);
+            previous_ms = previous_ms.max(recorded.offset_ms);
+            let scaled_micros = gap_ms as f64 * 1000.0 * f64::from(time_scale.max(0.0));
+            let delay = Duration::from_micros(scaled_micros.round() as u64);
+            (delay, recorded.event)
+        })
+        .collect()
+}
+
+/// Backend that re-emits a recording with its original timing.
+#[derive(Debug, Clone)]
+pub struct ReplayBackend {
+    path: PathBuf,
+    time_scale: f32,
+    player: Option<MockBackend>,
+}
+
+impl ReplayBackend {
+    /// Creates a backend that replays the recording at `path` in real time.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            time_scale: 1.0,
+            player: None,
+        }
+    }
+
+    /// Multiplies every recorded gap; values below 1 replay faster.
+    pub fn with_time_scale(mut self, time_scale: f32) -> Self {
+        self.time_scale = time_scale;
+        self
+    }
+}
+
+impl InputBackend for ReplayBackend {
+    fn start(&mut self, tx: Sender<InputEvent>) -> Result<(), AppError> {
+        let events = read_event_log(&self.path)?;
+        let mut player = MockBackend::with_timed_events(replay_delays(events, self.time_scale));
+        player.start(tx)?;
+        self.player = Some(player);
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), AppError> {
+        match self.player.take() {
+            Some(mut player) => player.stop(),
+            None => Ok(()),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crossbeam_channel::unbounded;
+
+    use super::{EventRecorder, RecordedEvent, ReplayBackend, read_event_log, replay_delays};
+    use crate::input::InputBackend;
+    use crate::types::InputEvent;
+
+    fn sample_events() -> Vec<InputEvent> {
+        vec![
+            InputEvent::KeyPress("Z".to_string()),
+            InputEvent::MousePress("Mouse1".to_string()),
+            InputEvent::KeyRelease("Z".to_string()),
+            InputEvent::MouseRelease("Mouse1".to_string()),
+        ]
+    }
+
+    #[test]
+    fn test_recorder_writes_json_lines_with_relative_offsets() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("events.jsonl");
+        let start = Instant::now();
+
+        let mut recorder = EventRecorder::create(&path, start).expect("recorder");
+        recorder
+            .record(&InputEvent::KeyPress("Z".to_string()), start)
+            .expect("record");
+        recorder
+            .record(
+                &InputEvent::KeyRelease("Z".to_string()),
+                start + Duration::from_millis(120),
+            )
+            .expect("record");
+
+        let log = read_event_log(&path).expect("log should read");
+        assert_eq!(
+            log,
+            vec![
+                RecordedEvent {
+                    offset_ms: 0,
+                    event: InputEvent::KeyPress("Z".to_string()),
+                },
+                RecordedEvent {
+                    offset_ms: 120,
+                    event: InputEvent::KeyRelease("Z".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_event_log_reports_invalid_line() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("events.jsonl");
+        std::fs::write(
+            &path,
+            "\n{\"offsetMs\":0,\"event\":{\"KeyPress\":\"Z\"}}\nnot json\n",
+        )
+        .expect("write log");
+
+        let err = read_event_log(&path).expect_err("bad line should fail");
+        assert!(
+            err.to_string().contains("line 3"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn test_replay_delays_scale_gaps_between_offsets() {
+        let events = [0, 100, 100, 40, 300]
+            .into_iter()
+            .map(|offset_ms| RecordedEvent {
+                offset_ms,
+                event: InputEvent::KeyPress("Z".to_string()),
+            })
+            .collect();
+
+        let delays: Vec<Duration> = replay_delays(events, 0.5)
+            .into_iter()
+            .map(|(delay, _)| delay)
+            .collect();
+
+        assert_eq!(
+            delays,
+            [0, 50, 0, 0, 100].map(Duration::from_millis).to_vec()
+        );
+    }
+
+    #[test]
+    fn test_record_then_replay_preserves_order_and_count() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("events.jsonl");
+        let start = Instant::now();
+
+        let mut recorder = EventRecorder::create(&path, start).expect("recorder");
+        for (index, event) in sample_events().iter().enumerate() {
+            let at = start + Duration::from_millis(200 * index as u64);
+            recorder.record(event, at).expect("record");
+        }
+        drop(recorder);
+
+        let (tx, rx) = unbounded();
+        let mut backend = ReplayBackend::new(&path).with_time_scale(0.01);
+        backend.start(tx).expect("replay should start");
+
+        let replayed: Vec<InputEvent> = (0..sample_events().len())
+            .map(|_| {
+                rx.recv_timeout(Duration::from_secs(2))
+                    .expect("replayed event")
+            })
+            .collect();
+        assert_eq!(replayed, sample_events());
+
+        backend.stop().expect("replay should stop");
+        assert!(rx.recv_timeout(Duration::from_millis(100)).is_err());
+    }
+
+    #[test]
+    fn test_replay_backend_start_fails_for_missing_file() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let (tx, _rx) = unbounded();
+        let mut backend = ReplayBackend::new(dir.path().join("missing.jsonl"));
+
+        assert!(backend.start(tx).is_err());
+    }
+}
@@ -5,18 +5,31 @@
 //!
 //! # Formula
 //!
-//! Window width = margin + Σ(column_width for each key)
-//! Column width = key_size * size_multiplier + outline_thickness * 2 + margin
+//! Window width = 2 * edge_padding + Σ(spacer_before + column_width for each key) - key_spacing
+//! Column width = key_size * size_multiplier + outline_thickness * 2 + key_spacing
+//!
+//! `edge_padding` and `key_spacing` both default to `margin`.
+//!
+//! With `keyAxis = "vertical"` the same sum becomes the window height and
+//! the configured `height` becomes the window width.
+//!
+//! Layout runs in unscaled units; only [`calculate_window_size`] applies
+//! `uiScale`, and the renderer scales the finished draw list to match.
 
-use crate::types::AppConfig;
+use egui::{Rect, Vec2};
+
+use crate::types::{AppConfig, KeyAxis, KeyGroup};
 
 /// Calculate the total window width required to display all keys.
 ///
 /// # Formula
 ///
-/// `width = margin + Σ(key_size * key.size + outline_thickness * 2 + margin)`
+/// `width = edge + Σ(spacer_before + key_size * key.size + outline_thickness * 2 + spacing) - spacing + edge`
 ///
-/// Each key occupies: `(key_size * size_multiplier) + (outline * 2) + margin`
+/// Each key occupies: `spacer_before + (key_size * size_multiplier) + (outline * 2) + spacing`;
+/// the last key's trailing spacing is replaced by the right edge padding.
+/// `edge` and `spacing` are [`AppConfig::effective_edge_padding`] and
+/// [`AppConfig::effective_key_spacing`].
 ///
 /// # Arguments
 ///
@@ -29,18 +42,25 @@ use crate::types::AppConfig;
 /// # Example
 ///
 /// With 2 keys of size 1.0, key_size=70, margin=25, outline=5:
-/// width = 25 + (70+10+25)*2 = 235
+/// width = 25 + (70+10+25)*2 - 25 + 25 = 235
 pub fn calculate_window_width(config: &AppConfig) -> f32 {
-    let mut total = config.margin; // Initial left margin
+    let edge_padding = config.effective_edge_padding();
+    let key_spacing = config.effective_key_spacing();
+    let mut total = edge_padding; // Left edge padding
 
     for key in &config.keys {
         let column_width = calculate_column_width(
             config.key_size,
             key.size,
             config.outline_thickness,
-            config.margin,
+            key_spacing,
         );
-        total += column_width;
+        total += key.spacer_before + column_width;
+    }
+
+    if !config.keys.is_empty() {
+        // The last column's trailing spacing becomes the right edge padding.
+        total += edge_padding - key_spacing;
     }
 
     total
@@ -80,18 +100,43 @@ pub fn calculate_column_width(
 /// # Returns
 ///
 /// Vector of x-coordinates for each key (left edge position)
-/// Positions are non-overlapping and in order
+/// Positions are non-overlapping and in order; a key's `spacer_before`
+/// shifts it and every later key to the right.
+///
+/// With `reverse_key_order` the layout is mirrored within the same window
+/// width: the first key sits at the right edge and `spacer_before` shifts
+/// later keys to the left.
 pub fn calculate_key_x_positions(config: &AppConfig) -> Vec<f32> {
+    let positions = calculate_forward_key_x_positions(config);
+    if !config.reverse_key_order {
+        return positions;
+    }
+
+    let width = calculate_window_width(config);
+    positions
+        .into_iter()
+        .zip(&config.keys)
+        .map(|(x, key)| {
+            let occupied =
+                calculate_column_width(config.key_size, key.size, config.outline_thickness, 0.0);
+            width - x - occupied
+        })
+        .collect()
+}
+
+fn calculate_forward_key_x_positions(config: &AppConfig) -> Vec<f32> {
     let mut positions = Vec::with_capacity(config.keys.len());
-    let mut current_x = config.margin;
+    let key_spacing = config.effective_key_spacing();
+    let mut current_x = config.effective_edge_padding();
 
     for key in &config.keys {
+        current_x += key.spacer_before;
         positions.push(current_x);
         let column_width = calculate_column_width(
             config.key_size,
             key.size,
             config.outline_thickness,
-            config.margin,
+            key_spacing,
         );
         current_x += column_width;
     }
@@ -99,6 +144,79 @@ pub fn calculate_key_x_positions(config: &AppConfig) -> Vec<f32> {
     positions
 }
 
+/// Calculate the offset of each key along the configured key axis.
+///
+/// The offsets are the same for both axes: left edges for
+/// [`KeyAxis::Horizontal`], top edges for [`KeyAxis::Vertical`].
+pub fn calculate_key_positions(config: &AppConfig) -> Vec<f32> {
+    calculate_key_x_positions(config)
+}
+
+/// Indices into `config.keys` of `group`'s configured members, in key order.
+pub fn group_member_indices(config: &AppConfig, group: &KeyGroup) -> Vec<usize> {
+    config
+        .keys
+        .iter()
+        .enumerate()
+        .filter(|(_, key)| group.keys.contains(&key.key_name))
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Span `(start, end)` along the key axis covered by the key boxes at
+/// `member_indices`, given the offsets from [`calculate_key_positions`].
+///
+/// A key box starts `outline_thickness` into its column and is
+/// `key_size * size` long. Returns `None` when no index has a position.
+pub fn calculate_group_span(
+    config: &AppConfig,
+    positions: &[f32],
+    member_indices: &[usize],
+) -> Option<(f32, f32)> {
+    member_indices
+        .iter()
+        .filter_map(|&index| {
+            let key = config.keys.get(index)?;
+            let start = positions.get(index)? + config.outline_thickness;
+            Some((start, start + config.key_size * key.size))
+        })
+        .reduce(|(start, end), (key_start, key_end)| (start.min(key_start), end.max(key_end)))
+}
+
+/// Calculate the window size `[width, height]` for the configured key axis.
+///
+/// The key row length from [`calculate_window_width`] runs along the key
+/// axis and `config.height` is the bar track across it. Both are multiplied
+/// by `config.ui_scale`.
+pub fn calculate_window_size(config: &AppConfig) -> [f32; 2] {
+    let key_extent = calculate_window_width(config) * config.ui_scale;
+    let height = config.height * config.ui_scale;
+    match config.key_axis {
+        KeyAxis::Horizontal => [key_extent, height],
+        KeyAxis::Vertical => [height, key_extent],
+    }
+}
+
+/// Clamp a window size to `maxWindowWidth` and `maxWindowHeight`.
+///
+/// Unset limits leave that dimension unchanged; content past a limit is clipped.
+pub fn clamp_window_size(size: [f32; 2], config: &AppConfig) -> [f32; 2] {
+    let [width, height] = size;
+    [
+        config.max_window_width.map_or(width, |max| width.min(max)),
+        config
+            .max_window_height
+            .map_or(height, |max| height.min(max)),
+    ]
+}
+
+/// Resizes `old` to `new_size` while keeping its center fixed.
+///
+/// Used to reposition the window when a reload changes its dimensions.
+pub fn recenter_rect(old: Rect, new_size: Vec2) -> Rect {
+    Rect::from_center_size(old.center(), new_size)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -152,12 +270,7 @@ mod tests {
     #[test]
     fn test_calculate_window_width_single_key() {
         let config = AppConfig {
-            keys: vec![KeyConfig {
-                key_name: "Z".to_string(),
-                display_name: "Z".to_string(),
-                color: Color::from_rgba_u8(255, 0, 0, 255),
-                size: 1.0,
-            }],
+            keys: vec![KeyConfig::new("Z", Color::from_rgba_u8(255, 0, 0, 255))],
             ..AppConfig::default()
         };
 
@@ -173,23 +286,14 @@ mod tests {
             margin: 25.0,
             outline_thickness: 5.0,
             keys: vec![
+                KeyConfig::new("Z", Color::black()), // width: 105
                 KeyConfig {
-                    key_name: "Z".to_string(),
-                    display_name: "Z".to_string(),
-                    color: Color::black(),
-                    size: 1.0, // width: 105
-                },
-                KeyConfig {
-                    key_name: "X".to_string(),
-                    display_name: "X".to_string(),
-                    color: Color::black(),
                     size: 1.5, // width: (70*1.5) + 10 + 25 = 140
+                    ..KeyConfig::new("X", Color::black())
                 },
                 KeyConfig {
-                    key_name: "C".to_string(),
-                    display_name: "C".to_string(),
-                    color: Color::black(),
                     size: 2.0, // width: (70*2.0) + 10 + 25 = 175
+                    ..KeyConfig::new("C", Color::black())
                 },
             ],
             ..AppConfig::default()
@@ -207,18 +311,8 @@ mod tests {
             margin: 10.0,
             outline_thickness: 3.0,
             keys: vec![
-                KeyConfig {
-                    key_name: "A".to_string(),
-                    display_name: "A".to_string(),
-                    color: Color::black(),
-                    size: 1.0, // width: (50*1.0) + (3*2) + 10 = 66
-                },
-                KeyConfig {
-                    key_name: "B".to_string(),
-                    display_name: "B".to_string(),
-                    color: Color::black(),
-                    size: 1.0, // width: 66
-                },
+                KeyConfig::new("A", Color::black()), // width: (50*1.0) + (3*2) + 10 = 66
+                KeyConfig::new("B", Color::black()), // width: 66
             ],
             ..AppConfig::default()
         };
@@ -228,6 +322,59 @@ mod tests {
         assert_f32_eq(width, 142.0, "window width with custom margins/outline");
     }
 
+    #[test]
+    fn test_edge_padding_moves_first_key_and_widens_both_edges() {
+        let config = AppConfig {
+            edge_padding: Some(5.0),
+            ..AppConfig::default()
+        };
+
+        let positions = calculate_key_x_positions(&config);
+        let width = calculate_window_width(&config);
+
+        // Keys keep the 25px margin between them: X starts 105 after Z.
+        assert_f32_eq(positions[0], 5.0, "first key starts at edge padding");
+        assert_f32_eq(positions[1], 110.0, "second key follows the key spacing");
+        // width = 5 + 80 + 25 + 80 + 5 = 195
+        assert_f32_eq(width, 195.0, "edge padding replaces margin at both edges");
+    }
+
+    #[test]
+    fn test_key_spacing_changes_only_the_gap_between_keys() {
+        let config = AppConfig {
+            key_spacing: Some(2.0),
+            ..AppConfig::default()
+        };
+
+        let positions = calculate_key_x_positions(&config);
+        let width = calculate_window_width(&config);
+
+        assert_f32_eq(positions[0], 25.0, "first key still starts at margin");
+        assert_f32_eq(positions[1], 107.0, "second key is 2px after the first box");
+        // width = 25 + 80 + 2 + 80 + 25 = 212
+        assert_f32_eq(width, 212.0, "edges keep the margin");
+    }
+
+    #[test]
+    fn test_edge_padding_and_key_spacing_unset_match_margin() {
+        let margin_only = AppConfig::default();
+        let explicit = AppConfig {
+            edge_padding: Some(margin_only.margin),
+            key_spacing: Some(margin_only.margin),
+            ..AppConfig::default()
+        };
+
+        assert_eq!(
+            calculate_key_x_positions(&margin_only),
+            calculate_key_x_positions(&explicit)
+        );
+        assert_f32_eq(
+            calculate_window_width(&margin_only),
+            calculate_window_width(&explicit),
+            "unset fields fall back to margin",
+        );
+    }
+
     #[test]
     fn test_calculate_key_x_positions_two_keys() {
         let config = AppConfig::default();
@@ -242,12 +389,7 @@ mod tests {
     #[test]
     fn test_calculate_key_x_positions_single_key() {
         let config = AppConfig {
-            keys: vec![KeyConfig {
-                key_name: "Z".to_string(),
-                display_name: "Z".to_string(),
-                color: Color::black(),
-                size: 1.0,
-            }],
+            keys: vec![KeyConfig::new("Z", Color::black())],
             ..AppConfig::default()
         };
 
@@ -264,24 +406,9 @@ mod tests {
             margin: 25.0,
             outline_thickness: 5.0,
             keys: vec![
-                KeyConfig {
-                    key_name: "Z".to_string(),
-                    display_name: "Z".to_string(),
-                    color: Color::black(),
-                    size: 1.0,
-                },
-                KeyConfig {
-                    key_name: "X".to_string(),
-                    display_name: "X".to_string(),
-                    color: Color::black(),
-                    size: 1.0,
-                },
-                KeyConfig {
-                    key_name: "C".to_string(),
-                    display_name: "C".to_string(),
-                    color: Color::black(),
-                    size: 1.0,
-                },
+                KeyConfig::new("Z", Color::black()),
+                KeyConfig::new("X", Color::black()),
+                KeyConfig::new("C", Color::black()),
             ],
             ..AppConfig::default()
         };
@@ -315,17 +442,10 @@ mod tests {
             margin: 25.0,
             outline_thickness: 5.0,
             keys: vec![
+                KeyConfig::new("Z", Color::black()), // column_width = 105
                 KeyConfig {
-                    key_name: "Z".to_string(),
-                    display_name: "Z".to_string(),
-                    color: Color::black(),
-                    size: 1.0, // column_width = 105
-                },
-                KeyConfig {
-                    key_name: "X".to_string(),
-                    display_name: "X".to_string(),
-                    color: Color::black(),
                     size: 1.5, // column_width = 140
+                    ..KeyConfig::new("X", Color::black())
                 },
             ],
             ..AppConfig::default()
@@ -339,6 +459,44 @@ mod tests {
         assert_f32_eq(positions[1], 130.0, "second key starts after first");
     }
 
+    fn three_key_config(reverse_key_order: bool) -> AppConfig {
+        let mut config = AppConfig {
+            reverse_key_order,
+            ..AppConfig::default()
+        };
+        let mut x = config.keys[1].clone();
+        x.size = 1.5;
+        x.spacer_before = 10.0;
+        let mut c = config.keys[0].clone();
+        c.key_name = "C".to_string();
+        config.keys = vec![config.keys[0].clone(), x, c];
+        config
+    }
+
+    #[test]
+    fn test_calculate_key_x_positions_reverse_key_order_mirrors_three_keys() {
+        let forward = three_key_config(false);
+        let reversed = three_key_config(true);
+
+        let forward_positions = calculate_key_x_positions(&forward);
+        let reversed_positions = calculate_key_x_positions(&reversed);
+
+        // Forward: Z at 25, X after Z's 105px column and the 10px spacer, C after X's 140px.
+        assert_f32_eq(forward_positions[0], 25.0, "forward Z");
+        assert_f32_eq(forward_positions[1], 140.0, "forward X");
+        assert_f32_eq(forward_positions[2], 280.0, "forward C");
+        // Reversed: each key box mirrored within the 385px window, config order kept.
+        assert_f32_eq(reversed_positions[0], 280.0, "reversed Z at the right edge");
+        assert_f32_eq(reversed_positions[1], 130.0, "reversed X");
+        assert_f32_eq(reversed_positions[2], 25.0, "reversed C at the left edge");
+        assert_f32_eq(calculate_window_width(&forward), 385.0, "forward width");
+        assert_f32_eq(
+            calculate_window_width(&reversed),
+            calculate_window_width(&forward),
+            "reversing keeps the width",
+        );
+    }
+
     #[test]
     fn test_calculate_key_x_positions_empty_keys() {
         let config = AppConfig {
@@ -349,4 +507,208 @@ mod tests {
         let positions = calculate_key_x_positions(&config);
         assert_eq!(positions.len(), 0, "should have no positions for no keys");
     }
+
+    #[test]
+    fn test_spacer_before_shifts_later_keys_and_widens_window() {
+        let mut config = AppConfig::default();
+        let width_without_spacer = calculate_window_width(&config);
+        config.keys[1].spacer_before = 40.0;
+
+        let positions = calculate_key_x_positions(&config);
+
+        // Z at margin; X after Z's 105px column plus the 40px spacer.
+        assert_f32_eq(positions[0], 25.0, "first key unaffected");
+        assert_f32_eq(positions[1], 25.0 + 105.0 + 40.0, "second key shifted");
+        assert_f32_eq(
+            calculate_window_width(&config),
+            width_without_spacer + 40.0,
+            "window width grows by spacer",
+        );
+    }
+
+    #[test]
+    fn test_calculate_window_size_horizontal_uses_width_then_height() {
+        let config = AppConfig::default();
+
+        let [width, height] = calculate_window_size(&config);
+
+        assert_f32_eq(width, 235.0, "horizontal width is the key row");
+        assert_f32_eq(height, 700.0, "horizontal height is the track");
+    }
+
+    #[test]
+    fn test_calculate_window_size_ui_scale_two_doubles_both_dimensions() {
+        let config = AppConfig {
+            ui_scale: 2.0,
+            ..AppConfig::default()
+        };
+
+        let [width, height] = calculate_window_size(&config);
+
+        // 2 * (25 + (70 + 10 + 25) * 2 - 25 + 25) = 470
+        assert_f32_eq(width, 470.0, "scaled key row");
+        assert_f32_eq(height, 1400.0, "scaled track");
+        assert_f32_eq(
+            calculate_window_width(&config),
+            235.0,
+            "layout stays unscaled",
+        );
+    }
+
+    #[test]
+    fn test_calculate_window_size_ui_scale_matches_scaled_size_fields() {
+        let scaled = AppConfig {
+            ui_scale: 2.0,
+            key_axis: KeyAxis::Vertical,
+            ..AppConfig::default()
+        };
+        let mut doubled = AppConfig {
+            key_axis: KeyAxis::Vertical,
+            key_size: 140.0,
+            margin: 50.0,
+            outline_thickness: 10.0,
+            height: 1400.0,
+            ..AppConfig::default()
+        };
+        doubled.keys[0].spacer_before = 0.0;
+
+        assert_eq!(
+            calculate_window_size(&scaled),
+            calculate_window_size(&doubled)
+        );
+    }
+
+    #[test]
+    fn test_clamp_window_size_limits_only_overflowing_dimensions() {
+        let config = AppConfig {
+            max_window_width: Some(200.0),
+            max_window_height: Some(800.0),
+            ..AppConfig::default()
+        };
+
+        let [width, height] = clamp_window_size(calculate_window_size(&config), &config);
+
+        assert_f32_eq(width, 200.0, "wide key row is clamped");
+        assert_f32_eq(height, 700.0, "height under the limit is kept");
+    }
+
+    #[test]
+    fn test_clamp_window_size_many_keys_stays_within_limits() {
+        let mut config = AppConfig {
+            max_window_width: Some(1920.0),
+            max_window_height: Some(600.0),
+            ..AppConfig::default()
+        };
+        let key = config.keys[0].clone();
+        config.keys = vec![key; 100];
+        let unclamped = calculate_window_size(&config);
+        assert!(unclamped[0] > 1920.0);
+
+        let [width, height] = clamp_window_size(unclamped, &config);
+
+        assert_f32_eq(width, 1920.0, "width is clamped");
+        assert_f32_eq(height, 600.0, "height is clamped");
+    }
+
+    #[test]
+    fn test_clamp_window_size_without_limits_is_unchanged() {
+        let config = AppConfig::default();
+        let size = calculate_window_size(&config);
+
+        assert_eq!(clamp_window_size(size, &config), size);
+    }
+
+    #[test]
+    fn test_calculate_window_size_vertical_mixed_sizes_swaps_roles() {
+        let mut config = AppConfig {
+            key_axis: KeyAxis::Vertical,
+            height: 500.0,
+            ..AppConfig::default()
+        };
+        config.keys[1].size = 1.5;
+
+        let [width, height] = calculate_window_size(&config);
+
+        // height = 25 + 105 + (70*1.5 + 10 + 25) = 270
+        assert_f32_eq(width, 500.0, "vertical width is the track");
+        assert_f32_eq(height, 270.0, "vertical height is the key column");
+    }
+
+    #[test]
+    fn test_calculate_key_positions_vertical_mixed_sizes_stack_top_to_bottom() {
+        let mut config = AppConfig {
+            key_axis: KeyAxis::Vertical,
+            ..AppConfig::default()
+        };
+        config.keys[0].size = 2.0;
+        config.keys[1].spacer_before = 10.0;
+
+        let positions = calculate_key_positions(&config);
+
+        assert_eq!(positions.len(), 2, "should have 2 positions");
+        assert_f32_eq(positions[0], 25.0, "first key top at margin");
+        // First row height = 70*2 + 10 + 25 = 175, plus a 10px spacer.
+        assert_f32_eq(positions[1], 210.0, "second key below the first");
+        assert_eq!(positions, calculate_key_x_positions(&config));
+    }
+
+    #[test]
+    fn test_calculate_group_span_covers_member_key_boxes() {
+        // Default keys: columns at x=25 and x=130, boxes inset by the 5px outline, 70px wide.
+        let config = AppConfig::default();
+        let positions = calculate_key_positions(&config);
+
+        let (start, end) = calculate_group_span(&config, &positions, &[0, 1]).expect("span");
+        assert_f32_eq(start, 30.0, "group starts at the first box");
+        assert_f32_eq(end, 205.0, "group ends at the last box");
+
+        let (start, end) = calculate_group_span(&config, &positions, &[1]).expect("span");
+        assert_f32_eq(start, 135.0, "single member start");
+        assert_f32_eq(end, 205.0, "single member end");
+    }
+
+    #[test]
+    fn test_calculate_group_span_without_known_members_is_none() {
+        let config = AppConfig::default();
+        let positions = calculate_key_positions(&config);
+
+        assert_eq!(calculate_group_span(&config, &positions, &[]), None);
+        assert_eq!(calculate_group_span(&config, &positions, &[7]), None);
+    }
+
+    #[test]
+    fn test_group_member_indices_follow_key_order_and_skip_unknown_keys() {
+        let config = AppConfig::default();
+        let group = crate::types::KeyGroup {
+            label: "taps".to_string(),
+            color: Color::from_rgba_u8(255, 255, 255, 255),
+            keys: vec!["X".to_string(), "Missing".to_string(), "Z".to_string()],
+        };
+
+        assert_eq!(group_member_indices(&config, &group), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_recenter_rect_keeps_center_when_growing_and_shrinking() {
+        let old = Rect::from_min_size(egui::pos2(100.0, 50.0), egui::vec2(235.0, 700.0));
+
+        let grown = recenter_rect(old, egui::vec2(340.0, 800.0));
+        assert_eq!(grown.center(), old.center());
+        assert_f32_eq(grown.min.x, 47.5, "grown left edge");
+        assert_f32_eq(grown.min.y, 0.0, "grown top edge");
+
+        let shrunk = recenter_rect(old, egui::vec2(135.0, 600.0));
+        assert_eq!(shrunk.center(), old.center());
+        assert_f32_eq(shrunk.min.x, 150.0, "shrunk left edge");
+        assert_f32_eq(shrunk.width(), 135.0, "shrunk width");
+    }
+
+    #[test]
+    fn test_recenter_rect_allows_negative_origins() {
+        let old = Rect::from_min_size(egui::pos2(-1920.0, 0.0), egui::vec2(200.0, 200.0));
+
+        let recentered = recenter_rect(old, egui::vec2(400.0, 200.0));
+
+        assert_f32_eq(recentered.min.x, -2020.0, "left edge on secondary monitor");
+    }
 }
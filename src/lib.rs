@@ -6,14 +6,21 @@
 pub mod app;
 pub mod bars;
 pub mod cli;
+pub mod clock;
 pub mod color;
 pub mod config;
+pub mod config_handle;
+pub mod draw_list;
 pub mod fading;
 pub mod font;
+pub mod icon;
 pub mod input;
 pub mod key_map;
 pub mod layout;
 pub mod logging;
+pub mod observer;
 pub mod renderer;
+pub mod snapshot;
+pub mod state;
 pub mod types;
 pub mod watcher;
@@ -4,5 +4,23 @@ use key_overlay_rs::{app, cli};
 
 fn main() -> Result<()> {
     let args = cli::parse_args();
-    app::run(&args.config)
+    if args.list_keys {
+        app::list_keys();
+        return Ok(());
+    }
+    if args.emit_schema {
+        return app::emit_schema();
+    }
+    if args.check {
+        return app::check(&args.config);
+    }
+    if args.print_config {
+        return app::print_config(&args.config);
+    }
+
+    let options = app::RunOptions {
+        setup_grid: args.setup_grid,
+        record_to: args.record,
+    };
+    app::run_with_options(&args.config, options)
 }
@@ -0,0 +1,29 @@
+//! Extension point for reacting to key presses (e.g. audio cues).
+//!
+//! The crate never depends on an audio library; embedders implement
+//! [`PressObserver`] and attach it to the renderer instead.
+
+use std::fmt::Debug;
+
+use crate::types::KeyConfig;
+
+/// Whether a notification describes a key going down or coming back up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Press,
+    Release,
+}
+
+/// Receives notifications for input on configured keys.
+pub trait PressObserver: Debug + Send + Sync {
+    /// Called once per press or release of a configured key.
+    fn on_event(&self, key: &KeyConfig, kind: EventKind);
+}
+
+/// Observer that ignores every notification.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopObserver;
+
+impl PressObserver for NoopObserver {
+    fn on_event(&self, _key: &KeyConfig, _kind: EventKind) {}
+}
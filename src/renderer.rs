@@ -1,26 +1,144 @@
 //! egui overlay rendering
 
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 
 use egui::{
-    Align2, Color32, Context, FontData, FontDefinitions, FontFamily, FontId, Frame, Pos2, Rect,
-    Stroke,
+    Align2, Context, FontData, FontDefinitions, FontFamily, FontId, Frame, Pos2, Rect, Stroke,
 };
 use egui_overlay::EguiOverlay;
+use tracing::warn;
 
-use crate::bars::{BarColumn, BarManager};
+use crate::bars::{Bar, BarColumn, BarManager, MergedRect, enforce_bar_gaps, merge_bar_rects};
+use crate::color::lerp_color;
+use crate::draw_list::{DrawList, DrawShape, TextBackdrop};
 use crate::fading::calculate_fade_alpha;
-use crate::font::load_font;
-use crate::layout::{calculate_key_x_positions, calculate_window_width};
-use crate::types::{AppConfig, KeyConfig};
+use crate::font::{load_fallback_fonts, load_primary_font, prepend_font_chain};
+use crate::icon::IconCache;
+use crate::input::KeyId;
+use crate::layout::{
+    calculate_group_span, calculate_key_positions, calculate_window_size, clamp_window_size,
+    group_member_indices,
+};
+use crate::observer::{EventKind, NoopObserver, PressObserver};
+use crate::snapshot;
+use crate::state::OverlayState;
+use crate::types::{
+    AppConfig, AppError, BarPattern, Color, GoalBarPosition, KeyAxis, KeyConfig, KeyGroup,
+    KeyLabelAlign, MAX_FPS,
+};
 
 const FONT_NAME: &str = "jetbrains-mono";
-const KEY_LABEL_SCALE: f32 = 0.32;
-const COUNTER_TEXT_SCALE: f32 = 0.24;
+const MIN_KEY_LABEL_FONT_SIZE: f32 = 12.0;
+const MIN_COUNTER_FONT_SIZE: f32 = 10.0;
 const FADE_REGION_RATIO: f32 = 0.25;
 const BOTTOM_TEXT_MARGIN: f32 = 8.0;
 const KEY_LABEL_VERTICAL_CENTER_RATIO: f32 = 0.6;
+const KEY_LABEL_EDGE_PADDING: f32 = 4.0;
 const WINDOW_SIZE_EPSILON: f32 = 0.5;
+const PATTERN_PERIOD: f32 = 12.0;
+const MAX_PATTERN_SHAPES: usize = 64;
+const STATS_TEXT_MARGIN: f32 = 4.0;
+const KPS_WINDOW: Duration = Duration::from_secs(1);
+const APM_WINDOW: Duration = Duration::from_secs(60);
+const TOAST_DURATION_SECS: f32 = 2.5;
+const TOAST_PADDING: f32 = 6.0;
+const GRID_SPACING: f32 = 50.0;
+const IDLE_FADE_OUT_SECS: f32 = 0.6;
+const IDLE_FADE_IN_SECS: f32 = 0.05;
+const EVENT_LOG_FONT_SIZE: f32 = 12.0;
+const EVENT_LOG_LINE_HEIGHT: f32 = EVENT_LOG_FONT_SIZE + 2.0;
+const GOAL_BAR_HEIGHT: f32 = 4.0;
+const ANCHOR_LINE_THICKNESS: f32 = 2.0;
+const HELP_FONT_SIZE: f32 = 12.0;
+const HELP_PADDING: f32 = 8.0;
+const HELP_LINE_HEIGHT: f32 = HELP_FONT_SIZE + 4.0;
+const GROUP_LABEL_FONT_SIZE: f32 = 12.0;
+/// Gap between the key boxes' top edge and a group's span line.
+const GROUP_LINE_GAP: f32 = 6.0;
+/// Side of a key icon relative to the key box's shorter side.
+const KEY_ICON_SIZE_RATIO: f32 = 0.6;
+/// Press intervals at or below this draw `velocityColoring` bars at full brightness.
+const FAST_PRESS_INTERVAL_SECS: f32 = 0.05;
+/// Press intervals at or above this draw `velocityColoring` bars at `MIN_PRESS_INTENSITY`.
+const SLOW_PRESS_INTERVAL_SECS: f32 = 0.5;
+const MIN_PRESS_INTENSITY: f32 = 0.4;
+/// Target height of one `barCapFadePx` gradient slice.
+const BAR_CAP_SLICE_PX: f32 = 2.0;
+const BAR_CAP_MAX_SLICES: usize = 16;
+
+/// Lightweight counters for monitoring a long-running overlay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Metrics {
+    /// Press and release events handed to the renderer.
+    pub events_processed: u64,
+    /// Processed events ignored because their key is not configured.
+    pub events_dropped: u64,
+    /// Configs applied from hot-reload.
+    pub config_reloads: u64,
+    pub frames_rendered: u64,
+}
+
+/// Transient message drawn over the overlay.
+#[derive(Debug, Clone, PartialEq)]
+struct Toast {
+    message: String,
+    remaining: f32,
+}
+
+/// Startup help text and its current opacity.
+#[derive(Debug, Clone, PartialEq)]
+struct HelpPanel {
+    lines: Vec<String>,
+    alpha: f32,
+}
+
+/// Key colors blending from the previous config after a reload.
+#[derive(Debug, Clone, PartialEq)]
+struct ColorTransition {
+    /// Color each changed key had on screen when the reload arrived.
+    from: HashMap<String, Color>,
+    elapsed: f32,
+    duration: f32,
+}
+
+impl ColorTransition {
+    fn progress(&self) -> f32 {
+        (self.elapsed / self.duration).clamp(0.0, 1.0)
+    }
+
+    /// Color of `key_name` on its way to `target`.
+    fn color(&self, key_name: &str, target: &Color) -> Color {
+        self.from.get(key_name).map_or_else(
+            || target.clone(),
+            |from| lerp_color(from, target, self.progress()),
+        )
+    }
+
+    fn is_finished(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+}
+
+/// Bounded list of recent input events, newest first.
+#[derive(Debug, Clone, Default, PartialEq)]
+struct EventLog {
+    entries: VecDeque<String>,
+}
+
+impl EventLog {
+    fn push(&mut self, entry: String, capacity: usize) {
+        self.entries.push_front(entry);
+        self.entries.truncate(capacity);
+    }
+
+    fn iter(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+}
 
 /// Renderer for egui overlay.
 #[derive(Debug)]
@@ -29,47 +147,337 @@ pub struct Renderer {
     pub bar_manager: BarManager,
     key_positions: Vec<f32>,
     last_frame_time: Option<f64>,
+    smoothed_dt: Option<f32>,
     font_loaded: bool,
+    icons: IconCache,
+    press_observer: Arc<dyn PressObserver>,
+    overlay_state: OverlayState,
+    /// Last position applied from config, so manual moves are not undone every frame.
+    applied_window_pos: Option<(i32, i32)>,
+    toast: Option<Toast>,
+    help: Option<HelpPanel>,
+    /// Persistent error message, one line per row, drawn over the middle of the overlay.
+    error_banner: Option<String>,
+    color_transition: Option<ColorTransition>,
+    /// Counter visibility, starting from `counter` and flipped by `toggleCounterKey`.
+    show_counter: bool,
+    setup_grid: bool,
+    metrics: Metrics,
+    idle_hidden: bool,
+    /// Whole-overlay opacity, eased toward 0 while idle and back to 1 on input.
+    overlay_alpha: f32,
+    /// Whether the startup `monitorIndex` placement has been handled.
+    monitor_placed: bool,
+    event_log: EventLog,
+    /// Physical keys currently down per column, so a column fed by several keys
+    /// stays held until the last of them is released.
+    held_inputs: HashMap<String, HashSet<String>>,
 }
 
 impl Renderer {
     pub fn new(config: AppConfig) -> Self {
-        let key_positions = calculate_key_x_positions(&config);
-        let bar_manager = BarManager::new(config.bar_speed);
+        let key_positions = calculate_key_positions(&config);
+        let mut bar_manager = BarManager::new(config.bar_speed);
+        apply_bar_settings(&mut bar_manager, &config);
+        bar_manager.seed_counts(&config.keys);
+        let show_counter = config.counter;
+        let mut icons = IconCache::default();
+        icons.load_keys(&config.keys);
 
         Self {
             config,
             bar_manager,
             key_positions,
             last_frame_time: None,
+            smoothed_dt: None,
             font_loaded: false,
+            icons,
+            press_observer: Arc::new(NoopObserver),
+            overlay_state: OverlayState::default(),
+            applied_window_pos: None,
+            toast: None,
+            help: None,
+            error_banner: None,
+            color_transition: None,
+            show_counter,
+            setup_grid: false,
+            metrics: Metrics::default(),
+            idle_hidden: false,
+            overlay_alpha: 1.0,
+            monitor_placed: false,
+            event_log: EventLog::default(),
+            held_inputs: HashMap::new(),
         }
     }
 
+    /// Attaches an observer notified on every press and release of a configured key.
+    pub fn set_press_observer(&mut self, observer: Arc<dyn PressObserver>) {
+        self.press_observer = observer;
+    }
+
     pub fn on_key_press(&mut self, key_name: &str) {
-        if let Some((mapped_key, color)) = self
-            .config
-            .keys
-            .iter()
-            .find(|key| key.key_name == key_name)
-            .map(|key| (key.key_name.clone(), key.color.clone()))
-        {
-            self.bar_manager.on_key_press(&mapped_key, color);
-        }
+        self.metrics.events_processed += 1;
+        self.log_event(format!("{key_name}↓"));
+        let Some(key) = find_key_config(&self.config.keys, key_name) else {
+            self.metrics.events_dropped += 1;
+            return;
+        };
+
+        self.held_inputs
+            .entry(key.key_name.clone())
+            .or_default()
+            .insert(key_name.to_string());
+        self.press_observer.on_event(key, EventKind::Press);
+        self.bar_manager.on_configured_key_press(key);
     }
 
     pub fn on_key_release(&mut self, key_name: &str) {
-        self.bar_manager.on_key_release(key_name);
+        self.metrics.events_processed += 1;
+        self.log_event(format!("{key_name}↑"));
+        match find_key_config(&self.config.keys, key_name) {
+            Some(key) => {
+                self.press_observer.on_event(key, EventKind::Release);
+                let held = self.held_inputs.entry(key.key_name.clone()).or_default();
+                held.remove(key_name);
+                if held.is_empty() {
+                    self.bar_manager.on_key_release(&key.key_name);
+                }
+            }
+            None => {
+                self.metrics.events_dropped += 1;
+                self.bar_manager.on_key_release(key_name);
+            }
+        }
+    }
+
+    fn log_event(&mut self, entry: String) {
+        if self.config.show_event_log {
+            self.event_log.push(entry, self.config.event_log_size);
+        }
+    }
+
+    /// Recent input events, newest first.
+    pub fn event_log(&self) -> impl Iterator<Item = &str> {
+        self.event_log.iter()
+    }
+
+    /// Persistent state such as the all-time peak KPS.
+    pub fn overlay_state(&self) -> &OverlayState {
+        &self.overlay_state
+    }
+
+    pub fn set_overlay_state(&mut self, state: OverlayState) {
+        self.overlay_state = state;
+    }
+
+    /// Feeds a live KPS sample into the persisted peak.
+    pub fn record_kps(&mut self, kps: f32) {
+        self.overlay_state.update_peak(kps);
+    }
+
+    /// Snapshot of the renderer's telemetry counters.
+    pub fn metrics(&self) -> Metrics {
+        self.metrics
+    }
+
+    /// Counts a config applied from hot-reload, as opposed to runtime tweaks.
+    pub fn record_config_reload(&mut self) {
+        self.metrics.config_reloads += 1;
+    }
+
+    /// Fades the overlay out while `hidden`, and quickly back in once cleared.
+    pub fn set_idle_hidden(&mut self, hidden: bool) {
+        self.idle_hidden = hidden;
+    }
+
+    pub fn is_idle_hidden(&self) -> bool {
+        self.idle_hidden
+    }
+
+    /// Enables the alignment grid used while positioning the capture.
+    pub fn set_setup_grid(&mut self, enabled: bool) {
+        self.setup_grid = enabled;
+    }
+
+    /// Shows `message` briefly, replacing any toast already on screen.
+    pub fn show_toast(&mut self, message: impl Into<String>) {
+        self.toast = Some(Toast {
+            message: message.into(),
+            remaining: TOAST_DURATION_SECS,
+        });
+    }
+
+    /// Shows a help panel with one row per entry in `lines` until faded out.
+    pub fn show_help(&mut self, lines: Vec<String>) {
+        self.help = Some(HelpPanel { lines, alpha: 1.0 });
+    }
+
+    /// Sets the help panel's opacity; zero or less removes it.
+    pub fn set_help_alpha(&mut self, alpha: f32) {
+        if alpha <= 0.0 {
+            self.help = None;
+        } else if let Some(help) = &mut self.help {
+            help.alpha = alpha.min(1.0);
+        }
+    }
+
+    /// Whether the help panel is currently shown.
+    pub fn is_help_visible(&self) -> bool {
+        self.help.is_some()
+    }
+
+    /// Shows `message` until replaced or cleared with `None`; newlines start new rows.
+    pub fn set_error_banner(&mut self, message: Option<String>) {
+        self.error_banner = message;
+    }
+
+    /// Message currently shown in the error banner, if any.
+    pub fn error_banner(&self) -> Option<&str> {
+        self.error_banner.as_deref()
+    }
+
+    /// Shows or hides the press counters without changing the config.
+    pub fn toggle_counter(&mut self) {
+        self.show_counter = !self.show_counter;
+    }
+
+    /// Restores counter visibility to the config's `counter` value.
+    pub fn reset_counter_visibility(&mut self) {
+        self.show_counter = self.config.counter;
+    }
+
+    /// Whether the press counters are currently drawn.
+    pub fn is_counter_visible(&self) -> bool {
+        self.show_counter
+    }
+
+    /// Names of the configured keys currently held down, sorted.
+    pub fn held_keys(&self) -> Vec<String> {
+        self.bar_manager.held_keys()
+    }
+
+    /// Message of the toast currently on screen, if any.
+    pub fn toast_message(&self) -> Option<&str> {
+        self.toast.as_ref().map(|toast| toast.message.as_str())
+    }
+
+    /// Delay until the next frame: `idleFps` while nothing animates, else `fps`.
+    ///
+    /// The rate is clamped to `1..=MAX_FPS` so a typo cannot spin the render loop.
+    pub fn repaint_interval(&self) -> Duration {
+        let settled_alpha = if self.idle_hidden { 0.0 } else { 1.0 };
+        let fading = (self.overlay_alpha - settled_alpha).abs() > f32::EPSILON;
+        let target_fps = match self.config.idle_fps {
+            Some(idle_fps)
+                if self.bar_manager.is_idle()
+                    && !fading
+                    && self.help.is_none()
+                    && self.color_transition.is_none() =>
+            {
+                idle_fps
+            }
+            _ => self.config.fps,
+        };
+        Duration::from_secs_f32(1.0 / target_fps.clamp(1, MAX_FPS) as f32)
+    }
+
+    pub fn config(&self) -> &AppConfig {
+        &self.config
     }
 
     pub fn set_config(&mut self, config: AppConfig) {
+        if config.fallback_fonts != self.config.fallback_fonts
+            || config.font_path != self.config.font_path
+        {
+            self.font_loaded = false;
+        }
+        let color_transition = self.start_color_transition(&config);
         self.config = config;
-        self.key_positions = calculate_key_x_positions(&self.config);
-        self.bar_manager.bar_speed = self.config.bar_speed;
+        self.key_positions = calculate_key_positions(&self.config);
+        self.bar_manager.sync_keys(&self.config.keys);
+        self.bar_manager.seed_counts(&self.config.keys);
+        apply_bar_settings(&mut self.bar_manager, &self.config);
+        self.icons.load_keys(&self.config.keys);
+        self.event_log.entries.truncate(self.config.event_log_size);
+        self.color_transition = color_transition;
+        self.apply_transition_colors();
+    }
+
+    /// Captures the on-screen color of every key whose color `config` changes,
+    /// or `None` when `colorTransitionMs` is zero or no color changes.
+    fn start_color_transition(&self, config: &AppConfig) -> Option<ColorTransition> {
+        if config.color_transition_ms == 0 {
+            return None;
+        }
+        let from: HashMap<String, Color> = self
+            .config
+            .keys
+            .iter()
+            .filter_map(|old_key| {
+                let new_key = config
+                    .keys
+                    .iter()
+                    .find(|key| key.key_name == old_key.key_name)?;
+                let shown = self.key_color(old_key);
+                (shown != new_key.color).then(|| (old_key.key_name.clone(), shown))
+            })
+            .collect();
+        (!from.is_empty()).then(|| ColorTransition {
+            from,
+            elapsed: 0.0,
+            duration: Duration::from_millis(config.color_transition_ms).as_secs_f32(),
+        })
+    }
+
+    /// `key`'s icon path when it has one that decoded; otherwise the text label is drawn.
+    fn loaded_icon<'a>(&self, key: &'a KeyConfig) -> Option<&'a Path> {
+        key.icon_path
+            .as_deref()
+            .filter(|path| self.icons.is_loaded(path))
+    }
+
+    /// Color `key` is drawn with, blended from the old config during a transition.
+    fn key_color(&self, key: &KeyConfig) -> Color {
+        self.color_transition.as_ref().map_or_else(
+            || key.color.clone(),
+            |transition| transition.color(&key.key_name, &key.color),
+        )
+    }
+
+    fn tick_color_transition(&mut self, dt: f32) {
+        let Some(transition) = &mut self.color_transition else {
+            return;
+        };
+        transition.elapsed += dt;
+        if transition.is_finished() {
+            self.color_transition = None;
+        }
+        self.apply_transition_colors();
+    }
+
+    /// Gives each column the current transition color for its new bars.
+    fn apply_transition_colors(&mut self) {
+        let colors: Vec<(String, Color, Option<Color>)> = self
+            .config
+            .keys
+            .iter()
+            .map(|key| {
+                (
+                    key.key_name.clone(),
+                    self.key_color(key),
+                    key.pressed_color.clone(),
+                )
+            })
+            .collect();
+        for (key_name, color, pressed_color) in colors {
+            if let Some(column) = self.bar_manager.columns.get_mut(&key_name) {
+                column.set_colors(color, pressed_color);
+            }
+        }
     }
 
     pub fn desired_window_size(&self) -> [f32; 2] {
-        [calculate_window_width(&self.config), self.config.height]
+        clamp_window_size(calculate_window_size(&self.config), &self.config)
     }
 
     fn sync_window_size(
@@ -82,19 +490,95 @@ impl Renderer {
         }
     }
 
+    fn sync_window_position(
+        &mut self,
+        glfw_backend: &mut egui_overlay::egui_window_glfw_passthrough::GlfwBackend,
+    ) {
+        let current = glfw_backend.window.get_pos();
+        if let Some((x, y)) = self.next_window_move(current) {
+            glfw_backend.window.set_pos(x, y);
+        }
+    }
+
+    /// Position to move the window to from `current`, recording the configured
+    /// position as applied even when the window is already there.
+    fn next_window_move(&mut self, current: (i32, i32)) -> Option<(i32, i32)> {
+        let configured = (self.config.window_x, self.config.window_y);
+        match window_position_update(configured, self.applied_window_pos, current) {
+            WindowPositionUpdate::Keep => None,
+            WindowPositionUpdate::AlreadyThere(position) => {
+                self.applied_window_pos = Some(position);
+                None
+            }
+            WindowPositionUpdate::Move(position) => {
+                self.applied_window_pos = Some(position);
+                Some(position)
+            }
+        }
+    }
+
+    /// Moves the window onto the configured monitor's work area on the first frame.
+    fn place_on_monitor(
+        &mut self,
+        glfw_backend: &mut egui_overlay::egui_window_glfw_passthrough::GlfwBackend,
+    ) {
+        if std::mem::replace(&mut self.monitor_placed, true) {
+            return;
+        }
+        let Some(index) = self.config.monitor_index else {
+            return;
+        };
+        if self.config.window_x.is_some() || self.config.window_y.is_some() {
+            return;
+        }
+
+        let work_areas: Vec<Rect> = glfw_backend.glfw.with_connected_monitors(|_, monitors| {
+            monitors
+                .iter()
+                .map(|monitor| {
+                    let (x, y, width, height) = monitor.get_workarea();
+                    Rect::from_min_size(
+                        Pos2::new(x as f32, y as f32),
+                        egui::vec2(width as f32, height as f32),
+                    )
+                })
+                .collect()
+        });
+
+        if let Some(origin) = resolve_monitor_origin(&work_areas, index) {
+            glfw_backend
+                .window
+                .set_pos(origin.x.round() as i32, origin.y.round() as i32);
+        }
+    }
+
     fn ensure_font_loaded(&mut self, egui_context: &Context) {
         if self.font_loaded {
             return;
         }
 
         let mut font_definitions = FontDefinitions::default();
+        let primary = match load_primary_font(self.config.font_path.as_deref()) {
+            Cow::Borrowed(bytes) => FontData::from_static(bytes),
+            Cow::Owned(bytes) => FontData::from_owned(bytes),
+        };
         font_definitions
             .font_data
-            .insert(FONT_NAME.to_string(), FontData::from_static(load_font()));
+            .insert(FONT_NAME.to_string(), primary);
+
+        let mut chain = vec![FONT_NAME.to_string()];
+        for (name, bytes) in load_fallback_fonts(&self.config.fallback_fonts) {
+            font_definitions
+                .font_data
+                .insert(name.clone(), FontData::from_owned(bytes));
+            chain.push(name);
+        }
 
+        // egui's built-in fonts, including its emoji fonts, stay behind the chain
+        // as the last resort for glyphs none of the configured fonts have.
         for family in [FontFamily::Monospace, FontFamily::Proportional] {
             if let Some(fonts) = font_definitions.families.get_mut(&family) {
-                fonts.insert(0, FONT_NAME.to_string());
+                prepend_font_chain(fonts, &chain);
             }
         }
 
@@ -109,57 +593,228 @@ impl Renderer {
             .map(|last| (current_time - last).max(0.0) as f32)
             .unwrap_or_default()
             .min(0.1);
+        let dt = match self.smoothed_dt {
+            Some(prev) => smooth_dt(prev, dt, self.config.dt_smoothing),
+            None => dt,
+        };
 
         self.last_frame_time = Some(current_time);
+        self.smoothed_dt = Some(dt);
+        self.tick_toast(dt);
+        self.tick_color_transition(dt);
+        self.overlay_alpha = step_overlay_alpha(self.overlay_alpha, self.idle_hidden, dt);
 
         self.bar_manager.update(dt);
-        self.bar_manager.remove_offscreen(self.config.height);
+        self.bar_manager.remove_offscreen(self.bar_track_height());
+        self.record_kps(self.bar_manager.total_kps(KPS_WINDOW));
+    }
+
+    /// Advances animation by one frame and paints it.
+    pub fn render_frame(&mut self, egui_context: &Context) {
+        self.ensure_font_loaded(egui_context);
+        self.update_animation(egui_context);
+        self.draw(egui_context);
+        self.metrics.frames_rendered += 1;
     }
 
-    fn draw(&self, egui_context: &Context) {
-        let frame = Frame::none().fill(self.config.background_color.to_egui());
+    fn tick_toast(&mut self, dt: f32) {
+        if let Some(toast) = &mut self.toast {
+            toast.remaining -= dt;
+            if toast.remaining <= 0.0 {
+                self.toast = None;
+            }
+        }
+    }
 
+    fn draw(&mut self, egui_context: &Context) {
         egui::CentralPanel::default()
-            .frame(frame)
+            .frame(Frame::none())
             .show(egui_context, |ui| {
                 let canvas = ui.max_rect();
                 let painter = ui.painter_at(canvas);
-                let fade_height = self.config.height * FADE_REGION_RATIO;
-
-                for (index, key) in self.config.keys.iter().enumerate() {
-                    let Some(column_x) = self.key_positions.get(index).copied() else {
-                        continue;
-                    };
+                let list = self.build_draw_list(canvas);
+                paint_draw_list(&painter, &list, &mut self.icons);
+            });
+    }
 
-                    let bar_width = self.config.key_size * key.size;
-                    let left = canvas.left() + column_x + self.config.outline_thickness;
-                    let right = left + bar_width;
+    /// Lists every shape of the current frame inside `canvas`, without touching egui state.
+    ///
+    /// The frame is laid out at `canvas / uiScale` and then scaled up, so
+    /// every size field scales together.
+    pub fn build_draw_list(&self, canvas: Rect) -> DrawList {
+        let scale = self.config.ui_scale;
+        if scale == 1.0 || scale <= 0.0 {
+            return self.build_unscaled_draw_list(canvas);
+        }
 
-                    self.draw_key_anchor_border(&painter, canvas, left, right, key);
+        let unscaled = Rect::from_min_size(canvas.min, canvas.size() / scale);
+        let mut list = self.build_unscaled_draw_list(unscaled);
+        list.scale(scale, canvas.min);
+        list
+    }
 
-                    if let Some(column) = self.bar_manager.columns.get(&key.key_name) {
-                        self.draw_column_bars(&painter, canvas, left, right, column, fade_height);
-                    }
+    fn build_unscaled_draw_list(&self, canvas: Rect) -> DrawList {
+        let mut list = DrawList::new(self.overlay_alpha);
 
-                    self.draw_key_text(&painter, canvas, left, right, key);
-                }
+        if let Some(fill) = background_fill(&self.config) {
+            list.push(DrawShape::FilledRect {
+                rect: canvas,
+                corner_radius: 0.0,
+                color: fill,
             });
+        }
+
+        if self.setup_grid {
+            push_setup_grid(&mut list, canvas);
+        }
+
+        let map = AxisMap::new(self.config.key_axis, canvas);
+        let layout = map.layout_canvas();
+
+        if self.config.show_anchor_line {
+            self.push_anchor_line(&mut list, &map);
+        }
+
+        for (index, key) in self.config.keys.iter().enumerate() {
+            let Some(column_x) = self.key_positions.get(index).copied() else {
+                continue;
+            };
+
+            let bar_width = self.config.key_size * key.size;
+            let left = layout.left() + column_x + self.config.outline_thickness;
+            let right = left + bar_width;
+
+            self.push_key_anchor_border(&mut list, &map, left, right, key);
+
+            if let Some(column) = self.bar_manager.columns.get(&key.key_name) {
+                let (bar_left, bar_right) =
+                    bar_horizontal_extent(left, right, self.config.bar_width_ratio);
+                self.push_column_bars(&mut list, &map, bar_left, bar_right, column, key);
+            }
+
+            self.push_key_text(&mut list, &map, left, right, key);
+        }
+
+        for group in &self.config.groups {
+            self.push_group_label(&mut list, &map, group);
+        }
+
+        if self.config.show_peak_kps {
+            self.push_peak_kps(&mut list, canvas);
+        }
+
+        if self.config.show_apm {
+            self.push_apm(&mut list, canvas);
+        }
+
+        if let Some(goal) = self.config.session_goal {
+            let progress = goal_progress(self.bar_manager.total_presses(), goal);
+            push_goal_bar(
+                &mut list,
+                canvas,
+                self.config.session_goal_position,
+                progress,
+            );
+        }
+
+        if self.config.show_event_log {
+            push_event_log(&mut list, canvas, &self.event_log);
+        }
+
+        if let Some(help) = &self.help {
+            push_help_panel(&mut list, canvas, help);
+        }
+
+        if let Some(message) = &self.error_banner {
+            push_error_banner(&mut list, canvas, message);
+        }
+
+        if let Some(toast) = &self.toast {
+            push_toast(&mut list, canvas, &toast.message);
+        }
+
+        list
+    }
+
+    /// Rasterizes the current frame at `width` x `height` and writes it to `path` as PNG.
+    ///
+    /// Runs without a window or egui context, e.g. for documentation screenshots.
+    pub fn render_to_png(&self, path: &Path, width: u32, height: u32) -> Result<(), AppError> {
+        std::fs::write(path, self.render_png_bytes(width, height)?)?;
+        Ok(())
+    }
+
+    /// PNG bytes of the current frame at `width` x `height`.
+    pub fn render_png_bytes(&self, width: u32, height: u32) -> Result<Vec<u8>, AppError> {
+        let canvas = Rect::from_min_size(Pos2::ZERO, egui::vec2(width as f32, height as f32));
+        let font = load_primary_font(self.config.font_path.as_deref());
+        let pixmap = snapshot::rasterize(&self.build_draw_list(canvas), width, height, &font)?;
+        snapshot::encode_png(&pixmap)
     }
 
-    fn draw_column_bars(
+    fn push_column_bars(
         &self,
-        painter: &egui::Painter,
-        canvas: Rect,
+        list: &mut DrawList,
+        map: &AxisMap,
         left: f32,
         right: f32,
         column: &BarColumn,
-        fade_height: f32,
+        key: &KeyConfig,
     ) {
+        let canvas = map.layout_canvas();
+        let fading = key_fading_enabled(key, self.config.fading);
         let key_bottom = self.key_bottom(canvas);
+        let fade_height = self.config.height * FADE_REGION_RATIO;
+        let release_decay = Duration::from_millis(self.config.release_decay_ms);
+        let ghost_trail = Duration::from_millis(self.config.ghost_trail_ms);
 
+        // Each bar with its final fill color and drawn height, so merging compares what is drawn.
+        let mut filled_bars = Vec::with_capacity(column.bars.len());
         for (bar_index, bar) in column.bars.iter().enumerate() {
-            let bottom_y = key_bottom - bar.y_position;
-            let top_y = bottom_y - bar.height;
+            let is_active_bar = column.is_held && (bar_index + 1 == column.bars.len());
+            let base_color = if is_active_bar {
+                bar.pressed_color.clone()
+            } else {
+                release_decay_color(
+                    &bar.pressed_color,
+                    &released_bar_color(bar, key.trail_color.as_ref(), self.config.height),
+                    bar.since_release(),
+                    release_decay,
+                )
+            };
+            let base_color = if self.config.velocity_coloring {
+                with_scaled_brightness(&base_color, press_intensity(bar.press_interval))
+            } else {
+                base_color
+            };
+
+            let fade_alpha = bar_fade_alpha(
+                fading,
+                is_active_bar,
+                bar.y_position,
+                self.config.height,
+                fade_height,
+            ) * bar.ghost_alpha(ghost_trail);
+
+            filled_bars.push(Bar {
+                height: bar.height.max(self.config.min_bar_height),
+                color: with_scaled_alpha(&base_color, fade_alpha),
+                ..bar.clone()
+            });
+        }
+
+        enforce_bar_gaps(&mut filled_bars, self.config.bar_gap_px);
+        filled_bars.retain(|bar| bar.height > 0.0);
+
+        let rects: Vec<MergedRect> = if self.config.merge_bars {
+            merge_bar_rects(&filled_bars)
+        } else {
+            filled_bars.iter().map(MergedRect::from).collect()
+        };
+
+        for merged in rects {
+            let bottom_y = key_bottom - merged.y_position;
+            let top_y = bottom_y - merged.height;
 
             if bottom_y <= canvas.top() || top_y >= key_bottom {
                 continue;
@@ -167,114 +822,294 @@ impl Renderer {
 
             let draw_top = top_y.max(canvas.top());
             let draw_bottom = bottom_y.min(key_bottom);
+            let bar_rect = Rect::from_min_max(Pos2::new(left, top_y), Pos2::new(right, bottom_y));
             let rect = Rect::from_min_max(Pos2::new(left, draw_top), Pos2::new(right, draw_bottom));
 
-            let is_active_bar = column.is_held && (bar_index + 1 == column.bars.len());
-            let base_color = if is_active_bar {
-                bar.pressed_color.to_egui()
-            } else {
-                bar.color.to_egui()
-            };
-
+            // Held bars sit at y = 0, below the fade region, so they stay opaque here too.
             let fade_alpha = bar_fade_alpha(
-                self.config.fading,
-                is_active_bar,
-                bar.y_position,
+                fading,
+                false,
+                merged.y_position,
                 self.config.height,
                 fade_height,
             );
+            let stroke_color = with_scaled_alpha(&self.config.bar_outline_color, fade_alpha);
 
-            let fill_color = with_scaled_alpha(base_color, fade_alpha);
-            let stroke_color = with_scaled_alpha(Color32::WHITE, fade_alpha);
-
-            painter.rect_filled(rect, 0.0, fill_color);
-            painter.rect_stroke(
+            let cap = bar_cap_rects(bar_rect, self.config.bar_cap_fade_px);
+            let body_top = cap
+                .last()
+                .map_or(bar_rect.top(), |(slice, _)| slice.bottom());
+            let body = Rect::from_min_max(Pos2::new(left, body_top), bar_rect.max).intersect(rect);
+            if body.is_positive() {
+                self.push_bar_fill(list, map, bar_rect, body, merged.color.clone());
+            }
+            for (slice, alpha) in cap {
+                let visible = slice.intersect(rect);
+                if visible.is_positive() {
+                    let color = with_scaled_alpha(&merged.color, alpha);
+                    self.push_bar_fill(list, map, bar_rect, visible, color);
+                }
+            }
+            let rect = map.rect(rect);
+            list.push(DrawShape::StrokedRect {
                 rect,
-                0.0,
-                Stroke::new(self.config.outline_thickness, stroke_color),
-            );
+                corner_radius: effective_corner_radius(self.config.corner_radius, rect),
+                thickness: self.config.outline_thickness,
+                color: stroke_color,
+            });
+        }
+    }
+
+    /// Fills `visible`, the on-screen part of `bar_rect`; both are in layout space.
+    fn push_bar_fill(
+        &self,
+        list: &mut DrawList,
+        map: &AxisMap,
+        bar_rect: Rect,
+        visible: Rect,
+        color: Color,
+    ) {
+        match self.config.bar_pattern {
+            BarPattern::Solid => {
+                let visible = map.rect(visible);
+                list.push(DrawShape::FilledRect {
+                    rect: visible,
+                    corner_radius: effective_corner_radius(self.config.corner_radius, visible),
+                    color,
+                });
+            }
+            BarPattern::Stripes => {
+                for stripe in stripe_rects(bar_rect) {
+                    let clipped = stripe.intersect(visible);
+                    if clipped.is_positive() {
+                        list.push(DrawShape::FilledRect {
+                            rect: map.rect(clipped),
+                            corner_radius: 0.0,
+                            color: color.clone(),
+                        });
+                    }
+                }
+            }
+            BarPattern::Dots => {
+                let radius = PATTERN_PERIOD * 0.25;
+                for center in dot_centers(bar_rect) {
+                    if visible.contains(center) {
+                        list.push(DrawShape::Circle {
+                            center: map.pos(center),
+                            radius,
+                            color: color.clone(),
+                        });
+                    }
+                }
+            }
         }
     }
 
-    fn draw_key_text(
+    fn push_key_text(
         &self,
-        painter: &egui::Painter,
-        canvas: Rect,
+        list: &mut DrawList,
+        map: &AxisMap,
         left: f32,
         right: f32,
         key: &KeyConfig,
     ) {
+        let canvas = map.layout_canvas();
         let key_bottom = self.key_bottom(canvas);
         let key_top = key_bottom - self.config.key_size;
         let center_x = (left + right) * 0.5;
-        let label_pos = Pos2::new(
-            center_x,
-            key_top + (self.config.key_size * KEY_LABEL_VERTICAL_CENTER_RATIO),
-        );
-        let label_font = FontId::new(
-            (self.config.key_size * KEY_LABEL_SCALE).max(12.0),
-            FontFamily::Monospace,
+        let (label_y, label_align) = key_label_anchor(
+            self.config.key_label_align,
+            key_top,
+            self.config.key_size,
+            self.config.outline_thickness,
         );
 
-        painter.text(
-            label_pos,
-            Align2::CENTER_CENTER,
-            &key.display_name,
-            label_font,
-            Color32::WHITE,
-        );
+        if let Some(path) = self.loaded_icon(key) {
+            let side = self.config.key_size * key.size.min(1.0) * KEY_ICON_SIZE_RATIO;
+            let center = Pos2::new(center_x, key_top + self.config.key_size * 0.5);
+            list.push(DrawShape::Image {
+                rect: map.rect(Rect::from_center_size(center, egui::vec2(side, side))),
+                path: path.to_path_buf(),
+            });
+        } else {
+            list.push(DrawShape::Text {
+                pos: map.pos(Pos2::new(center_x, label_y)),
+                align: map.align(label_align),
+                text: key.display_name.clone(),
+                font_size: scaled_font_size(
+                    self.config.key_size,
+                    self.config.key_label_scale,
+                    MIN_KEY_LABEL_FONT_SIZE,
+                ),
+                color: white(),
+                backdrop: None,
+            });
+        }
 
-        if !self.config.counter {
-            return;
+        let text_font_size = self.counter_font_size();
+        let column = self.bar_manager.columns.get(&key.key_name);
+        let mut text_bottom = canvas.bottom() - BOTTOM_TEXT_MARGIN;
+
+        if self.config.show_kps {
+            let kps = column.map_or(0.0, |column| column.kps(KPS_WINDOW));
+            list.push(DrawShape::Text {
+                pos: map.pos(Pos2::new(center_x, text_bottom)),
+                align: map.align(Align2::CENTER_BOTTOM),
+                text: format!("{kps:.0}/s"),
+                font_size: text_font_size,
+                color: self.key_color(key),
+                backdrop: None,
+            });
+            text_bottom -= text_font_size + BOTTOM_TEXT_MARGIN;
         }
 
-        let press_count = self
-            .bar_manager
-            .columns
-            .get(&key.key_name)
-            .map_or(0, |column| column.press_count);
-
-        let counter_pos = Pos2::new(center_x, canvas.bottom() - BOTTOM_TEXT_MARGIN);
-        let counter_font = FontId::new(
-            (self.config.key_size * COUNTER_TEXT_SCALE).max(10.0),
-            FontFamily::Monospace,
-        );
+        if self.show_counter {
+            let press_count = column.map_or(0, |column| column.press_count);
+            list.push(DrawShape::Text {
+                pos: map.pos(Pos2::new(center_x, text_bottom)),
+                align: map.align(Align2::CENTER_BOTTOM),
+                text: format_counter(self.config.counter_format.as_deref(), press_count),
+                font_size: text_font_size,
+                color: self.key_color(key),
+                backdrop: None,
+            });
+        }
+    }
 
-        painter.text(
-            counter_pos,
-            Align2::CENTER_BOTTOM,
-            format!("{press_count}"),
-            counter_font,
-            key.color.to_egui(),
-        );
+    fn push_peak_kps(&self, list: &mut DrawList, canvas: Rect) {
+        list.push(DrawShape::Text {
+            pos: canvas.left_top() + egui::vec2(STATS_TEXT_MARGIN, STATS_TEXT_MARGIN),
+            align: Align2::LEFT_TOP,
+            text: format!("peak {:.1}", self.overlay_state.peak_kps),
+            font_size: self.counter_font_size(),
+            color: white(),
+            backdrop: None,
+        });
     }
 
-    fn draw_key_anchor_border(
-        &self,
-        painter: &egui::Painter,
-        canvas: Rect,
+    /// Draws the session APM in the top-left corner, below the peak KPS if shown.
+    fn push_apm(&self, list: &mut DrawList, canvas: Rect) {
+        let font_size = self.counter_font_size();
+        let mut top = STATS_TEXT_MARGIN;
+        if self.config.show_peak_kps {
+            top += font_size + STATS_TEXT_MARGIN;
+        }
+        list.push(DrawShape::Text {
+            pos: canvas.left_top() + egui::vec2(STATS_TEXT_MARGIN, top),
+            align: Align2::LEFT_TOP,
+            text: format!("{:.0} apm", self.bar_manager.apm(APM_WINDOW)),
+            font_size,
+            color: white(),
+            backdrop: None,
+        });
+    }
+
+    fn push_key_anchor_border(
+        &self,
+        list: &mut DrawList,
+        map: &AxisMap,
         left: f32,
         right: f32,
         key: &KeyConfig,
     ) {
-        let bottom = self.key_bottom(canvas);
+        let bottom = self.key_bottom(map.layout_canvas());
         let top = bottom - self.config.key_size;
-        let border_rect = Rect::from_min_max(Pos2::new(left, top), Pos2::new(right, bottom));
+        let border_rect = map.rect(Rect::from_min_max(
+            Pos2::new(left, top),
+            Pos2::new(right, bottom),
+        ));
+        let outline_color = self
+            .bar_manager
+            .active_combos()
+            .into_iter()
+            .filter_map(|index| self.config.combos.get(index))
+            .find(|combo| combo.keys.contains(&key.key_name))
+            .map_or_else(|| self.key_color(key), |combo| combo.color.clone());
+
+        list.push(DrawShape::StrokedRect {
+            rect: border_rect,
+            corner_radius: effective_corner_radius(self.config.corner_radius, border_rect),
+            thickness: self.config.outline_thickness,
+            color: outline_color,
+        });
+    }
 
-        painter.rect_stroke(
-            border_rect,
+    /// Line over `group`'s member key boxes with its label centered above it.
+    fn push_group_label(&self, list: &mut DrawList, map: &AxisMap, group: &KeyGroup) {
+        let members = group_member_indices(&self.config, group);
+        let Some((start, end)) = calculate_group_span(&self.config, &self.key_positions, &members)
+        else {
+            return;
+        };
+
+        let layout = map.layout_canvas();
+        let (left, right) = (layout.left() + start, layout.left() + end);
+        let line_y = self.key_bottom(layout) - self.config.key_size - GROUP_LINE_GAP;
+
+        list.push(DrawShape::Line {
+            from: map.pos(Pos2::new(left, line_y)),
+            to: map.pos(Pos2::new(right, line_y)),
+            thickness: 1.0,
+            color: group.color.clone(),
+        });
+        list.push(DrawShape::Text {
+            pos: map.pos(Pos2::new((left + right) * 0.5, line_y - 2.0)),
+            align: map.align(Align2::CENTER_BOTTOM),
+            text: group.label.clone(),
+            font_size: GROUP_LABEL_FONT_SIZE,
+            color: group.color.clone(),
+            backdrop: None,
+        });
+    }
+
+    /// Line across the whole layout at the keys' bottom edge, where bars start.
+    fn push_anchor_line(&self, list: &mut DrawList, map: &AxisMap) {
+        let layout = map.layout_canvas();
+        let y = self.key_bottom(layout);
+        let alpha = bar_fade_alpha(
+            self.config.fading,
+            false,
             0.0,
-            Stroke::new(self.config.outline_thickness, key.color.to_egui()),
+            self.config.height,
+            self.config.height * FADE_REGION_RATIO,
         );
+
+        list.push(DrawShape::Line {
+            from: map.pos(Pos2::new(layout.left(), y)),
+            to: map.pos(Pos2::new(layout.right(), y)),
+            thickness: ANCHOR_LINE_THICKNESS,
+            color: with_scaled_alpha(&self.config.anchor_line_color, alpha),
+        });
+    }
+
+    fn counter_font_size(&self) -> f32 {
+        scaled_font_size(
+            self.config.key_size,
+            self.config.counter_scale,
+            MIN_COUNTER_FONT_SIZE,
+        )
     }
 
+    /// Bottom edge of the key boxes, leaving one text row each for the counter and KPS.
     fn key_bottom(&self, canvas: Rect) -> f32 {
-        if self.config.counter {
-            let counter_font_size = (self.config.key_size * COUNTER_TEXT_SCALE).max(10.0);
-            canvas.bottom() - (counter_font_size + (BOTTOM_TEXT_MARGIN * 2.0))
-        } else {
-            canvas.bottom()
+        canvas.bottom() - self.text_rows_height()
+    }
+
+    /// Height of the counter and KPS rows below the key boxes.
+    fn text_rows_height(&self) -> f32 {
+        let text_rows = usize::from(self.show_counter) + usize::from(self.config.show_kps);
+        if text_rows == 0 {
+            return 0.0;
         }
+
+        let text_font_size = self.counter_font_size();
+        let rows = text_rows as f32;
+        rows * text_font_size + (rows + 1.0) * BOTTOM_TEXT_MARGIN
+    }
+
+    /// Distance bars travel from the key bottom to the top of the window.
+    fn bar_track_height(&self) -> f32 {
+        self.config.height - self.text_rows_height()
     }
 }
 
@@ -286,12 +1121,11 @@ impl EguiOverlay for Renderer {
         glfw_backend: &mut egui_overlay::egui_window_glfw_passthrough::GlfwBackend,
     ) {
         self.sync_window_size(glfw_backend);
-        self.ensure_font_loaded(egui_context);
-        self.update_animation(egui_context);
-        self.draw(egui_context);
+        self.place_on_monitor(glfw_backend);
+        self.sync_window_position(glfw_backend);
+        self.render_frame(egui_context);
 
-        let target_fps = self.config.fps.max(1);
-        egui_context.request_repaint_after(Duration::from_secs_f32(1.0 / target_fps as f32));
+        egui_context.request_repaint_after(self.repaint_interval());
     }
 }
 
@@ -299,12 +1133,485 @@ pub fn create_renderer(config: AppConfig) -> Renderer {
     Renderer::new(config)
 }
 
-fn with_scaled_alpha(color: Color32, alpha_scale: f32) -> Color32 {
-    let scaled = (color.a() as f32 * alpha_scale.clamp(0.0, 1.0))
-        .round()
-        .clamp(0.0, 255.0) as u8;
+/// Configured key fed by an event for `key_name`: an exact match, then a key
+/// listing it in `aliases`, or else a generic modifier such as `Control` for
+/// `LControl`/`RControl`.
+fn find_key_config<'a>(keys: &'a [KeyConfig], key_name: &str) -> Option<&'a KeyConfig> {
+    keys.iter()
+        .find(|key| key.key_name == key_name)
+        .or_else(|| {
+            keys.iter()
+                .find(|key| key.aliases.iter().any(|alias| alias == key_name))
+        })
+        .or_else(|| {
+            let generic = key_name
+                .parse::<KeyId>()
+                .ok()?
+                .generic_modifier()?
+                .to_string();
+            keys.iter().find(|key| key.key_name == generic)
+        })
+}
+
+/// Copies the bar physics settings from `config` onto `bar_manager`.
+fn apply_bar_settings(bar_manager: &mut BarManager, config: &AppConfig) {
+    bar_manager.bar_speed = config.bar_speed;
+    bar_manager.max_bars_per_column = config.max_bars_per_column;
+    bar_manager.min_bar_lifetime = config.min_bar_lifetime_ms.map(Duration::from_millis);
+    bar_manager.ghost_trail =
+        (config.ghost_trail_ms > 0).then(|| Duration::from_millis(config.ghost_trail_ms));
+    bar_manager.bar_acceleration = config.bar_acceleration;
+    bar_manager.combos = config
+        .combos
+        .iter()
+        .map(|combo| combo.keys.clone())
+        .collect();
+}
+
+fn push_setup_grid(list: &mut DrawList, canvas: Rect) {
+    let color = Color::from_rgba_u8(255, 255, 255, 40);
+
+    for x in grid_line_positions(GRID_SPACING, canvas.width()) {
+        list.push(DrawShape::Line {
+            from: Pos2::new(canvas.left() + x, canvas.top()),
+            to: Pos2::new(canvas.left() + x, canvas.bottom()),
+            thickness: 1.0,
+            color: color.clone(),
+        });
+    }
+    for y in grid_line_positions(GRID_SPACING, canvas.height()) {
+        list.push(DrawShape::Line {
+            from: Pos2::new(canvas.left(), canvas.top() + y),
+            to: Pos2::new(canvas.right(), canvas.top() + y),
+            thickness: 1.0,
+            color: color.clone(),
+        });
+    }
+
+    list.push(DrawShape::Text {
+        pos: canvas.right_top() + egui::vec2(-STATS_TEXT_MARGIN, STATS_TEXT_MARGIN),
+        align: Align2::RIGHT_TOP,
+        text: format!("{:.0}x{:.0}", canvas.width(), canvas.height()),
+        font_size: 12.0,
+        color: white(),
+        backdrop: None,
+    });
+}
+
+/// Font size as a fraction of the key size, never below `min_size`.
+fn scaled_font_size(key_size: f32, scale: f32, min_size: f32) -> f32 {
+    (key_size * scale).max(min_size)
+}
+
+/// Exponentially smooths a frame delta: `factor` is the weight kept from
+/// `prev_smoothed`, so `0.0` returns `raw` unchanged.
+fn smooth_dt(prev_smoothed: f32, raw: f32, factor: f32) -> f32 {
+    let factor = factor.clamp(0.0, 1.0);
+    prev_smoothed * factor + raw * (1.0 - factor)
+}
+
+/// Moves the overlay opacity one frame toward hidden (0) or shown (1).
+fn step_overlay_alpha(alpha: f32, hidden: bool, dt: f32) -> f32 {
+    if hidden {
+        (alpha - dt / IDLE_FADE_OUT_SECS).max(0.0)
+    } else {
+        (alpha + dt / IDLE_FADE_IN_SECS).min(1.0)
+    }
+}
+
+/// Offsets of gridlines every `spacing` pixels, excluding the edges at 0 and `extent`.
+fn grid_line_positions(spacing: f32, extent: f32) -> Vec<f32> {
+    if spacing <= 0.0 {
+        return Vec::new();
+    }
+
+    let mut positions = Vec::new();
+    let mut offset = spacing;
+    while offset < extent {
+        positions.push(offset);
+        offset += spacing;
+    }
+
+    positions
+}
+
+/// Substitutes `count` into a validated `counterFormat`, or returns the bare number.
+fn format_counter(format: Option<&str>, count: u64) -> String {
+    match format {
+        Some(format) => format.replacen("{}", &count.to_string(), 1),
+        None => count.to_string(),
+    }
+}
+
+/// Fraction of the session goal reached, clamped to `[0, 1]`; a zero goal counts as met.
+fn goal_progress(total_presses: u64, goal: u64) -> f32 {
+    if goal == 0 {
+        return 1.0;
+    }
+
+    (total_presses as f64 / goal as f64).min(1.0) as f32
+}
+
+fn push_goal_bar(list: &mut DrawList, canvas: Rect, position: GoalBarPosition, progress: f32) {
+    let top = match position {
+        GoalBarPosition::Top => canvas.top(),
+        GoalBarPosition::Bottom => canvas.bottom() - GOAL_BAR_HEIGHT,
+    };
+    let track = Rect::from_min_size(
+        Pos2::new(canvas.left(), top),
+        egui::vec2(canvas.width(), GOAL_BAR_HEIGHT),
+    );
+    let mut fill = track;
+    fill.set_width(track.width() * progress);
+
+    list.push(DrawShape::FilledRect {
+        rect: track,
+        corner_radius: 0.0,
+        color: Color::from_rgba_u8(255, 255, 255, 40),
+    });
+    list.push(DrawShape::FilledRect {
+        rect: fill,
+        corner_radius: 0.0,
+        color: white(),
+    });
+}
+
+fn push_event_log(list: &mut DrawList, canvas: Rect, log: &EventLog) {
+    let mut y = canvas.top() + STATS_TEXT_MARGIN;
+
+    for (index, entry) in log.iter().enumerate() {
+        // Older entries fade out so the newest event stands out.
+        let alpha = 1.0 - index as f32 / (log.entries.len() as f32 + 1.0);
+        list.push(DrawShape::Text {
+            pos: Pos2::new(canvas.right() - STATS_TEXT_MARGIN, y),
+            align: Align2::RIGHT_TOP,
+            text: entry.to_string(),
+            font_size: EVENT_LOG_FONT_SIZE,
+            color: with_scaled_alpha(&white(), alpha),
+            backdrop: None,
+        });
+        y += EVENT_LOG_LINE_HEIGHT;
+    }
+}
+
+fn push_toast(list: &mut DrawList, canvas: Rect, message: &str) {
+    list.push(DrawShape::Text {
+        pos: Pos2::new(canvas.center().x, canvas.top() + TOAST_PADDING),
+        align: Align2::CENTER_TOP,
+        text: message.to_string(),
+        font_size: 14.0,
+        color: white(),
+        backdrop: Some(TextBackdrop {
+            color: Color::from_rgba_u8(0, 0, 0, 200),
+            padding: TOAST_PADDING,
+            corner_radius: 4.0,
+        }),
+    });
+}
+
+/// Panel across the top of the canvas listing `help`'s lines, faded by its alpha.
+fn push_help_panel(list: &mut DrawList, canvas: Rect, help: &HelpPanel) {
+    let height = help.lines.len() as f32 * HELP_LINE_HEIGHT + HELP_PADDING * 2.0;
+    let panel = Rect::from_min_max(
+        canvas.left_top() + egui::vec2(HELP_PADDING, HELP_PADDING),
+        Pos2::new(
+            canvas.right() - HELP_PADDING,
+            canvas.top() + HELP_PADDING + height,
+        ),
+    );
+
+    list.push(DrawShape::FilledRect {
+        rect: panel,
+        corner_radius: 4.0,
+        color: with_scaled_alpha(&Color::from_rgba_u8(0, 0, 0, 200), help.alpha),
+    });
+    for (index, line) in help.lines.iter().enumerate() {
+        list.push(DrawShape::Text {
+            pos: panel.left_top()
+                + egui::vec2(HELP_PADDING, HELP_PADDING + index as f32 * HELP_LINE_HEIGHT),
+            align: Align2::LEFT_TOP,
+            text: line.clone(),
+            font_size: HELP_FONT_SIZE,
+            color: with_scaled_alpha(&white(), help.alpha),
+            backdrop: None,
+        });
+    }
+}
+
+/// Red panel centered on the canvas with one row per line of `message`.
+fn push_error_banner(list: &mut DrawList, canvas: Rect, message: &str) {
+    let lines: Vec<&str> = message.lines().collect();
+    let height = lines.len() as f32 * HELP_LINE_HEIGHT + HELP_PADDING * 2.0;
+    let panel = Rect::from_center_size(
+        canvas.center(),
+        egui::vec2(canvas.width() - HELP_PADDING * 2.0, height),
+    );
+
+    list.push(DrawShape::FilledRect {
+        rect: panel,
+        corner_radius: 4.0,
+        color: Color::from_rgba_u8(160, 20, 20, 230),
+    });
+    for (index, line) in lines.into_iter().enumerate() {
+        list.push(DrawShape::Text {
+            pos: Pos2::new(
+                panel.center().x,
+                panel.top() + HELP_PADDING + index as f32 * HELP_LINE_HEIGHT,
+            ),
+            align: Align2::CENTER_TOP,
+            text: line.to_string(),
+            font_size: HELP_FONT_SIZE,
+            color: white(),
+            backdrop: None,
+        });
+    }
+}
+
+/// Paints `list` with egui, scaling everything by the list's opacity.
+fn paint_draw_list(painter: &egui::Painter, list: &DrawList, icons: &mut IconCache) {
+    let mut painter = painter.clone();
+    painter.set_opacity(list.opacity);
+
+    for shape in &list.shapes {
+        match shape {
+            DrawShape::FilledRect {
+                rect,
+                corner_radius,
+                color,
+            } => {
+                painter.rect_filled(*rect, *corner_radius, color.to_egui());
+            }
+            DrawShape::StrokedRect {
+                rect,
+                corner_radius,
+                thickness,
+                color,
+            } => {
+                painter.rect_stroke(
+                    *rect,
+                    *corner_radius,
+                    Stroke::new(*thickness, color.to_egui()),
+                );
+            }
+            DrawShape::Circle {
+                center,
+                radius,
+                color,
+            } => {
+                painter.circle_filled(*center, *radius, color.to_egui());
+            }
+            DrawShape::Line {
+                from,
+                to,
+                thickness,
+                color,
+            } => {
+                painter.line_segment([*from, *to], Stroke::new(*thickness, color.to_egui()));
+            }
+            DrawShape::Text {
+                pos,
+                align,
+                text,
+                font_size,
+                color,
+                backdrop,
+            } => {
+                let font = FontId::new(*font_size, FontFamily::Monospace);
+                match backdrop {
+                    None => {
+                        painter.text(*pos, *align, text, font, color.to_egui());
+                    }
+                    Some(backdrop) => {
+                        let galley = painter.layout_no_wrap(text.clone(), font, color.to_egui());
+                        let padding = egui::vec2(backdrop.padding, backdrop.padding);
+                        let rect = align.anchor_size(*pos, galley.size() + padding * 2.0);
+                        painter.rect_filled(rect, backdrop.corner_radius, backdrop.color.to_egui());
+                        painter.galley(rect.min + padding, galley, color.to_egui());
+                    }
+                }
+            }
+            DrawShape::Image { rect, path } => {
+                if let Some(texture_id) = icons.texture_id(painter.ctx(), path) {
+                    let uv = Rect::from_min_max(Pos2::ZERO, Pos2::new(1.0, 1.0));
+                    painter.image(texture_id, *rect, uv, egui::Color32::WHITE);
+                }
+            }
+        }
+    }
+}
+
+/// Background fill for the overlay, or `None` when the background is left unpainted.
+fn background_fill(config: &AppConfig) -> Option<Color> {
+    if config.transparent_background {
+        return None;
+    }
+    Some(config.background_color.clone())
+}
+
+/// Configured corner radius limited to half the smaller side of `rect`.
+fn effective_corner_radius(radius: f32, rect: Rect) -> f32 {
+    let max_radius = rect.width().min(rect.height()).max(0.0) * 0.5;
+    radius.clamp(0.0, max_radius)
+}
+
+/// Horizontal span of a column's bars: `ratio` of the key box width, centered in it.
+fn bar_horizontal_extent(left: f32, right: f32, ratio: f32) -> (f32, f32) {
+    let center = (left + right) * 0.5;
+    let half_width = (right - left) * ratio.clamp(0.0, 1.0) * 0.5;
+    (center - half_width, center + half_width)
+}
+
+/// Maps layout space, where keys sit in a row along the bottom and bars
+/// rise, onto the window for the configured key axis.
+///
+/// For [`KeyAxis::Vertical`] layout space is the window transposed: the
+/// layout bottom edge becomes the window's left edge and layout x runs
+/// down the window.
+#[derive(Debug, Clone, Copy)]
+struct AxisMap {
+    axis: KeyAxis,
+    canvas: Rect,
+}
+
+impl AxisMap {
+    fn new(axis: KeyAxis, canvas: Rect) -> Self {
+        Self { axis, canvas }
+    }
+
+    /// Canvas to lay keys and bars out in before mapping.
+    fn layout_canvas(&self) -> Rect {
+        match self.axis {
+            KeyAxis::Horizontal => self.canvas,
+            KeyAxis::Vertical => Rect::from_min_size(
+                self.canvas.min,
+                egui::vec2(self.canvas.height(), self.canvas.width()),
+            ),
+        }
+    }
+
+    fn pos(&self, pos: Pos2) -> Pos2 {
+        match self.axis {
+            KeyAxis::Horizontal => pos,
+            KeyAxis::Vertical => {
+                let layout = self.layout_canvas();
+                Pos2::new(
+                    self.canvas.left() + (layout.bottom() - pos.y),
+                    self.canvas.top() + (pos.x - layout.left()),
+                )
+            }
+        }
+    }
+
+    fn rect(&self, rect: Rect) -> Rect {
+        Rect::from_two_pos(self.pos(rect.min), self.pos(rect.max))
+    }
+
+    /// Text anchor matching `align` after mapping; text itself is never rotated.
+    fn align(&self, align: Align2) -> Align2 {
+        match self.axis {
+            KeyAxis::Horizontal => align,
+            KeyAxis::Vertical => {
+                let flipped = match align.y() {
+                    egui::Align::Min => egui::Align::Max,
+                    egui::Align::Center => egui::Align::Center,
+                    egui::Align::Max => egui::Align::Min,
+                };
+                Align2([flipped, align.x()])
+            }
+        }
+    }
+}
+
+/// Label y coordinate and text anchor for a key box spanning `key_size` below `key_top`.
+///
+/// Top and bottom keep the label clear of the outline stroke.
+fn key_label_anchor(
+    align: KeyLabelAlign,
+    key_top: f32,
+    key_size: f32,
+    outline_thickness: f32,
+) -> (f32, Align2) {
+    let inset = outline_thickness + KEY_LABEL_EDGE_PADDING;
+
+    match align {
+        KeyLabelAlign::Top => (key_top + inset, Align2::CENTER_TOP),
+        KeyLabelAlign::Center => (
+            key_top + key_size * KEY_LABEL_VERTICAL_CENTER_RATIO,
+            Align2::CENTER_CENTER,
+        ),
+        KeyLabelAlign::Bottom => (key_top + key_size - inset, Align2::CENTER_BOTTOM),
+    }
+}
+
+fn with_scaled_alpha(color: &Color, alpha_scale: f32) -> Color {
+    Color {
+        a: color.a * alpha_scale.clamp(0.0, 1.0),
+        ..color.clone()
+    }
+}
+
+/// Brightness factor for a bar pressed `interval` seconds after the previous press.
+///
+/// Falls linearly from 1.0 at `FAST_PRESS_INTERVAL_SECS` to
+/// `MIN_PRESS_INTENSITY` at `SLOW_PRESS_INTERVAL_SECS`; a press with no
+/// recent predecessor counts as slow.
+fn press_intensity(interval: Option<f32>) -> f32 {
+    let Some(interval) = interval else {
+        return MIN_PRESS_INTENSITY;
+    };
+    let slowness = ((interval - FAST_PRESS_INTERVAL_SECS)
+        / (SLOW_PRESS_INTERVAL_SECS - FAST_PRESS_INTERVAL_SECS))
+        .clamp(0.0, 1.0);
+    1.0 - slowness * (1.0 - MIN_PRESS_INTENSITY)
+}
+
+/// Scales the RGB channels of `color` by `factor`, keeping its alpha.
+fn with_scaled_brightness(color: &Color, factor: f32) -> Color {
+    let factor = factor.clamp(0.0, 1.0);
+    Color {
+        r: color.r * factor,
+        g: color.g * factor,
+        b: color.b * factor,
+        a: color.a,
+    }
+}
+
+fn white() -> Color {
+    Color::from_rgba_u8(255, 255, 255, 255)
+}
+
+/// Color of a released bar, shifted toward `trail_color` by how far it has risen.
+fn released_bar_color(bar: &Bar, trail_color: Option<&Color>, window_height: f32) -> Color {
+    match trail_color {
+        Some(trail) if window_height > 0.0 => {
+            lerp_color(&bar.color, trail, bar.y_position / window_height)
+        }
+        _ => bar.color.clone(),
+    }
+}
+
+/// Blends a just-released bar from `pressed` to `released` over `decay`.
+///
+/// Bars without a release time (e.g. restored from an older snapshot) and
+/// bars past the window use `released` as is.
+fn release_decay_color(
+    pressed: &Color,
+    released: &Color,
+    since_release: Option<f32>,
+    decay: Duration,
+) -> Color {
+    let decay_secs = decay.as_secs_f32();
+    match since_release {
+        Some(elapsed) if elapsed < decay_secs => {
+            lerp_color(pressed, released, elapsed / decay_secs)
+        }
+        _ => released.clone(),
+    }
+}
 
-    Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), scaled)
+/// Whether `key`'s bars fade, honoring its override before the global flag.
+fn key_fading_enabled(key: &KeyConfig, global_fading: bool) -> bool {
+    key.fading.unwrap_or(global_fading)
 }
 
 fn bar_fade_alpha(
@@ -321,6 +1628,127 @@ fn bar_fade_alpha(
     calculate_fade_alpha(bar_y_position, window_height, fade_height)
 }
 
+/// Slices covering the top `cap_px` of `bar_rect`, from the top edge down, each
+/// with the alpha its fill is scaled by so the cap fades out toward the top.
+///
+/// The cap is capped at the bar height and split into at most
+/// [`BAR_CAP_MAX_SLICES`] slices of roughly [`BAR_CAP_SLICE_PX`] each.
+fn bar_cap_rects(bar_rect: Rect, cap_px: f32) -> Vec<(Rect, f32)> {
+    let cap_height = cap_px.min(bar_rect.height());
+    if cap_height <= 0.0 {
+        return Vec::new();
+    }
+
+    let slices = ((cap_height / BAR_CAP_SLICE_PX).ceil() as usize).clamp(1, BAR_CAP_MAX_SLICES);
+    let slice_height = cap_height / slices as f32;
+    (0..slices)
+        .map(|index| {
+            let top = bar_rect.top() + slice_height * index as f32;
+            let slice = Rect::from_min_max(
+                Pos2::new(bar_rect.left(), top),
+                Pos2::new(bar_rect.right(), top + slice_height),
+            );
+            (slice, (index as f32 + 0.5) / slices as f32)
+        })
+        .collect()
+}
+
+/// Spacing between pattern shapes, widened so tall bars never exceed `max_shapes`.
+fn pattern_period(extent: f32, max_shapes: usize) -> f32 {
+    PATTERN_PERIOD.max(extent / max_shapes.max(1) as f32)
+}
+
+/// Horizontal stripes tiled upward from the bottom of `bar_rect`.
+fn stripe_rects(bar_rect: Rect) -> Vec<Rect> {
+    let period = pattern_period(bar_rect.height(), MAX_PATTERN_SHAPES);
+    let stripe_height = period * 0.5;
+    let mut stripes = Vec::new();
+    let mut bottom = bar_rect.bottom();
+
+    while bottom > bar_rect.top() && stripes.len() < MAX_PATTERN_SHAPES {
+        let top = (bottom - stripe_height).max(bar_rect.top());
+        stripes.push(Rect::from_min_max(
+            Pos2::new(bar_rect.left(), top),
+            Pos2::new(bar_rect.right(), bottom),
+        ));
+        bottom -= period;
+    }
+
+    stripes
+}
+
+/// Dot centers laid out in a grid tiled upward from the bottom of `bar_rect`.
+fn dot_centers(bar_rect: Rect) -> Vec<Pos2> {
+    let columns = ((bar_rect.width() / PATTERN_PERIOD).floor() as usize).max(1);
+    let column_spacing = bar_rect.width() / columns as f32;
+    let row_spacing = pattern_period(bar_rect.height(), MAX_PATTERN_SHAPES / columns);
+    let mut centers = Vec::new();
+    let mut y = bar_rect.bottom() - row_spacing * 0.5;
+
+    while y > bar_rect.top() && centers.len() + columns <= MAX_PATTERN_SHAPES {
+        for column in 0..columns {
+            let x = bar_rect.left() + column_spacing * (column as f32 + 0.5);
+            centers.push(Pos2::new(x, y));
+        }
+        y -= row_spacing;
+    }
+
+    centers
+}
+
+/// Top-left of the work area of monitor `index`, falling back to monitor 0 when
+/// the index is out of range. Returns `None` when no monitors are connected.
+fn resolve_monitor_origin(monitors: &[Rect], index: usize) -> Option<Pos2> {
+    let monitor = monitors.get(index).or_else(|| {
+        if !monitors.is_empty() {
+            warn!(
+                "monitorIndex {index} is out of range ({} connected); using monitor 0",
+                monitors.len()
+            );
+        }
+        monitors.first()
+    })?;
+    Some(monitor.min)
+}
+
+/// Outcome of reconciling the window with its configured position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum WindowPositionUpdate {
+    /// Nothing is configured, or the configured position was already applied.
+    Keep,
+    /// The window already sits at a newly configured position; record it as applied.
+    AlreadyThere((i32, i32)),
+    /// Move the window to this newly configured position.
+    Move((i32, i32)),
+}
+
+/// Decides whether to move the window to its configured position.
+///
+/// Unset axes keep the current position. Only a configured position that changed
+/// since it was last applied is acted on, so later user drags are left alone.
+fn window_position_update(
+    configured: (Option<i32>, Option<i32>),
+    last_applied: Option<(i32, i32)>,
+    current: (i32, i32),
+) -> WindowPositionUpdate {
+    if configured.0.is_none() && configured.1.is_none() {
+        return WindowPositionUpdate::Keep;
+    }
+
+    let desired = (
+        configured.0.unwrap_or(current.0),
+        configured.1.unwrap_or(current.1),
+    );
+
+    if last_applied == Some(desired) {
+        WindowPositionUpdate::Keep
+    } else if desired == current {
+        WindowPositionUpdate::AlreadyThere(desired)
+    } else {
+        WindowPositionUpdate::Move(desired)
+    }
+}
+
 fn window_size_needs_update(current: [f32; 2], desired: [f32; 2]) -> bool {
     (current[0] - desired[0]).abs() > WINDOW_SIZE_EPSILON
         || (current[1] - desired[1]).abs() > WINDOW_SIZE_EPSILON
@@ -328,8 +1756,31 @@ fn window_size_needs_update(current: [f32; 2], desired: [f32; 2]) -> bool {
 
 #[cfg(test)]
 mod tests {
-    use super::Renderer;
-    use crate::types::{AppConfig, Color, KeyConfig};
+    use std::path::Path;
+    use std::sync::{Arc, Mutex};
+
+    use egui::{Align2, Pos2, Rect};
+
+    use std::time::Duration;
+
+    use super::{EventLog, Renderer, WindowPositionUpdate};
+    use crate::input::KeyId;
+    use crate::observer::{EventKind, PressObserver};
+    use crate::types::{AppConfig, Color, KeyAxis, KeyConfig, KeyLabelAlign};
+
+    #[derive(Debug, Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<(String, Color, EventKind)>>,
+    }
+
+    impl PressObserver for RecordingObserver {
+        fn on_event(&self, key: &KeyConfig, kind: EventKind) {
+            self.events
+                .lock()
+                .expect("observer lock should not be poisoned")
+                .push((key.key_name.clone(), key.color.clone(), kind));
+        }
+    }
 
     const EPSILON: f32 = 1e-6;
 
@@ -348,17 +1799,10 @@ mod tests {
             margin: 25.0,
             outline_thickness: 5.0,
             keys: vec![
+                KeyConfig::new("Z", Color::black()),
                 KeyConfig {
-                    key_name: "Z".to_string(),
-                    display_name: "Z".to_string(),
-                    color: Color::black(),
-                    size: 1.0,
-                },
-                KeyConfig {
-                    key_name: "X".to_string(),
-                    display_name: "X".to_string(),
-                    color: Color::black(),
                     size: 1.5,
+                    ..KeyConfig::new("X", Color::black())
                 },
             ],
             ..AppConfig::default()
@@ -373,47 +1817,1408 @@ mod tests {
     }
 
     #[test]
-    fn test_window_size_needs_update_when_difference_exceeds_epsilon() {
-        assert!(super::window_size_needs_update(
-            [100.0, 200.0],
-            [101.0, 200.0]
-        ));
-        assert!(super::window_size_needs_update(
-            [100.0, 200.0],
-            [100.0, 201.0]
-        ));
+    fn test_resolve_monitor_origin_picks_requested_monitor() {
+        let monitors = [
+            Rect::from_min_size(Pos2::new(0.0, 0.0), egui::vec2(1920.0, 1040.0)),
+            Rect::from_min_size(Pos2::new(-1280.0, 200.0), egui::vec2(1280.0, 984.0)),
+        ];
+
+        assert_eq!(
+            super::resolve_monitor_origin(&monitors, 1),
+            Some(Pos2::new(-1280.0, 200.0))
+        );
+        assert_eq!(
+            super::resolve_monitor_origin(&monitors, 0),
+            Some(Pos2::new(0.0, 0.0))
+        );
     }
 
     #[test]
-    fn test_window_size_does_not_need_update_within_epsilon() {
-        assert!(!super::window_size_needs_update(
-            [100.0, 200.0],
-            [100.4, 200.0]
-        ));
-        assert!(!super::window_size_needs_update(
-            [100.0, 200.0],
-            [100.0, 200.4]
-        ));
+    fn test_resolve_monitor_origin_out_of_range_falls_back_to_first() {
+        let monitors = [Rect::from_min_size(
+            Pos2::new(10.0, 20.0),
+            egui::vec2(800.0, 600.0),
+        )];
+
+        assert_eq!(
+            super::resolve_monitor_origin(&monitors, 3),
+            Some(Pos2::new(10.0, 20.0))
+        );
+        assert_eq!(super::resolve_monitor_origin(&[], 0), None);
     }
 
     #[test]
-    fn test_bar_fade_alpha_keeps_active_held_bar_fully_opaque() {
-        let alpha = super::bar_fade_alpha(true, true, 790.0, 800.0, 200.0);
-        assert_f32_eq(alpha, 1.0);
+    fn test_window_position_update_applies_configured_position_once() {
+        let first = super::window_position_update((Some(-1920), Some(40)), None, (0, 0));
+        assert_eq!(first, WindowPositionUpdate::Move((-1920, 40)));
+
+        let applied = Some((-1920, 40));
+        let after_user_drag =
+            super::window_position_update((Some(-1920), Some(40)), applied, (100, 100));
+        assert_eq!(after_user_drag, WindowPositionUpdate::Keep);
+
+        let changed = super::window_position_update((Some(10), Some(40)), applied, (100, 100));
+        assert_eq!(changed, WindowPositionUpdate::Move((10, 40)));
     }
 
     #[test]
-    fn test_bar_fade_alpha_applies_to_non_active_bars() {
-        let alpha = super::bar_fade_alpha(true, false, 790.0, 800.0, 200.0);
-        let expected = super::calculate_fade_alpha(790.0, 800.0, 200.0);
-        assert_f32_eq(alpha, expected);
+    fn test_window_position_update_skips_when_already_in_place_or_unset() {
+        assert_eq!(
+            super::window_position_update((Some(5), Some(6)), None, (5, 6)),
+            WindowPositionUpdate::AlreadyThere((5, 6))
+        );
+        assert_eq!(
+            super::window_position_update((None, None), None, (5, 6)),
+            WindowPositionUpdate::Keep
+        );
+        assert_eq!(
+            super::window_position_update((None, Some(-30)), None, (5, 6)),
+            WindowPositionUpdate::Move((5, -30))
+        );
     }
 
     #[test]
-    fn test_bar_fade_alpha_non_active_bar_starts_opaque_after_release() {
-        // Released bars start moving from y=0 regardless of hold duration,
-        // so they should not instantly fade out on release.
-        let alpha = super::bar_fade_alpha(true, false, 0.0, 800.0, 200.0);
-        assert_f32_eq(alpha, 1.0);
+    fn test_window_already_at_configured_position_is_not_snapped_back_after_drag() {
+        let mut renderer = Renderer::new(AppConfig {
+            window_x: Some(5),
+            window_y: Some(6),
+            ..AppConfig::default()
+        });
+
+        assert_eq!(renderer.next_window_move((5, 6)), None);
+        assert_eq!(renderer.next_window_move((100, 100)), None);
+
+        renderer.set_config(AppConfig {
+            window_x: Some(10),
+            window_y: Some(6),
+            ..AppConfig::default()
+        });
+        assert_eq!(renderer.next_window_move((100, 100)), Some((10, 6)));
+    }
+
+    #[test]
+    fn test_axis_map_vertical_puts_keys_on_left_and_bars_flowing_right() {
+        let canvas = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(700.0, 235.0));
+        let map = super::AxisMap::new(KeyAxis::Vertical, canvas);
+        let layout = map.layout_canvas();
+        assert_f32_eq(layout.width(), 235.0);
+        assert_f32_eq(layout.height(), 700.0);
+
+        // A key box at the layout bottom, second slot in the row.
+        let key_box = egui::Rect::from_min_max(egui::pos2(130.0, 630.0), egui::pos2(200.0, 700.0));
+        let mapped = map.rect(key_box);
+        assert_eq!(mapped.min, egui::pos2(0.0, 130.0));
+        assert_eq!(mapped.max, egui::pos2(70.0, 200.0));
+
+        // Rising in layout space moves right on screen.
+        assert_f32_eq(map.pos(egui::pos2(130.0, 100.0)).x, 600.0);
+        assert_eq!(
+            map.align(egui::Align2::CENTER_BOTTOM),
+            egui::Align2::LEFT_CENTER
+        );
+    }
+
+    #[test]
+    fn test_axis_map_horizontal_is_identity() {
+        let canvas = egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(235.0, 700.0));
+        let map = super::AxisMap::new(KeyAxis::Horizontal, canvas);
+        let rect = egui::Rect::from_min_max(egui::pos2(25.0, 10.0), egui::pos2(95.0, 80.0));
+
+        assert_eq!(map.layout_canvas(), canvas);
+        assert_eq!(map.rect(rect), rect);
+        assert_eq!(
+            map.align(egui::Align2::CENTER_TOP),
+            egui::Align2::CENTER_TOP
+        );
+    }
+
+    #[test]
+    fn test_repaint_interval_uses_idle_fps_only_while_idle() {
+        let mut renderer = Renderer::new(AppConfig {
+            fps: 60,
+            idle_fps: Some(5),
+            ..AppConfig::default()
+        });
+        assert_eq!(
+            renderer.repaint_interval(),
+            Duration::from_secs_f32(1.0 / 5.0)
+        );
+
+        renderer.on_key_press("Z");
+        assert_eq!(
+            renderer.repaint_interval(),
+            Duration::from_secs_f32(1.0 / 60.0)
+        );
+    }
+
+    #[test]
+    fn test_repaint_interval_clamps_fps_at_both_bounds() {
+        let fast = Renderer::new(AppConfig {
+            fps: 1000,
+            ..AppConfig::default()
+        });
+        let stopped = Renderer::new(AppConfig {
+            fps: 0,
+            ..AppConfig::default()
+        });
+
+        assert_eq!(
+            fast.repaint_interval(),
+            Duration::from_secs_f32(1.0 / super::MAX_FPS as f32)
+        );
+        assert_eq!(stopped.repaint_interval(), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_repaint_interval_without_idle_fps_keeps_fps() {
+        let renderer = Renderer::new(AppConfig {
+            fps: 30,
+            ..AppConfig::default()
+        });
+
+        assert_eq!(
+            renderer.repaint_interval(),
+            Duration::from_secs_f32(1.0 / 30.0)
+        );
+    }
+
+    #[test]
+    fn test_scaled_font_size_applies_configured_scale() {
+        assert_f32_eq(super::scaled_font_size(100.0, 0.32, 12.0), 32.0);
+        assert_f32_eq(super::scaled_font_size(100.0, 0.5, 12.0), 50.0);
+    }
+
+    #[test]
+    fn test_scaled_font_size_respects_minimum_floor() {
+        assert_f32_eq(super::scaled_font_size(70.0, 0.05, 12.0), 12.0);
+        assert_f32_eq(super::scaled_font_size(20.0, 0.24, 10.0), 10.0);
+    }
+
+    #[test]
+    fn test_smooth_dt_zero_factor_passes_raw_delta_through() {
+        assert_f32_eq(super::smooth_dt(0.016, 0.05, 0.0), 0.05);
+    }
+
+    #[test]
+    fn test_smooth_dt_blends_toward_raw_delta() {
+        assert_f32_eq(super::smooth_dt(0.02, 0.06, 0.75), 0.03);
+        assert_f32_eq(super::smooth_dt(0.02, 0.06, 1.0), 0.02);
+    }
+
+    #[test]
+    fn test_smooth_dt_damps_a_single_spike() {
+        let mut smoothed = 0.016;
+        for raw in [0.016, 0.1, 0.016] {
+            smoothed = super::smooth_dt(smoothed, raw, 0.8);
+            assert!(smoothed < 0.04, "spike leaked through: {smoothed}");
+        }
+    }
+
+    #[test]
+    fn test_smooth_dt_clamps_out_of_range_factor() {
+        assert_f32_eq(super::smooth_dt(0.02, 0.06, -1.0), 0.06);
+        assert_f32_eq(super::smooth_dt(0.02, 0.06, 2.0), 0.02);
+    }
+
+    #[test]
+    fn test_step_overlay_alpha_fades_out_slowly_and_in_quickly() {
+        let frame = 1.0 / 60.0;
+        let mut alpha = 1.0;
+
+        for _ in 0..10 {
+            alpha = super::step_overlay_alpha(alpha, true, frame);
+        }
+        assert!(alpha > 0.0 && alpha < 1.0);
+
+        for _ in 0..60 {
+            alpha = super::step_overlay_alpha(alpha, true, frame);
+        }
+        assert_eq!(alpha, 0.0);
+
+        for _ in 0..3 {
+            alpha = super::step_overlay_alpha(alpha, false, frame);
+        }
+        assert_eq!(alpha, 1.0);
+    }
+
+    #[test]
+    fn test_grid_line_positions_step_by_spacing_inside_extent() {
+        assert_eq!(
+            super::grid_line_positions(50.0, 235.0),
+            vec![50.0, 100.0, 150.0, 200.0]
+        );
+        assert_eq!(super::grid_line_positions(50.0, 100.0), vec![50.0]);
+        assert!(super::grid_line_positions(50.0, 40.0).is_empty());
+        assert!(super::grid_line_positions(0.0, 700.0).is_empty());
+    }
+
+    #[test]
+    fn test_key_label_anchor_places_label_within_key_box() {
+        let key_top = 500.0;
+        let key_size = 70.0;
+        let key_bottom = key_top + key_size;
+
+        let (top_y, top_align) =
+            super::key_label_anchor(KeyLabelAlign::Top, key_top, key_size, 5.0);
+        assert_eq!(top_y, key_top + 9.0);
+        assert_eq!(top_align, Align2::CENTER_TOP);
+
+        let (center_y, center_align) =
+            super::key_label_anchor(KeyLabelAlign::Center, key_top, key_size, 5.0);
+        assert_eq!(center_y, key_top + 42.0);
+        assert_eq!(center_align, Align2::CENTER_CENTER);
+
+        let (bottom_y, bottom_align) =
+            super::key_label_anchor(KeyLabelAlign::Bottom, key_top, key_size, 5.0);
+        assert_eq!(bottom_y, key_bottom - 9.0);
+        assert_eq!(bottom_align, Align2::CENTER_BOTTOM);
+
+        assert!(top_y < center_y && center_y < bottom_y);
+    }
+
+    #[test]
+    fn test_window_size_needs_update_when_difference_exceeds_epsilon() {
+        assert!(super::window_size_needs_update(
+            [100.0, 200.0],
+            [101.0, 200.0]
+        ));
+        assert!(super::window_size_needs_update(
+            [100.0, 200.0],
+            [100.0, 201.0]
+        ));
+    }
+
+    #[test]
+    fn test_window_size_does_not_need_update_within_epsilon() {
+        assert!(!super::window_size_needs_update(
+            [100.0, 200.0],
+            [100.4, 200.0]
+        ));
+        assert!(!super::window_size_needs_update(
+            [100.0, 200.0],
+            [100.0, 200.4]
+        ));
+    }
+
+    #[test]
+    fn test_bar_fade_alpha_keeps_active_held_bar_fully_opaque() {
+        let alpha = super::bar_fade_alpha(true, true, 790.0, 800.0, 200.0);
+        assert_f32_eq(alpha, 1.0);
+    }
+
+    #[test]
+    fn test_key_with_fading_disabled_keeps_full_alpha_everywhere() {
+        let mut config = AppConfig::default();
+        config.keys[0].fading = Some(false);
+        config.keys[1].fading = None;
+
+        let pinned = super::key_fading_enabled(&config.keys[0], true);
+        let inherited = super::key_fading_enabled(&config.keys[1], true);
+
+        for y_position in [0.0, 400.0, 650.0, 699.0] {
+            assert_eq!(
+                super::bar_fade_alpha(pinned, false, y_position, 700.0, 175.0),
+                1.0
+            );
+        }
+        assert!(super::bar_fade_alpha(inherited, false, 650.0, 700.0, 175.0) < 1.0);
+    }
+
+    #[test]
+    fn test_bar_fade_alpha_applies_to_non_active_bars() {
+        let alpha = super::bar_fade_alpha(true, false, 790.0, 800.0, 200.0);
+        let expected = super::calculate_fade_alpha(790.0, 800.0, 200.0);
+        assert_f32_eq(alpha, expected);
+    }
+
+    #[test]
+    fn test_bar_fade_alpha_non_active_bar_starts_opaque_after_release() {
+        // Released bars start moving from y=0 regardless of hold duration,
+        // so they should not instantly fade out on release.
+        let alpha = super::bar_fade_alpha(true, false, 0.0, 800.0, 200.0);
+        assert_f32_eq(alpha, 1.0);
+    }
+
+    #[test]
+    fn test_stripe_rects_tile_bar_from_bottom() {
+        let bar = Rect::from_min_max(Pos2::new(10.0, 0.0), Pos2::new(80.0, 60.0));
+
+        let stripes = super::stripe_rects(bar);
+
+        // period = 12, stripe height = 6: [54,60], [42,48], [30,36], [18,24], [6,12]
+        assert_eq!(stripes.len(), 5);
+        assert_f32_eq(stripes[0].bottom(), 60.0);
+        assert_f32_eq(stripes[0].top(), 54.0);
+        assert_f32_eq(stripes[4].bottom(), 12.0);
+        assert_f32_eq(stripes[4].top(), 6.0);
+        for stripe in &stripes {
+            assert_f32_eq(stripe.left(), 10.0);
+            assert_f32_eq(stripe.right(), 80.0);
+            assert!(bar.contains_rect(*stripe));
+        }
+    }
+
+    #[test]
+    fn test_stripe_rects_limit_detail_for_tall_bars() {
+        let bar = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(70.0, 10_000.0));
+
+        let stripes = super::stripe_rects(bar);
+
+        assert!(stripes.len() <= super::MAX_PATTERN_SHAPES);
+        assert!(stripes.iter().all(|stripe| bar.contains_rect(*stripe)));
+    }
+
+    #[test]
+    fn test_dot_centers_stay_inside_bar_and_respect_shape_limit() {
+        let bar = Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(70.0, 5_000.0));
+
+        let centers = super::dot_centers(bar);
+
+        assert!(!centers.is_empty());
+        assert!(centers.len() <= super::MAX_PATTERN_SHAPES);
+        assert!(centers.iter().all(|center| bar.contains(*center)));
+    }
+
+    #[test]
+    fn test_generic_modifier_column_is_fed_by_either_side() {
+        let config =
+            crate::config::load_from_str("[[key]]\nname = \"Ctrl\"\n").expect("config should load");
+        let mut renderer = Renderer::new(config);
+
+        renderer.on_key_press("LControl");
+        renderer.on_key_release("LControl");
+        renderer.on_key_press("RControl");
+
+        let column = &renderer.bar_manager.columns["Control"];
+        assert_eq!(column.press_count, 2);
+        assert!(column.is_held);
+
+        renderer.on_key_release("RControl");
+        assert!(!renderer.bar_manager.columns["Control"].is_held);
+        assert_eq!(renderer.metrics().events_dropped, 0);
+    }
+
+    #[test]
+    fn test_generic_modifier_column_stays_held_until_both_sides_release() {
+        let config =
+            crate::config::load_from_str("[[key]]\nname = \"Ctrl\"\n").expect("config should load");
+        let mut renderer = Renderer::new(config);
+
+        renderer.on_key_press("LControl");
+        renderer.on_key_press("RControl");
+        renderer.on_key_release("LControl");
+
+        let column = &renderer.bar_manager.columns["Control"];
+        assert!(column.is_held, "RControl is still down");
+        assert_eq!(column.bars.len(), 1);
+
+        renderer.on_key_release("RControl");
+        assert!(!renderer.bar_manager.columns["Control"].is_held);
+
+        renderer.on_key_press("RControl");
+        assert!(renderer.bar_manager.columns["Control"].is_held);
+        assert_eq!(renderer.bar_manager.columns["Control"].bars.len(), 2);
+    }
+
+    #[test]
+    fn test_exact_side_key_wins_over_generic_modifier() {
+        let config =
+            crate::config::load_from_str("[[key]]\nname = \"Ctrl\"\n\n[[key]]\nname = \"LCtrl\"\n")
+                .expect("config should load");
+        let mut renderer = Renderer::new(config);
+
+        renderer.on_key_press("LControl");
+        renderer.on_key_press("RControl");
+
+        assert_eq!(renderer.bar_manager.columns["LControl"].press_count, 1);
+        assert_eq!(renderer.bar_manager.columns["Control"].press_count, 1);
+    }
+
+    #[test]
+    fn test_aliases_feed_the_shared_column() {
+        let config =
+            crate::config::load_from_str("[[key]]\nname = \"Up\"\naliases = [\"w\", \"Space\"]\n")
+                .expect("config should load");
+        let mut renderer = Renderer::new(config);
+
+        for key in ["W", "Up", "Space"] {
+            renderer.on_key_press(key);
+            assert!(renderer.bar_manager.columns["Up"].is_held);
+            renderer.on_key_release(key);
+            assert!(!renderer.bar_manager.columns["Up"].is_held);
+        }
+
+        assert_eq!(renderer.bar_manager.columns.len(), 1);
+        assert_eq!(renderer.bar_manager.columns["Up"].press_count, 3);
+        assert_eq!(renderer.metrics().events_dropped, 0);
+    }
+
+    #[test]
+    fn test_overlapping_alias_presses_hold_the_column_until_both_release() {
+        let config = crate::config::load_from_str("[[key]]\nname = \"Up\"\naliases = [\"W\"]\n")
+            .expect("config should load");
+        let mut renderer = Renderer::new(config);
+
+        renderer.on_key_press("W");
+        renderer.on_key_press("Up");
+        renderer.on_key_release("W");
+
+        let column = &renderer.bar_manager.columns["Up"];
+        assert!(column.is_held, "Up is still down");
+        assert_eq!(column.bars.len(), 1);
+
+        renderer.on_key_release("Up");
+        assert!(!renderer.bar_manager.columns["Up"].is_held);
+    }
+
+    #[test]
+    fn test_key_with_own_entry_wins_over_alias() {
+        let config = crate::config::load_from_str(
+            "[[key]]\nname = \"Up\"\naliases = [\"W\"]\n\n[[key]]\nname = \"W\"\n",
+        )
+        .expect("config should load");
+        let mut renderer = Renderer::new(config);
+
+        renderer.on_key_press("W");
+
+        assert_eq!(renderer.bar_manager.columns["W"].press_count, 1);
+        assert!(!renderer.bar_manager.columns.contains_key("Up"));
+    }
+
+    #[test]
+    fn test_set_config_keeps_counters_for_retained_keys_and_drops_removed() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        renderer.on_key_press("Z");
+        renderer.on_key_press("X");
+
+        let mut reloaded = AppConfig::default();
+        let new_color = Color::from_rgba_u8(0, 255, 0, 255);
+        reloaded.keys[0].color = new_color.clone();
+        reloaded.keys.truncate(1);
+        renderer.set_config(reloaded);
+
+        let column = &renderer.bar_manager.columns["Z"];
+        assert_eq!(column.press_count, 2);
+        assert_eq!(column.bars.len(), 2);
+        assert!(column.is_held);
+        assert!(!renderer.bar_manager.columns.contains_key("X"));
+
+        renderer.on_key_release("Z");
+        renderer.on_key_press("Z");
+        assert_eq!(renderer.bar_manager.columns["Z"].bars[2].color, new_color);
+    }
+
+    fn recolored(config: &AppConfig, color: Color, transition_ms: u64) -> AppConfig {
+        let mut reloaded = config.clone();
+        reloaded.keys[0].color = color;
+        reloaded.color_transition_ms = transition_ms;
+        reloaded
+    }
+
+    #[test]
+    fn test_color_transition_interpolates_by_elapsed_time() {
+        let old = Color::from_rgba_u8(0, 0, 0, 255);
+        let new = Color::from_rgba_u8(200, 100, 0, 255);
+        let mut transition = super::ColorTransition {
+            from: [("Z".to_string(), old.clone())].into(),
+            elapsed: 0.0,
+            duration: 0.4,
+        };
+
+        assert_eq!(transition.color("Z", &new), old);
+        transition.elapsed = 0.2;
+        assert_eq!(
+            transition.color("Z", &new),
+            crate::color::lerp_color(&old, &new, 0.5)
+        );
+        assert!(!transition.is_finished());
+        transition.elapsed = 0.5;
+        assert_eq!(transition.color("Z", &new), new);
+        assert!(transition.is_finished());
+        assert_eq!(
+            transition.color("X", &new),
+            new,
+            "unchanged keys use the target"
+        );
+    }
+
+    #[test]
+    fn test_set_config_with_color_transition_blends_changed_keys_then_finishes() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        let old = renderer.config().keys[0].color.clone();
+        let new = Color::from_rgba_u8(0, 255, 0, 255);
+
+        renderer.set_config(recolored(renderer.config(), new.clone(), 400));
+
+        let z_key = renderer.config().keys[0].clone();
+        let x_key = renderer.config().keys[1].clone();
+        assert_eq!(renderer.key_color(&z_key), old);
+        assert_eq!(renderer.key_color(&x_key), x_key.color);
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        assert_eq!(renderer.bar_manager.columns["Z"].bars[1].color, old);
+
+        renderer.tick_color_transition(0.2);
+        assert_eq!(
+            renderer.key_color(&z_key),
+            crate::color::lerp_color(&old, &new, 0.5)
+        );
+        assert!(renderer.color_transition.is_some());
+
+        renderer.tick_color_transition(0.2);
+        assert_eq!(renderer.key_color(&z_key), new);
+        assert!(renderer.color_transition.is_none());
+        renderer.on_key_press("Z");
+        assert_eq!(renderer.bar_manager.columns["Z"].bars[2].color, new);
+    }
+
+    #[test]
+    fn test_set_config_without_color_transition_switches_instantly() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        let new = Color::from_rgba_u8(0, 255, 0, 255);
+
+        renderer.set_config(recolored(renderer.config(), new.clone(), 0));
+
+        assert!(renderer.color_transition.is_none());
+        assert_eq!(renderer.key_color(&renderer.config().keys[0]), new);
+    }
+
+    #[test]
+    fn test_background_fill_skipped_for_transparent_background() {
+        let config = AppConfig {
+            transparent_background: true,
+            ..AppConfig::default()
+        };
+        assert_eq!(super::background_fill(&config), None);
+
+        let opaque = AppConfig::default();
+        assert_eq!(
+            super::background_fill(&opaque),
+            Some(opaque.background_color.clone())
+        );
+    }
+
+    fn draw_list_canvas(renderer: &Renderer) -> egui::Rect {
+        let [width, height] = renderer.desired_window_size();
+        egui::Rect::from_min_size(egui::pos2(0.0, 0.0), egui::vec2(width, height))
+    }
+
+    #[test]
+    fn test_build_draw_list_idle_config_has_background_and_key_borders() {
+        let renderer = Renderer::new(AppConfig::default());
+
+        let list = renderer.build_draw_list(draw_list_canvas(&renderer));
+
+        // Background plus one outline per key; no bars yet.
+        assert_eq!(list.rect_count(), 3);
+        assert_eq!(list.texts().collect::<Vec<_>>(), vec!["Z", "0", "X", "0"]);
+        assert_f32_eq(list.opacity, 1.0);
+    }
+
+    #[test]
+    fn test_build_draw_list_draws_error_banner_rows() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        let canvas = draw_list_canvas(&renderer);
+
+        renderer.set_error_banner(Some("first row\nsecond row".to_string()));
+        let list = renderer.build_draw_list(canvas);
+        assert!(list.texts().any(|text| text == "first row"));
+        assert!(list.texts().any(|text| text == "second row"));
+
+        renderer.set_error_banner(None);
+        let list = renderer.build_draw_list(canvas);
+        assert!(!list.texts().any(|text| text == "first row"));
+    }
+
+    #[test]
+    fn test_build_draw_list_shows_apm_only_when_enabled() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        for key in ["Z", "X", "Z"] {
+            renderer.on_key_press(key);
+            renderer.on_key_release(key);
+        }
+        renderer.bar_manager.update(0.1);
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+        assert!(!list.texts().any(|text| text.ends_with("apm")));
+
+        renderer.set_config(AppConfig {
+            show_apm: true,
+            ..AppConfig::default()
+        });
+        let list = renderer.build_draw_list(canvas);
+        assert!(list.texts().any(|text| text == "3 apm"));
+    }
+
+    fn icon_config(icon_path: &Path) -> AppConfig {
+        let mut config = AppConfig::default();
+        config.keys[0].icon_path = Some(icon_path.to_path_buf());
+        config
+    }
+
+    fn images(list: &crate::draw_list::DrawList) -> Vec<(egui::Rect, &Path)> {
+        list.shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                crate::draw_list::DrawShape::Image { rect, path } => Some((*rect, path.as_path())),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_draw_list_draws_loaded_icon_instead_of_label() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let icon = dir.path().join("mouse.png");
+        tiny_skia::Pixmap::new(8, 8)
+            .expect("pixmap")
+            .save_png(&icon)
+            .expect("write icon");
+        let renderer = Renderer::new(icon_config(&icon));
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let images = images(&list);
+        assert_eq!(images.len(), 1);
+        assert_eq!(images[0].1, icon.as_path());
+        assert_f32_eq(images[0].0.width(), 70.0 * super::KEY_ICON_SIZE_RATIO);
+        assert_eq!(list.texts().collect::<Vec<_>>(), vec!["0", "X", "0"]);
+    }
+
+    #[test]
+    fn test_build_draw_list_falls_back_to_label_when_icon_fails_to_load() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let renderer = Renderer::new(icon_config(&dir.path().join("missing.png")));
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        assert!(images(&list).is_empty());
+        assert_eq!(list.texts().collect::<Vec<_>>(), vec!["Z", "0", "X", "0"]);
+    }
+
+    #[test]
+    fn test_toggle_counter_hides_counter_text_until_reset() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        let canvas = draw_list_canvas(&renderer);
+
+        renderer.toggle_counter();
+        assert!(!renderer.is_counter_visible());
+        let hidden = renderer.build_draw_list(canvas);
+        assert_eq!(hidden.texts().collect::<Vec<_>>(), vec!["Z", "X"]);
+        assert_f32_eq(renderer.key_bottom(canvas), canvas.bottom());
+
+        renderer.reset_counter_visibility();
+        assert!(renderer.is_counter_visible());
+        let shown = renderer.build_draw_list(canvas);
+        assert_eq!(shown.texts().collect::<Vec<_>>(), vec!["Z", "0", "X", "0"]);
+    }
+
+    #[test]
+    fn test_initial_count_shows_in_counter_without_presses_or_bars() {
+        let mut config = AppConfig::default();
+        config.keys[0].initial_count = Some(1234);
+        let mut renderer = Renderer::new(config);
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        assert_eq!(
+            list.texts().collect::<Vec<_>>(),
+            vec!["Z", "1234", "X", "0"]
+        );
+        assert!(renderer.bar_manager.columns["Z"].bars.is_empty());
+        assert!(renderer.bar_manager.is_idle());
+
+        renderer.on_key_press("Z");
+        assert_eq!(renderer.bar_manager.columns["Z"].press_count, 1235);
+    }
+
+    #[test]
+    fn test_build_draw_list_adds_fill_and_stroke_per_visible_bar() {
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        renderer.on_key_press("Z");
+        renderer.on_key_press("X");
+
+        let list = renderer.build_draw_list(draw_list_canvas(&renderer));
+
+        // Two key outlines plus a fill and stroke for each of the three bars.
+        assert_eq!(list.rect_count(), 2 + 3 * 2);
+        assert!(list.texts().any(|text| text == "2"));
+    }
+
+    #[test]
+    fn test_build_draw_list_merge_bars_draws_overlapping_bars_once() {
+        let config = AppConfig {
+            transparent_background: true,
+            keys: AppConfig::default().keys[..1].to_vec(),
+            ..AppConfig::default()
+        };
+        let mut separate = Renderer::new(config.clone());
+        let mut merged = Renderer::new(AppConfig {
+            merge_bars: true,
+            ..config
+        });
+        for renderer in [&mut separate, &mut merged] {
+            for _ in 0..2 {
+                renderer.on_key_press("Z");
+                renderer.on_key_release("Z");
+            }
+        }
+
+        let canvas = draw_list_canvas(&separate);
+
+        // One key outline plus a fill and stroke per drawn rect.
+        assert_eq!(separate.build_draw_list(canvas).rect_count(), 1 + 2 * 2);
+        assert_eq!(merged.build_draw_list(canvas).rect_count(), 1 + 2);
+    }
+
+    #[test]
+    fn test_non_ascii_display_names_build_and_rasterize() {
+        let mut config = AppConfig::default();
+        config.keys[0].display_name = "↑".to_string();
+        config.keys[1].display_name = "🎹".to_string();
+        let renderer = Renderer::new(config);
+
+        let list = renderer.build_draw_list(draw_list_canvas(&renderer));
+        assert!(list.texts().any(|text| text == "↑"));
+        assert!(list.texts().any(|text| text == "🎹"));
+
+        renderer
+            .render_png_bytes(120, 120)
+            .expect("missing glyphs should not fail the snapshot");
+    }
+
+    #[test]
+    fn test_press_intensity_maps_intervals_between_fast_and_slow() {
+        use super::{MIN_PRESS_INTENSITY, press_intensity};
+
+        assert_f32_eq(press_intensity(Some(0.0)), 1.0);
+        assert_f32_eq(press_intensity(Some(0.05)), 1.0);
+        // Halfway between 0.05s and 0.5s is halfway between 1.0 and 0.4.
+        assert_f32_eq(press_intensity(Some(0.275)), 0.7);
+        assert_f32_eq(press_intensity(Some(0.5)), MIN_PRESS_INTENSITY);
+        assert_f32_eq(press_intensity(Some(3.0)), MIN_PRESS_INTENSITY);
+        assert_f32_eq(press_intensity(None), MIN_PRESS_INTENSITY);
+        assert!(press_intensity(Some(0.1)) > press_intensity(Some(0.3)));
+    }
+
+    #[test]
+    fn test_with_scaled_brightness_keeps_alpha() {
+        let color = Color::new(1.0, 0.5, 0.2, 0.8);
+
+        assert_eq!(
+            super::with_scaled_brightness(&color, 0.5),
+            Color::new(0.5, 0.25, 0.1, 0.8)
+        );
+    }
+
+    #[test]
+    fn test_build_draw_list_ghost_bar_fades_at_window_top() {
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            fading: false,
+            ghost_trail_ms: 400,
+            keys: vec![AppConfig::default().keys[0].clone()],
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.bar_manager.update(0.05);
+        renderer.on_key_release("Z");
+        let track_height = renderer.bar_track_height();
+        for _ in 0..1_000 {
+            if renderer.bar_manager.columns["Z"].bars[0]
+                .ghost_since
+                .is_some()
+            {
+                break;
+            }
+            renderer.bar_manager.update(0.01);
+            renderer.bar_manager.remove_offscreen(track_height);
+        }
+        renderer.bar_manager.update(0.2);
+        renderer.bar_manager.remove_offscreen(track_height);
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let key_color = &renderer.config().keys[0].color;
+        let (rect, color) = list
+            .shapes
+            .iter()
+            .find_map(|shape| match shape {
+                crate::draw_list::DrawShape::FilledRect { rect, color, .. } => {
+                    Some((*rect, color.clone()))
+                }
+                _ => None,
+            })
+            .expect("ghost bar should still be drawn");
+        assert_f32_eq(rect.top(), canvas.top());
+        assert_f32_eq(color.r, key_color.r);
+        assert_f32_eq(color.a, key_color.a * 0.5);
+    }
+
+    #[test]
+    fn test_build_draw_list_velocity_coloring_brightens_rapid_presses() {
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            fading: false,
+            velocity_coloring: true,
+            keys: vec![AppConfig::default().keys[0].clone()],
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        renderer.bar_manager.update(0.02);
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        let bars = &renderer.bar_manager.columns["Z"].bars;
+        assert_eq!(bars[0].press_interval, None);
+        assert_f32_eq(bars[1].press_interval.expect("second press interval"), 0.02);
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let fills: Vec<(f32, Color)> = list
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                crate::draw_list::DrawShape::FilledRect { rect, color, .. } => {
+                    Some((rect.bottom(), color.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+        let key_color = &renderer.config().keys[0].color;
+        let (_, rapid) = fills
+            .iter()
+            .max_by(|a, b| a.0.total_cmp(&b.0))
+            .expect("rapid bar fill");
+        let (_, slow) = fills
+            .iter()
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .expect("slow bar fill");
+        assert_eq!(rapid, key_color);
+        assert_eq!(
+            slow,
+            &super::with_scaled_brightness(key_color, super::MIN_PRESS_INTENSITY)
+        );
+    }
+
+    #[test]
+    fn test_build_draw_list_strokes_bars_with_bar_outline_color() {
+        let outline = Color::from_rgba_u8(10, 20, 30, 255);
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            fading: false,
+            bar_outline_color: outline.clone(),
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let strokes: Vec<&Color> = list
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                crate::draw_list::DrawShape::StrokedRect { color, .. } => Some(color),
+                _ => None,
+            })
+            .collect();
+        assert!(
+            strokes.contains(&&outline),
+            "bar stroke uses barOutlineColor"
+        );
+        assert!(
+            !strokes.contains(&&super::white()),
+            "no white stroke remains"
+        );
+    }
+
+    #[test]
+    fn test_bar_cap_rects_split_the_top_of_the_bar_into_fading_slices() {
+        let bar = egui::Rect::from_min_max(egui::pos2(10.0, 20.0), egui::pos2(40.0, 120.0));
+
+        let cap = super::bar_cap_rects(bar, 8.0);
+
+        assert_eq!(cap.len(), 4);
+        assert_f32_eq(cap[0].0.top(), 20.0);
+        assert_f32_eq(cap[3].0.bottom(), 28.0);
+        for (slice, _) in &cap {
+            assert_f32_eq(slice.height(), 2.0);
+            assert_f32_eq(slice.left(), 10.0);
+            assert_f32_eq(slice.right(), 40.0);
+        }
+        for pair in cap.windows(2) {
+            assert_f32_eq(pair[0].0.bottom(), pair[1].0.top());
+            assert!(pair[0].1 < pair[1].1, "alpha rises toward the bar body");
+        }
+        assert!(cap.iter().all(|(_, alpha)| *alpha > 0.0 && *alpha < 1.0));
+    }
+
+    #[test]
+    fn test_bar_cap_rects_clamp_to_bar_height_and_slice_count() {
+        let short = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(10.0, 3.0));
+        let tall = egui::Rect::from_min_max(egui::pos2(0.0, 0.0), egui::pos2(10.0, 500.0));
+
+        let short_cap = super::bar_cap_rects(short, 20.0);
+        assert_f32_eq(short_cap.last().expect("cap slice").0.bottom(), 3.0);
+        assert_eq!(short_cap.len(), 2);
+
+        let tall_cap = super::bar_cap_rects(tall, 200.0);
+        assert_eq!(tall_cap.len(), super::BAR_CAP_MAX_SLICES);
+        assert_f32_eq(tall_cap.last().expect("cap slice").0.bottom(), 200.0);
+
+        assert!(super::bar_cap_rects(tall, 0.0).is_empty());
+        assert!(super::bar_cap_rects(tall, -5.0).is_empty());
+    }
+
+    #[test]
+    fn test_build_draw_list_fades_the_bar_cap() {
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            fading: false,
+            bar_cap_fade_px: 8.0,
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.bar_manager.update(0.1);
+        renderer.on_key_release("Z");
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let fills: Vec<(egui::Rect, f32)> = list
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                crate::draw_list::DrawShape::FilledRect { rect, color, .. } => {
+                    Some((*rect, color.a))
+                }
+                _ => None,
+            })
+            .collect();
+        let body = fills
+            .iter()
+            .find(|(_, alpha)| *alpha == 1.0)
+            .expect("opaque bar body");
+        let cap: Vec<_> = fills.iter().filter(|(_, alpha)| *alpha < 1.0).collect();
+        assert_eq!(cap.len(), 4);
+        assert_f32_eq(cap.last().expect("cap slice").0.bottom(), body.0.top());
+    }
+
+    #[test]
+    fn test_build_draw_list_ui_scale_scales_the_whole_frame() {
+        let mut plain = Renderer::new(AppConfig::default());
+        let mut scaled = Renderer::new(AppConfig {
+            ui_scale: 2.0,
+            ..AppConfig::default()
+        });
+        for renderer in [&mut plain, &mut scaled] {
+            renderer.on_key_press("Z");
+            renderer.bar_manager.update(0.1);
+        }
+        let [width, height] = plain.desired_window_size();
+        assert_eq!(scaled.desired_window_size(), [width * 2.0, height * 2.0]);
+
+        let mut expected = plain.build_draw_list(draw_list_canvas(&plain));
+        expected.scale(2.0, egui::Pos2::ZERO);
+
+        assert_eq!(scaled.build_draw_list(draw_list_canvas(&scaled)), expected);
+    }
+
+    #[test]
+    fn test_build_draw_list_keeps_bar_gap_between_close_presses() {
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            fading: false,
+            bar_gap_px: 5.0,
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.bar_manager.update(0.02);
+        renderer.on_key_release("Z");
+        renderer.bar_manager.update(0.005);
+        renderer.on_key_press("Z");
+        renderer.bar_manager.update(0.02);
+        let bars = &renderer.bar_manager.columns["Z"].bars;
+        assert!(
+            bars[0].y_position - bars[1].height < 5.0,
+            "bars start closer than the gap"
+        );
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let mut fills: Vec<egui::Rect> = list
+            .shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                crate::draw_list::DrawShape::FilledRect { rect, .. } => Some(*rect),
+                _ => None,
+            })
+            .collect();
+        fills.sort_by(|a, b| a.top().total_cmp(&b.top()));
+        assert_eq!(fills.len(), 2);
+        assert_f32_eq(fills[1].top() - fills[0].bottom(), 5.0);
+        assert_f32_eq(fills[1].bottom(), renderer.key_bottom(canvas));
+    }
+
+    #[test]
+    fn test_build_draw_list_draws_short_bars_at_min_bar_height() {
+        let mut renderer = Renderer::new(AppConfig {
+            transparent_background: true,
+            min_bar_height: 6.0,
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        assert_f32_eq(renderer.bar_manager.columns["Z"].bars[0].height, 1.0);
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        let key_bottom = renderer.key_bottom(canvas);
+        let bar_fill = list
+            .shapes
+            .iter()
+            .find_map(|shape| match shape {
+                crate::draw_list::DrawShape::FilledRect { rect, .. } => Some(*rect),
+                _ => None,
+            })
+            .expect("bar fill");
+        assert_f32_eq(bar_fill.bottom(), key_bottom);
+        assert_f32_eq(bar_fill.height(), 6.0);
+        assert_f32_eq(renderer.bar_manager.columns["Z"].bars[0].height, 1.0);
+    }
+
+    #[test]
+    fn test_render_png_bytes_paints_background_and_key_box() {
+        let renderer = Renderer::new(AppConfig {
+            background_color: Color::from_rgba_u8(0, 0, 64, 255),
+            ..AppConfig::default()
+        });
+        let [width, height] = renderer.desired_window_size();
+
+        let bytes = renderer
+            .render_png_bytes(width as u32, height as u32)
+            .expect("snapshot should render");
+        let image = tiny_skia::Pixmap::decode_png(&bytes).expect("snapshot should decode");
+        assert_eq!((image.width(), image.height()), (235, 700));
+
+        let rgba = |x: u32, y: u32| {
+            let pixel = image.pixel(x, y).expect("pixel in bounds").demultiply();
+            [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+        };
+        // Key Z's red outline is stroked along x = 30 (margin plus outline thickness),
+        // sampled halfway up the 70px key box.
+        let key_bottom = renderer.key_bottom(draw_list_canvas(&renderer)) as u32;
+        assert_eq!(rgba(30, key_bottom - 35), [255, 0, 0, 255]);
+        assert_eq!(rgba(5, 5), [0, 0, 64, 255]);
+    }
+
+    #[test]
+    fn test_render_to_png_writes_file() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let path = dir.path().join("frame.png");
+
+        Renderer::new(AppConfig::default())
+            .render_to_png(&path, 64, 32)
+            .expect("snapshot should be written");
+
+        let image = tiny_skia::Pixmap::load_png(&path).expect("snapshot should load");
+        assert_eq!((image.width(), image.height()), (64, 32));
+    }
+
+    fn line_segments(list: &crate::draw_list::DrawList) -> Vec<(Pos2, Pos2)> {
+        list.shapes
+            .iter()
+            .filter_map(|shape| match shape {
+                crate::draw_list::DrawShape::Line { from, to, .. } => Some((*from, *to)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_build_draw_list_anchor_line_spans_width_at_key_bottom() {
+        let renderer = Renderer::new(AppConfig {
+            show_anchor_line: true,
+            ..AppConfig::default()
+        });
+        let canvas = draw_list_canvas(&renderer);
+
+        let lines = line_segments(&renderer.build_draw_list(canvas));
+
+        let key_bottom = renderer.key_bottom(canvas);
+        assert_eq!(
+            lines,
+            vec![(
+                Pos2::new(canvas.left(), key_bottom),
+                Pos2::new(canvas.right(), key_bottom)
+            )]
+        );
+
+        let hidden = Renderer::new(AppConfig::default());
+        assert!(line_segments(&hidden.build_draw_list(canvas)).is_empty());
+    }
+
+    #[test]
+    fn test_build_draw_list_vertical_anchor_line_spans_height() {
+        let renderer = Renderer::new(AppConfig {
+            show_anchor_line: true,
+            key_axis: crate::types::KeyAxis::Vertical,
+            ..AppConfig::default()
+        });
+        let canvas = draw_list_canvas(&renderer);
+
+        let lines = line_segments(&renderer.build_draw_list(canvas));
+
+        assert_eq!(lines.len(), 1);
+        let (from, to) = lines[0];
+        assert_f32_eq(from.x, to.x);
+        assert_f32_eq(from.y.min(to.y), canvas.top());
+        assert_f32_eq(from.y.max(to.y), canvas.bottom());
+    }
+
+    #[test]
+    fn test_build_draw_list_includes_help_panel_until_faded_out() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        renderer.show_help(vec!["line one".to_string(), "line two".to_string()]);
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+        assert!(list.texts().any(|text| text == "line one"));
+        assert!(list.texts().any(|text| text == "line two"));
+
+        renderer.set_help_alpha(0.0);
+        assert!(!renderer.is_help_visible());
+        let list = renderer.build_draw_list(canvas);
+        assert!(!list.texts().any(|text| text == "line one"));
+    }
+
+    #[test]
+    fn test_build_draw_list_draws_group_label_over_member_span() {
+        let renderer = Renderer::new(AppConfig {
+            groups: vec![crate::types::KeyGroup {
+                label: "taps".to_string(),
+                color: Color::from_rgba_u8(0, 255, 0, 255),
+                keys: vec!["Z".to_string(), "X".to_string()],
+            }],
+            ..AppConfig::default()
+        });
+        let canvas = draw_list_canvas(&renderer);
+
+        let list = renderer.build_draw_list(canvas);
+
+        assert!(list.texts().any(|text| text == "taps"));
+        let key_top = renderer.key_bottom(canvas) - renderer.config.key_size;
+        let (from, to) = line_segments(&list)[0];
+        assert_f32_eq(from.x, 30.0);
+        assert_f32_eq(to.x, 205.0);
+        assert_f32_eq(from.y, key_top - super::GROUP_LINE_GAP);
+    }
+
+    #[test]
+    fn test_build_draw_list_without_keys_only_paints_background() {
+        let renderer = Renderer::new(AppConfig {
+            keys: Vec::new(),
+            ..AppConfig::default()
+        });
+
+        let list = renderer.build_draw_list(draw_list_canvas(&renderer));
+
+        assert_eq!(list.shapes.len(), 1);
+        assert!(matches!(
+            list.shapes[0],
+            crate::draw_list::DrawShape::FilledRect { .. }
+        ));
+    }
+
+    #[test]
+    fn test_released_bar_color_shifts_toward_trail_color_with_height() {
+        let bar = crate::bars::Bar {
+            y_position: 350.0,
+            height: 20.0,
+            color: Color::new(1.0, 0.0, 0.0, 1.0),
+            pressed_color: Color::new(1.0, 0.0, 0.0, 0.5),
+            age: 0.0,
+            velocity: None,
+            released_at: None,
+            press_interval: None,
+            ghost_since: None,
+        };
+        let trail = Color::new(0.0, 0.0, 1.0, 1.0);
+
+        let shifted = super::released_bar_color(&bar, Some(&trail), 700.0);
+        assert_f32_eq(shifted.r, 0.5);
+        assert_f32_eq(shifted.b, 0.5);
+        assert_eq!(super::released_bar_color(&bar, None, 700.0), bar.color);
+    }
+
+    #[test]
+    fn test_release_decay_color_blends_within_window_only() {
+        let pressed = Color::new(1.0, 1.0, 1.0, 1.0);
+        let released = Color::new(0.0, 0.0, 1.0, 1.0);
+        let decay = Duration::from_millis(200);
+
+        let start = super::release_decay_color(&pressed, &released, Some(0.0), decay);
+        assert_eq!(start, pressed);
+
+        let halfway = super::release_decay_color(&pressed, &released, Some(0.1), decay);
+        assert_f32_eq(halfway.r, 0.5);
+        assert_f32_eq(halfway.b, 1.0);
+
+        for since_release in [Some(0.2), Some(5.0), None] {
+            assert_eq!(
+                super::release_decay_color(&pressed, &released, since_release, decay),
+                released
+            );
+        }
+    }
+
+    #[test]
+    fn test_release_decay_color_zero_window_is_instant() {
+        let pressed = Color::new(1.0, 1.0, 1.0, 1.0);
+        let released = Color::new(0.0, 0.0, 1.0, 1.0);
+
+        assert_eq!(
+            super::release_decay_color(&pressed, &released, Some(0.0), Duration::ZERO),
+            released
+        );
+    }
+
+    #[test]
+    fn test_effective_corner_radius_clamps_to_half_smaller_side() {
+        let rect = Rect::from_min_size(Pos2::new(0.0, 0.0), egui::vec2(70.0, 12.0));
+
+        assert_f32_eq(super::effective_corner_radius(4.0, rect), 4.0);
+        assert_f32_eq(super::effective_corner_radius(20.0, rect), 6.0);
+        assert_f32_eq(super::effective_corner_radius(-3.0, rect), 0.0);
+
+        let flat = Rect::from_min_size(Pos2::new(0.0, 0.0), egui::vec2(70.0, 0.0));
+        assert_f32_eq(super::effective_corner_radius(8.0, flat), 0.0);
+    }
+
+    #[test]
+    fn test_bar_horizontal_extent_centers_narrower_bar_in_key_box() {
+        let (bar_left, bar_right) = super::bar_horizontal_extent(30.0, 100.0, 0.5);
+
+        assert_f32_eq(bar_right - bar_left, 35.0);
+        assert_f32_eq((bar_left + bar_right) * 0.5, 65.0);
+
+        let (full_left, full_right) = super::bar_horizontal_extent(30.0, 100.0, 1.0);
+        assert_f32_eq(full_left, 30.0);
+        assert_f32_eq(full_right, 100.0);
+    }
+
+    #[test]
+    fn test_format_counter_substitutes_placeholder() {
+        assert_eq!(super::format_counter(Some("{} hits"), 42), "42 hits");
+        assert_eq!(super::format_counter(Some("[{}]"), 0), "[0]");
+        assert_eq!(super::format_counter(None, 1234), "1234");
+    }
+
+    #[test]
+    fn test_goal_progress_is_fraction_of_goal_clamped_to_one() {
+        assert_f32_eq(super::goal_progress(0, 10_000), 0.0);
+        assert_f32_eq(super::goal_progress(2_500, 10_000), 0.25);
+        assert_f32_eq(super::goal_progress(10_000, 10_000), 1.0);
+        assert_f32_eq(super::goal_progress(12_000, 10_000), 1.0);
+        assert_f32_eq(super::goal_progress(5, 0), 1.0);
+    }
+
+    #[test]
+    fn test_event_log_is_bounded_and_newest_first() {
+        let mut log = EventLog::default();
+
+        for entry in ["Z↓", "X↓", "Z↑", "X↑"] {
+            log.push(entry.to_string(), 3);
+        }
+
+        assert_eq!(log.iter().collect::<Vec<_>>(), vec!["X↑", "Z↑", "X↓"]);
+    }
+
+    #[test]
+    fn test_event_log_records_only_when_enabled() {
+        let mut renderer = Renderer::new(AppConfig::default());
+        renderer.on_key_press("Z");
+        assert_eq!(renderer.event_log().count(), 0);
+
+        renderer.set_config(AppConfig {
+            show_event_log: true,
+            event_log_size: 2,
+            ..AppConfig::default()
+        });
+        renderer.on_key_press("Z");
+        renderer.on_key_press("Q");
+        renderer.on_key_release("Z");
+
+        assert_eq!(renderer.event_log().collect::<Vec<_>>(), vec!["Z↑", "Q↓"]);
+    }
+
+    #[test]
+    fn test_scancode_event_feeds_the_scancode_column() {
+        let config = crate::config::load_from_str("[[key]]\nname = \"sc:0x1a\"\n")
+            .expect("scancode key should load");
+        let mut renderer = Renderer::new(config);
+        let event_name = KeyId::Scancode(0x1A).to_string();
+
+        renderer.on_key_press(&event_name);
+
+        assert_eq!(renderer.bar_manager.columns["sc:0x1A"].press_count, 1);
+        assert_eq!(renderer.held_keys(), vec!["sc:0x1A"]);
+    }
+
+    #[test]
+    fn test_held_keys_tracks_configured_presses_and_releases() {
+        let mut renderer = Renderer::new(AppConfig::default());
+
+        renderer.on_key_press("Z");
+        renderer.on_key_press("X");
+        renderer.on_key_press("Q");
+        assert_eq!(renderer.held_keys(), vec!["X", "Z"]);
+
+        renderer.on_key_release("Z");
+        assert_eq!(renderer.held_keys(), vec!["X"]);
+    }
+
+    #[test]
+    fn test_press_observer_receives_key_config_and_event_kind() {
+        let config = AppConfig::default();
+        let z_color = config.keys[0].color.clone();
+        let mut renderer = Renderer::new(config);
+        let observer = Arc::new(RecordingObserver::default());
+        renderer.set_press_observer(observer.clone());
+
+        renderer.on_key_press("Z");
+        renderer.on_key_release("Z");
+        renderer.on_key_press("Q");
+        renderer.on_key_release("Q");
+
+        let events = observer
+            .events
+            .lock()
+            .expect("observer lock should not be poisoned");
+        assert_eq!(
+            *events,
+            vec![
+                ("Z".to_string(), z_color.clone(), EventKind::Press),
+                ("Z".to_string(), z_color, EventKind::Release),
+            ]
+        );
     }
 }
@@ -0,0 +1,385 @@
+//! Software rasterizer for [`DrawList`]s, used to export PNG snapshots
+//! without opening a window.
+//!
+//! Shapes are drawn with `tiny-skia`; text is rasterized from the primary
+//! font with `ab_glyph`. Output is close to, but not pixel-identical with,
+//! what egui paints.
+
+use std::path::Path;
+
+use ab_glyph::{Font, FontRef, PxScale, ScaleFont, point};
+use egui::{Pos2, Rect};
+use tiny_skia::{
+    FillRule, FilterQuality, Paint, PathBuilder, Pixmap, PixmapPaint, PremultipliedColorU8, Stroke,
+    Transform,
+};
+
+use crate::draw_list::{DrawList, DrawShape};
+use crate::types::{AppError, Color};
+
+/// Cubic Bézier control distance for approximating a quarter circle.
+const ARC_KAPPA: f32 = 0.552_284_8;
+
+/// Rasterizes `list` into a `width` x `height` pixmap, starting from transparent.
+///
+/// `font_data` is the TTF/OTF used for every text shape.
+pub fn rasterize(
+    list: &DrawList,
+    width: u32,
+    height: u32,
+    font_data: &[u8],
+) -> Result<Pixmap, AppError> {
+    let mut pixmap = Pixmap::new(width, height)
+        .ok_or_else(|| AppError::Render(format!("invalid snapshot size {width}x{height}")))?;
+    let font = FontRef::try_from_slice(font_data)
+        .map_err(|err| AppError::Render(format!("failed to parse font: {err}")))?;
+
+    for shape in &list.shapes {
+        draw_shape(&mut pixmap, &font, shape, list.opacity);
+    }
+
+    Ok(pixmap)
+}
+
+/// Encodes `pixmap` as PNG bytes.
+pub fn encode_png(pixmap: &Pixmap) -> Result<Vec<u8>, AppError> {
+    pixmap
+        .encode_png()
+        .map_err(|err| AppError::Render(format!("failed to encode PNG: {err}")))
+}
+
+fn draw_shape(pixmap: &mut Pixmap, font: &FontRef<'_>, shape: &DrawShape, opacity: f32) {
+    match shape {
+        DrawShape::FilledRect {
+            rect,
+            corner_radius,
+            color,
+        } => fill_rect(pixmap, *rect, *corner_radius, color, opacity),
+        DrawShape::StrokedRect {
+            rect,
+            corner_radius,
+            thickness,
+            color,
+        } => {
+            if let Some(path) = rect_path(*rect, *corner_radius) {
+                stroke(pixmap, &path, *thickness, color, opacity);
+            }
+        }
+        DrawShape::Circle {
+            center,
+            radius,
+            color,
+        } => {
+            if let Some(path) = PathBuilder::from_circle(center.x, center.y, *radius) {
+                pixmap.fill_path(
+                    &path,
+                    &paint(color, opacity),
+                    FillRule::Winding,
+                    Transform::identity(),
+                    None,
+                );
+            }
+        }
+        DrawShape::Line {
+            from,
+            to,
+            thickness,
+            color,
+        } => {
+            let mut builder = PathBuilder::new();
+            builder.move_to(from.x, from.y);
+            builder.line_to(to.x, to.y);
+            if let Some(path) = builder.finish() {
+                stroke(pixmap, &path, *thickness, color, opacity);
+            }
+        }
+        DrawShape::Text {
+            pos,
+            align,
+            text,
+            font_size,
+            color,
+            backdrop,
+        } => {
+            let scaled = font.as_scaled(PxScale::from(*font_size));
+            let text_size = egui::vec2(
+                text.chars()
+                    .map(|ch| scaled.h_advance(scaled.glyph_id(ch)))
+                    .sum(),
+                scaled.ascent() - scaled.descent(),
+            );
+
+            let text_rect = match backdrop {
+                Some(backdrop) => {
+                    let padding = egui::vec2(backdrop.padding, backdrop.padding);
+                    let outer = align.anchor_size(*pos, text_size + padding * 2.0);
+                    fill_rect(
+                        pixmap,
+                        outer,
+                        backdrop.corner_radius,
+                        &backdrop.color,
+                        opacity,
+                    );
+                    Rect::from_min_size(outer.min + padding, text_size)
+                }
+                None => align.anchor_size(*pos, text_size),
+            };
+
+            draw_text(
+                pixmap,
+                font,
+                text,
+                *font_size,
+                text_rect.min,
+                color,
+                opacity,
+            );
+        }
+        DrawShape::Image { rect, path } => draw_image(pixmap, *rect, path, opacity),
+    }
+}
+
+/// Draws the PNG at `path` scaled into `rect`; unreadable files are skipped.
+fn draw_image(pixmap: &mut Pixmap, rect: Rect, path: &Path, opacity: f32) {
+    let Ok(image) = Pixmap::load_png(path) else {
+        return;
+    };
+    let transform = Transform::from_row(
+        rect.width() / image.width() as f32,
+        0.0,
+        0.0,
+        rect.height() / image.height() as f32,
+        rect.min.x,
+        rect.min.y,
+    );
+    let paint = PixmapPaint {
+        opacity,
+        quality: FilterQuality::Bilinear,
+        ..PixmapPaint::default()
+    };
+    pixmap.draw_pixmap(0, 0, image.as_ref(), &paint, transform, None);
+}
+
+fn fill_rect(pixmap: &mut Pixmap, rect: Rect, corner_radius: f32, color: &Color, opacity: f32) {
+    if let Some(path) = rect_path(rect, corner_radius) {
+        pixmap.fill_path(
+            &path,
+            &paint(color, opacity),
+            FillRule::Winding,
+            Transform::identity(),
+            None,
+        );
+    }
+}
+
+fn stroke(pixmap: &mut Pixmap, path: &tiny_skia::Path, width: f32, color: &Color, opacity: f32) {
+    if width <= 0.0 {
+        return;
+    }
+    let stroke = Stroke {
+        width,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(
+        path,
+        &paint(color, opacity),
+        &stroke,
+        Transform::identity(),
+        None,
+    );
+}
+
+fn paint(color: &Color, opacity: f32) -> Paint<'static> {
+    let [r, g, b, a] = color_bytes(color, opacity);
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(r, g, b, a);
+    paint.anti_alias = true;
+    paint
+}
+
+/// Unmultiplied RGBA bytes of `color` with its alpha scaled by `opacity`.
+fn color_bytes(color: &Color, opacity: f32) -> [u8; 4] {
+    let byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [
+        byte(color.r),
+        byte(color.g),
+        byte(color.b),
+        byte(color.a * opacity),
+    ]
+}
+
+/// Path of `rect` with corners rounded by `radius`, or `None` for empty rects.
+fn rect_path(rect: Rect, radius: f32) -> Option<tiny_skia::Path> {
+    let radius = radius.clamp(0.0, rect.width().min(rect.height()) * 0.5);
+    if radius <= 0.0 {
+        let rect =
+            tiny_skia::Rect::from_ltrb(rect.left(), rect.top(), rect.right(), rect.bottom())?;
+        return Some(PathBuilder::from_rect(rect));
+    }
+
+    let (left, top, right, bottom) = (rect.left(), rect.top(), rect.right(), rect.bottom());
+    let control = radius * (1.0 - ARC_KAPPA);
+    let mut builder = PathBuilder::new();
+    builder.move_to(left + radius, top);
+    builder.line_to(right - radius, top);
+    builder.cubic_to(
+        right - control,
+        top,
+        right,
+        top + control,
+        right,
+        top + radius,
+    );
+    builder.line_to(right, bottom - radius);
+    builder.cubic_to(
+        right,
+        bottom - control,
+        right - control,
+        bottom,
+        right - radius,
+        bottom,
+    );
+    builder.line_to(left + radius, bottom);
+    builder.cubic_to(
+        left + control,
+        bottom,
+        left,
+        bottom - control,
+        left,
+        bottom - radius,
+    );
+    builder.line_to(left, top + radius);
+    builder.cubic_to(left, top + control, left + control, top, left + radius, top);
+    builder.close();
+    builder.finish()
+}
+
+/// Draws a single line of `text` with its top-left corner at `origin`.
+fn draw_text(
+    pixmap: &mut Pixmap,
+    font: &FontRef<'_>,
+    text: &str,
+    font_size: f32,
+    origin: Pos2,
+    color: &Color,
+    opacity: f32,
+) {
+    let scaled = font.as_scaled(PxScale::from(font_size));
+    let baseline = origin.y + scaled.ascent();
+    let mut caret = origin.x;
+
+    for ch in text.chars() {
+        let glyph_id = scaled.glyph_id(ch);
+        let glyph = glyph_id.with_scale_and_position(font_size, point(caret, baseline));
+        caret += scaled.h_advance(glyph_id);
+
+        let Some(outlined) = font.outline_glyph(glyph) else {
+            continue;
+        };
+        let bounds = outlined.px_bounds();
+        outlined.draw(|x, y, coverage| {
+            let px = bounds.min.x as i32 + x as i32;
+            let py = bounds.min.y as i32 + y as i32;
+            blend_pixel(pixmap, px, py, color, coverage * opacity);
+        });
+    }
+}
+
+/// Source-over blends `color` at `coverage` into the premultiplied pixel at (x, y).
+fn blend_pixel(pixmap: &mut Pixmap, x: i32, y: i32, color: &Color, coverage: f32) {
+    let (Ok(x), Ok(y)) = (u32::try_from(x), u32::try_from(y)) else {
+        return;
+    };
+    if x >= pixmap.width() || y >= pixmap.height() {
+        return;
+    }
+
+    let index = (y * pixmap.width() + x) as usize;
+    let dst = pixmap.pixels()[index];
+    let alpha = (color.a * coverage).clamp(0.0, 1.0);
+    let mix = |src: f32, dst: u8| {
+        (src.clamp(0.0, 1.0) * alpha * 255.0 + f32::from(dst) * (1.0 - alpha)).round() as u8
+    };
+    let a = mix(1.0, dst.alpha());
+    let blended = PremultipliedColorU8::from_rgba(
+        mix(color.r, dst.red()).min(a),
+        mix(color.g, dst.green()).min(a),
+        mix(color.b, dst.blue()).min(a),
+        a,
+    );
+
+    if let Some(blended) = blended {
+        pixmap.pixels_mut()[index] = blended;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use egui::{Align2, Pos2, Rect};
+
+    use super::{encode_png, rasterize};
+    use crate::draw_list::{DrawList, DrawShape};
+    use crate::font::load_font;
+    use crate::types::Color;
+
+    fn rgba_at(pixmap: &tiny_skia::Pixmap, x: u32, y: u32) -> [u8; 4] {
+        let pixel = pixmap.pixel(x, y).expect("pixel in bounds").demultiply();
+        [pixel.red(), pixel.green(), pixel.blue(), pixel.alpha()]
+    }
+
+    #[test]
+    fn test_rasterize_fills_rects_in_paint_order_with_opacity() {
+        let mut list = DrawList::new(1.0);
+        list.push(DrawShape::FilledRect {
+            rect: Rect::from_min_max(Pos2::new(0.0, 0.0), Pos2::new(20.0, 20.0)),
+            corner_radius: 0.0,
+            color: Color::from_rgba_u8(0, 0, 255, 255),
+        });
+        list.push(DrawShape::FilledRect {
+            rect: Rect::from_min_max(Pos2::new(10.0, 0.0), Pos2::new(20.0, 20.0)),
+            corner_radius: 0.0,
+            color: Color::from_rgba_u8(255, 0, 0, 255),
+        });
+
+        let pixmap = rasterize(&list, 20, 20, load_font()).expect("rasterize");
+
+        assert_eq!(rgba_at(&pixmap, 5, 5), [0, 0, 255, 255]);
+        assert_eq!(rgba_at(&pixmap, 15, 5), [255, 0, 0, 255]);
+
+        list.opacity = 0.0;
+        let hidden = rasterize(&list, 20, 20, load_font()).expect("rasterize");
+        assert_eq!(rgba_at(&hidden, 5, 5)[3], 0);
+    }
+
+    #[test]
+    fn test_rasterize_draws_text_glyphs() {
+        let mut list = DrawList::new(1.0);
+        list.push(DrawShape::Text {
+            pos: Pos2::new(20.0, 20.0),
+            align: Align2::CENTER_CENTER,
+            text: "W".to_string(),
+            font_size: 30.0,
+            color: Color::from_rgba_u8(255, 255, 255, 255),
+            backdrop: None,
+        });
+
+        let pixmap = rasterize(&list, 40, 40, load_font()).expect("rasterize");
+
+        assert!(pixmap.pixels().iter().any(|pixel| pixel.alpha() > 0));
+    }
+
+    #[test]
+    fn test_rasterize_rejects_zero_size() {
+        assert!(rasterize(&DrawList::new(1.0), 0, 10, load_font()).is_err());
+    }
+
+    #[test]
+    fn test_encode_png_round_trips_dimensions() {
+        let pixmap = rasterize(&DrawList::new(1.0), 12, 7, load_font()).expect("rasterize");
+
+        let bytes = encode_png(&pixmap).expect("encode");
+        let decoded = tiny_skia::Pixmap::decode_png(&bytes).expect("decode");
+
+        assert_eq!((decoded.width(), decoded.height()), (12, 7));
+    }
+}
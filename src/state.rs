@@ -0,0 +1,192 @@
+//! Persistent overlay state stored in a sidecar file next to the config,
+//! and the session stats optionally exported on shutdown.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::AppError;
+
+const STATE_EXTENSION: &str = "state.toml";
+
+/// Values that survive restarts but are not user-editable configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct OverlayState {
+    /// Highest keys-per-second value ever observed.
+    pub peak_kps: f32,
+}
+
+impl OverlayState {
+    /// Records a live KPS sample, returning `true` when it sets a new peak.
+    pub fn update_peak(&mut self, kps: f32) -> bool {
+        if kps.is_finite() && kps > self.peak_kps {
+            self.peak_kps = kps;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Press counts for one overlay session, written to `statsExportPath` as JSON.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStats {
+    /// Time from startup to shutdown.
+    pub duration_secs: f64,
+    /// Sum of `presses` over all keys.
+    pub total_presses: u64,
+    /// One entry per configured key, in overlay order.
+    pub keys: Vec<KeyPressCount>,
+}
+
+/// Presses recorded for a single key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyPressCount {
+    /// Canonical key name, as in `[[key]] name`.
+    pub name: String,
+    /// Presses counted during the session, including any `initialCount`.
+    pub presses: u64,
+}
+
+impl SessionStats {
+    /// Builds stats from `(key name, presses)` pairs, totalling the counts.
+    pub fn new(duration_secs: f64, keys: impl IntoIterator<Item = (String, u64)>) -> Self {
+        let keys: Vec<KeyPressCount> = keys
+            .into_iter()
+            .map(|(name, presses)| KeyPressCount { name, presses })
+            .collect();
+        Self {
+            duration_secs,
+            total_presses: keys.iter().map(|key| key.presses).sum(),
+            keys,
+        }
+    }
+}
+
+/// Returns the sidecar state path for a config file, e.g. `config.state.toml`.
+pub fn state_path_for(config_path: &Path) -> PathBuf {
+    config_path.with_extension(STATE_EXTENSION)
+}
+
+/// Loads state from disk; a missing file yields the default state.
+pub fn load_state(path: &Path) -> Result<OverlayState, AppError> {
+    if !path.exists() {
+        return Ok(OverlayState::default());
+    }
+
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents)
+        .map_err(|err| AppError::Config(format!("failed to parse state file: {err}")))
+}
+
+/// Writes state to disk, replacing any previous contents.
+pub fn save_state(path: &Path, state: &OverlayState) -> Result<(), AppError> {
+    let contents = toml::to_string_pretty(state)
+        .map_err(|err| AppError::Config(format!("failed to serialize state: {err}")))?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+/// Writes session stats to `path` as pretty-printed JSON.
+pub fn save_session_stats(path: &Path, stats: &SessionStats) -> Result<(), AppError> {
+    let contents = serde_json::to_string_pretty(stats)
+        .map_err(|err| AppError::Config(format!("failed to serialize session stats: {err}")))?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{
+        OverlayState, SessionStats, load_state, save_session_stats, save_state, state_path_for,
+    };
+
+    #[test]
+    fn test_session_stats_serializes_counts_and_total_as_camel_case_json() {
+        let stats = SessionStats::new(12.5, [("Z".to_string(), 3), ("X".to_string(), 4)]);
+
+        let json = serde_json::to_value(&stats).expect("stats should serialize");
+
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "durationSecs": 12.5,
+                "totalPresses": 7,
+                "keys": [
+                    { "name": "Z", "presses": 3 },
+                    { "name": "X", "presses": 4 },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_save_session_stats_round_trips() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("stats.json");
+        let stats = SessionStats::new(3.0, [("Z".to_string(), 1)]);
+
+        save_session_stats(&path, &stats).expect("save stats");
+
+        let contents = std::fs::read_to_string(&path).expect("read stats");
+        let loaded: SessionStats = serde_json::from_str(&contents).expect("parse stats");
+        assert_eq!(loaded, stats);
+    }
+
+    #[test]
+    fn test_update_peak_only_raises() {
+        let mut state = OverlayState::default();
+
+        assert!(state.update_peak(8.0));
+        assert!(!state.update_peak(5.0));
+        assert!(!state.update_peak(8.0));
+        assert!(state.update_peak(12.5));
+        assert!(!state.update_peak(f32::NAN));
+
+        assert_eq!(state.peak_kps, 12.5);
+    }
+
+    #[test]
+    fn test_load_state_missing_peak_field_defaults_to_zero() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.state.toml");
+        std::fs::write(&path, "# written by an older version\n").expect("write state");
+
+        let state = load_state(&path).expect("old state file should load");
+
+        assert_eq!(state, OverlayState::default());
+    }
+
+    #[test]
+    fn test_load_state_missing_file_returns_default() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+
+        let state = load_state(&dir.path().join("absent.state.toml")).expect("load state");
+
+        assert_eq!(state.peak_kps, 0.0);
+    }
+
+    #[test]
+    fn test_save_then_load_state_round_trips_peak() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = dir.path().join("config.state.toml");
+        let state = OverlayState { peak_kps: 17.25 };
+
+        save_state(&path, &state).expect("save state");
+
+        assert_eq!(load_state(&path).expect("load state"), state);
+    }
+
+    #[test]
+    fn test_state_path_for_replaces_config_extension() {
+        assert_eq!(
+            state_path_for(Path::new("dir/config.toml")),
+            Path::new("dir/config.state.toml")
+        );
+    }
+}
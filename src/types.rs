@@ -1,11 +1,26 @@
 //! Core domain types for key overlay visualization.
 
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::input::BackendKind;
+
 const GOLDEN_RATIO: f32 = 1.618;
 
+/// Key that closes the overlay when double-tapped while it is focused.
+pub const CLOSE_KEY_NAME: &str = "Escape";
+
+/// Highest frame rate the overlay renders at; higher `fps` values are clamped.
+pub const MAX_FPS: u32 = 240;
+
 /// RGBA color with normalized f32 channels (0.0 - 1.0).
-#[derive(Debug, Clone, PartialEq)]
+///
+/// Serializes as the `"r,g,b,a"` byte string used in config files.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
 pub struct Color {
     pub r: f32,
     pub g: f32,
@@ -34,6 +49,18 @@ impl Color {
         }
     }
 
+    /// Formats this color as the `"r,g,b,a"` byte string used in config files.
+    pub fn to_config_string(&self) -> String {
+        let byte = |channel: f32| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+        format!(
+            "{},{},{},{}",
+            byte(self.r),
+            byte(self.g),
+            byte(self.b),
+            byte(self.a)
+        )
+    }
+
     /// Converts this color to egui's RGBA byte color.
     pub fn to_egui(&self) -> egui::Color32 {
         egui::Color32::from_rgba_unmultiplied(
@@ -55,6 +82,20 @@ impl Color {
     }
 }
 
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        crate::color::parse_color_normalized(&value).map_err(|err| err.to_string())
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.to_config_string()
+    }
+}
+
 /// Configuration for a single monitored key.
 #[derive(Debug, Clone, PartialEq)]
 pub struct KeyConfig {
@@ -62,6 +103,134 @@ pub struct KeyConfig {
     pub display_name: String,
     pub color: Color,
     pub size: f32,
+    /// Extra horizontal gap inserted before this key's column, in pixels.
+    pub spacer_before: f32,
+    /// Color of the held bar; `None` dims `color` by the golden ratio.
+    pub pressed_color: Option<Color>,
+    /// Per-key fade override; `None` follows `AppConfig::fading`.
+    pub fading: Option<bool>,
+    /// Color released bars shift toward as they rise; `None` keeps `color`.
+    pub trail_color: Option<Color>,
+    /// Scroll speed of this key's bars; `None` follows `AppConfig::bar_speed`.
+    pub bar_speed: Option<f32>,
+    /// PNG drawn in the key box instead of `display_name`.
+    pub icon_path: Option<PathBuf>,
+    /// Press count the key's counter starts from, e.g. to resume a session.
+    pub initial_count: Option<u64>,
+    /// Other key names that feed this key's column, e.g. `Up` for a `W` key.
+    pub aliases: Vec<String>,
+}
+
+impl KeyConfig {
+    /// Creates a key labelled with its own name, at size 1.0 and with no per-key overrides.
+    pub fn new(key_name: impl Into<String>, color: Color) -> Self {
+        let key_name = key_name.into();
+        Self {
+            display_name: key_name.clone(),
+            key_name,
+            color,
+            size: 1.0,
+            spacer_before: 0.0,
+            pressed_color: None,
+            fading: None,
+            trail_color: None,
+            bar_speed: None,
+            icon_path: None,
+            initial_count: None,
+            aliases: Vec::new(),
+        }
+    }
+}
+
+/// Keys highlighted together while all of them are held.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Combo {
+    /// Member `KeyConfig::key_name`s.
+    pub keys: Vec<String>,
+    /// Outline color for member key boxes while the combo is active.
+    pub color: Color,
+}
+
+/// Labelled cluster of keys, captioned above the span of its key boxes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyGroup {
+    pub label: String,
+    /// Color of the caption and its span line.
+    pub color: Color,
+    /// Member `KeyConfig::key_name`s; keys that are not configured are ignored.
+    pub keys: Vec<String>,
+}
+
+/// Named set of color overrides that can be swapped in at runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    pub background_color: Option<Color>,
+    /// Replacement colors keyed by `KeyConfig::key_name`.
+    pub key_colors: Vec<(String, Color)>,
+}
+
+impl Theme {
+    /// Returns a copy of `config` with this theme's colors applied.
+    pub fn apply(&self, config: &AppConfig) -> AppConfig {
+        let mut themed = config.clone();
+
+        if let Some(background_color) = &self.background_color {
+            themed.background_color = background_color.clone();
+        }
+
+        for key in &mut themed.keys {
+            if let Some((_, color)) = self
+                .key_colors
+                .iter()
+                .find(|(key_name, _)| *key_name == key.key_name)
+            {
+                key.color = color.clone();
+            }
+        }
+
+        themed
+    }
+}
+
+/// Fill style used when drawing bars.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum BarPattern {
+    #[default]
+    Solid,
+    Stripes,
+    Dots,
+}
+
+/// Vertical anchor of the key label within the key box.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyLabelAlign {
+    Top,
+    #[default]
+    Center,
+    Bottom,
+}
+
+/// Direction keys are laid out along; bars flow perpendicular to it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum KeyAxis {
+    /// Keys in a row along the bottom edge, bars rising upward.
+    #[default]
+    Horizontal,
+    /// Keys in a column along the left edge, bars flowing right.
+    Vertical,
+}
+
+/// Edge of the overlay where the session goal progress bar is drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum GoalBarPosition {
+    #[default]
+    Top,
+    Bottom,
 }
 
 /// Full application configuration.
@@ -73,11 +242,74 @@ pub struct AppConfig {
     pub background_color: Color,
     pub margin: f32,
     pub outline_thickness: f32,
+    pub bar_pattern: BarPattern,
+    pub max_bars_per_column: Option<usize>,
     pub fading: bool,
     pub counter: bool,
     pub fps: u32,
     pub log_to_file: bool,
+    pub input_backend: BackendKind,
+    pub show_peak_kps: bool,
+    pub window_x: Option<i32>,
+    pub window_y: Option<i32>,
+    pub show_kps: bool,
+    pub toggle_theme_key: Option<String>,
+    pub key_label_align: KeyLabelAlign,
+    pub min_bar_lifetime_ms: Option<u64>,
+    pub fallback_fonts: Vec<PathBuf>,
+    pub idle_hide_ms: u64,
+    pub strict_key_names: bool,
+    pub bar_acceleration: f32,
+    pub recenter_on_reload: bool,
+    pub show_event_log: bool,
+    pub event_log_size: usize,
+    pub session_goal: Option<u64>,
+    pub session_goal_position: GoalBarPosition,
+    pub bar_width_ratio: f32,
+    pub move_key_left_key: Option<String>,
+    pub move_key_right_key: Option<String>,
+    pub auto_save_tweaks: bool,
+    pub counter_format: Option<String>,
+    pub font_path: Option<PathBuf>,
+    pub corner_radius: f32,
+    pub monitor_index: Option<usize>,
+    pub transparent_background: bool,
+    pub reset_key: Option<String>,
+    pub reset_clears_bars: bool,
+    pub dt_smoothing: f32,
+    pub key_label_scale: f32,
+    pub counter_scale: f32,
+    pub key_axis: KeyAxis,
+    pub replay_file: Option<PathBuf>,
+    pub idle_fps: Option<u32>,
+    pub toggle_passthrough_key: Option<String>,
+    pub release_decay_ms: u64,
+    pub show_anchor_line: bool,
+    pub anchor_line_color: Color,
+    pub show_help_on_startup: bool,
+    pub help_duration_ms: u64,
+    pub merge_bars: bool,
+    pub min_bar_height: f32,
+    pub max_window_width: Option<f32>,
+    pub max_window_height: Option<f32>,
+    pub color_transition_ms: u64,
+    pub toggle_counter_key: Option<String>,
+    pub stats_export_path: Option<PathBuf>,
+    pub edge_padding: Option<f32>,
+    pub key_spacing: Option<f32>,
+    pub pause_when_unfocused: bool,
+    pub bar_outline_color: Color,
+    pub velocity_coloring: bool,
+    pub reverse_key_order: bool,
+    pub bar_cap_fade_px: f32,
+    pub ui_scale: f32,
+    pub bar_gap_px: f32,
+    pub ghost_trail_ms: u64,
+    pub show_apm: bool,
     pub keys: Vec<KeyConfig>,
+    pub themes: Vec<Theme>,
+    pub combos: Vec<Combo>,
+    pub groups: Vec<KeyGroup>,
 }
 
 impl Default for AppConfig {
@@ -89,28 +321,221 @@ impl Default for AppConfig {
             background_color: Color::black(),
             margin: 25.0,
             outline_thickness: 5.0,
+            bar_pattern: BarPattern::Solid,
+            max_bars_per_column: None,
             fading: true,
             counter: true,
             fps: 60,
             log_to_file: false,
+            input_backend: BackendKind::Auto,
+            show_peak_kps: false,
+            window_x: None,
+            window_y: None,
+            show_kps: false,
+            toggle_theme_key: None,
+            key_label_align: KeyLabelAlign::Center,
+            min_bar_lifetime_ms: None,
+            fallback_fonts: Vec::new(),
+            idle_hide_ms: 0,
+            strict_key_names: false,
+            bar_acceleration: 0.0,
+            recenter_on_reload: false,
+            show_event_log: false,
+            event_log_size: 10,
+            session_goal: None,
+            session_goal_position: GoalBarPosition::Top,
+            bar_width_ratio: 1.0,
+            move_key_left_key: None,
+            move_key_right_key: None,
+            auto_save_tweaks: false,
+            counter_format: None,
+            font_path: None,
+            corner_radius: 0.0,
+            monitor_index: None,
+            transparent_background: false,
+            reset_key: None,
+            reset_clears_bars: false,
+            dt_smoothing: 0.0,
+            key_label_scale: 0.32,
+            counter_scale: 0.24,
+            key_axis: KeyAxis::Horizontal,
+            replay_file: None,
+            idle_fps: None,
+            toggle_passthrough_key: None,
+            release_decay_ms: 0,
+            show_anchor_line: false,
+            anchor_line_color: Color::from_rgba_u8(255, 255, 255, 255),
+            show_help_on_startup: false,
+            help_duration_ms: 5000,
+            merge_bars: false,
+            min_bar_height: 0.0,
+            max_window_width: None,
+            max_window_height: None,
+            color_transition_ms: 0,
+            toggle_counter_key: None,
+            stats_export_path: None,
+            edge_padding: None,
+            key_spacing: None,
+            pause_when_unfocused: false,
+            bar_outline_color: Color::from_rgba_u8(255, 255, 255, 255),
+            velocity_coloring: false,
+            reverse_key_order: false,
+            bar_cap_fade_px: 0.0,
+            ui_scale: 1.0,
+            bar_gap_px: 0.0,
+            ghost_trail_ms: 0,
+            show_apm: false,
             keys: vec![
-                KeyConfig {
-                    key_name: "Z".to_string(),
-                    display_name: "Z".to_string(),
-                    color: Color::from_rgba_u8(255, 0, 0, 255),
-                    size: 1.0,
-                },
-                KeyConfig {
-                    key_name: "X".to_string(),
-                    display_name: "X".to_string(),
-                    color: Color::from_rgba_u8(0, 255, 255, 255),
-                    size: 1.0,
-                },
+                KeyConfig::new("Z", Color::from_rgba_u8(255, 0, 0, 255)),
+                KeyConfig::new("X", Color::from_rgba_u8(0, 255, 255, 255)),
             ],
+            themes: Vec::new(),
+            combos: Vec::new(),
+            groups: Vec::new(),
         }
     }
 }
 
+impl AppConfig {
+    /// Starts an [`AppConfigBuilder`] with every field at its default.
+    pub fn builder() -> AppConfigBuilder {
+        AppConfigBuilder::default()
+    }
+
+    /// Keys bound to overlay controls, paired with the control's config name.
+    pub fn control_hotkeys(&self) -> Vec<(&'static str, &str)> {
+        let mut hotkeys = vec![("close", CLOSE_KEY_NAME)];
+        if let Some(key) = &self.toggle_theme_key {
+            hotkeys.push(("toggleThemeKey", key.as_str()));
+        }
+        if let Some(key) = &self.move_key_left_key {
+            hotkeys.push(("moveKeyLeftKey", key.as_str()));
+        }
+        if let Some(key) = &self.move_key_right_key {
+            hotkeys.push(("moveKeyRightKey", key.as_str()));
+        }
+        if let Some(key) = &self.reset_key {
+            hotkeys.push(("resetKey", key.as_str()));
+        }
+        if let Some(key) = &self.toggle_passthrough_key {
+            hotkeys.push(("togglePassthroughKey", key.as_str()));
+        }
+        if let Some(key) = &self.toggle_counter_key {
+            hotkeys.push(("toggleCounterKey", key.as_str()));
+        }
+        hotkeys
+    }
+
+    /// Space between the window edges and the outermost keys, `margin` when unset.
+    pub fn effective_edge_padding(&self) -> f32 {
+        self.edge_padding.unwrap_or(self.margin)
+    }
+
+    /// Space between neighbouring keys, `margin` when unset.
+    pub fn effective_key_spacing(&self) -> f32 {
+        self.key_spacing.unwrap_or(self.margin)
+    }
+}
+
+/// Chained construction of [`AppConfig`] for library and test use.
+///
+/// Unset fields keep their [`AppConfig::default`] values. Keys added with
+/// [`AppConfigBuilder::add_key`] replace the default keys rather than extending them.
+#[derive(Debug, Clone, Default)]
+pub struct AppConfigBuilder {
+    config: AppConfig,
+    keys: Option<Vec<KeyConfig>>,
+}
+
+impl AppConfigBuilder {
+    /// Sets the window height, which bars travel across.
+    pub fn height(mut self, height: f32) -> Self {
+        self.config.height = height;
+        self
+    }
+
+    /// Sets the base key box size in pixels.
+    pub fn key_size(mut self, key_size: f32) -> Self {
+        self.config.key_size = key_size;
+        self
+    }
+
+    /// Sets how fast bars scroll, in pixels per second.
+    pub fn bar_speed(mut self, bar_speed: f32) -> Self {
+        self.config.bar_speed = bar_speed;
+        self
+    }
+
+    /// Sets the overlay background color.
+    pub fn background_color(mut self, color: Color) -> Self {
+        self.config.background_color = color;
+        self
+    }
+
+    /// Sets the margin around the keys in pixels.
+    pub fn margin(mut self, margin: f32) -> Self {
+        self.config.margin = margin;
+        self
+    }
+
+    /// Sets the key box outline thickness in pixels.
+    pub fn outline_thickness(mut self, outline_thickness: f32) -> Self {
+        self.config.outline_thickness = outline_thickness;
+        self
+    }
+
+    /// Sets the fill pattern of the bars.
+    pub fn bar_pattern(mut self, bar_pattern: BarPattern) -> Self {
+        self.config.bar_pattern = bar_pattern;
+        self
+    }
+
+    /// Sets whether bars fade out near the top of the window.
+    pub fn fading(mut self, fading: bool) -> Self {
+        self.config.fading = fading;
+        self
+    }
+
+    /// Sets whether press counters are shown.
+    pub fn counter(mut self, counter: bool) -> Self {
+        self.config.counter = counter;
+        self
+    }
+
+    /// Sets the target frame rate.
+    pub fn fps(mut self, fps: u32) -> Self {
+        self.config.fps = fps;
+        self
+    }
+
+    /// Appends a key; the first call discards the default `Z`/`X` keys.
+    pub fn add_key(mut self, key: KeyConfig) -> Self {
+        self.keys.get_or_insert_with(Vec::new).push(key);
+        self
+    }
+
+    /// Replaces the key list, which may be empty.
+    pub fn keys(mut self, keys: Vec<KeyConfig>) -> Self {
+        self.keys = Some(keys);
+        self
+    }
+
+    /// Appends a theme to the config's theme list.
+    pub fn add_theme(mut self, theme: Theme) -> Self {
+        self.config.themes.push(theme);
+        self
+    }
+
+    /// Finishes the config.
+    pub fn build(self) -> AppConfig {
+        let mut config = self.config;
+        if let Some(keys) = self.keys {
+            config.keys = keys;
+        }
+        config
+    }
+}
+
 /// Application error type.
 #[derive(Debug, Error)]
 pub enum AppError {
@@ -127,7 +552,7 @@ pub enum AppError {
 }
 
 /// Represents an input event emitted by input backends.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum InputEvent {
     KeyPress(String),
     KeyRelease(String),
@@ -137,7 +562,11 @@ pub enum InputEvent {
 
 #[cfg(test)]
 mod tests {
-    use super::{AppConfig, Color, KeyConfig};
+    use super::{AppConfig, AppError, BarPattern, Color, KeyConfig};
+
+    fn mk_key(name: &str) -> KeyConfig {
+        KeyConfig::new(name, Color::from_rgba_u8(10, 20, 30, 255))
+    }
 
     const EPSILON: f32 = 1e-6;
 
@@ -169,6 +598,26 @@ mod tests {
         assert_f32_eq(pressed.a, 1.0 / 1.618);
     }
 
+    #[test]
+    fn test_color_serde_round_trips_as_config_string() {
+        for color in [
+            Color::black(),
+            Color::from_rgba_u8(255, 0, 128, 64),
+            Color::from_rgba_u8(12, 34, 56, 0),
+        ] {
+            let json = serde_json::to_string(&color).expect("color should serialize");
+            assert_eq!(json, format!("\"{}\"", color.to_config_string()));
+
+            let decoded: Color = serde_json::from_str(&json).expect("color should deserialize");
+            assert_eq!(decoded, color);
+        }
+    }
+
+    #[test]
+    fn test_color_serde_rejects_invalid_string() {
+        assert!(serde_json::from_str::<Color>("\"not a color\"").is_err());
+    }
+
     #[test]
     fn test_app_config_default_matches_original_defaults() {
         let config = AppConfig::default();
@@ -179,6 +628,7 @@ mod tests {
         assert_eq!(config.background_color, Color::black());
         assert_f32_eq(config.margin, 25.0);
         assert_f32_eq(config.outline_thickness, 5.0);
+        assert_eq!(config.bar_pattern, BarPattern::Solid);
         assert!(config.fading);
         assert!(config.counter);
         assert_eq!(config.fps, 60);
@@ -199,10 +649,9 @@ mod tests {
     #[test]
     fn test_key_config_creation() {
         let key_config = KeyConfig {
-            key_name: "Mouse1".to_string(),
             display_name: "M1".to_string(),
-            color: Color::from_rgba_u8(10, 20, 30, 200),
             size: 1.25,
+            ..KeyConfig::new("Mouse1", Color::from_rgba_u8(10, 20, 30, 200))
         };
 
         assert_eq!(key_config.key_name, "Mouse1");
@@ -210,4 +659,56 @@ mod tests {
         assert_eq!(key_config.color, Color::from_rgba_u8(10, 20, 30, 200));
         assert_f32_eq(key_config.size, 1.25);
     }
+
+    #[test]
+    fn test_app_config_builder_matches_manual_construction() {
+        let built = AppConfig::builder()
+            .height(500.0)
+            .key_size(60.0)
+            .bar_speed(900.0)
+            .bar_pattern(BarPattern::Stripes)
+            .fading(false)
+            .add_key(mk_key("A"))
+            .build();
+
+        let manual = AppConfig {
+            height: 500.0,
+            key_size: 60.0,
+            bar_speed: 900.0,
+            bar_pattern: BarPattern::Stripes,
+            fading: false,
+            keys: vec![mk_key("A")],
+            ..AppConfig::default()
+        };
+
+        assert_eq!(built, manual);
+        assert_eq!(AppConfig::builder().build(), AppConfig::default());
+    }
+
+    #[test]
+    fn test_app_config_builder_add_key_accumulates_keys() {
+        let config = AppConfig::builder()
+            .add_key(mk_key("A"))
+            .add_key(mk_key("S"))
+            .add_key(mk_key("D"))
+            .build();
+
+        let names: Vec<&str> = config
+            .keys
+            .iter()
+            .map(|key| key.key_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["A", "S", "D"]);
+    }
+
+    #[test]
+    fn test_app_error_watcher_variant_display() {
+        let err = AppError::Watcher("failed to watch 'config.toml'".to_string());
+
+        assert_eq!(
+            err.to_string(),
+            "Watcher error: failed to watch 'config.toml'"
+        );
+        assert!(!matches!(err, AppError::Io(_)));
+    }
 }
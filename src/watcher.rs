@@ -4,8 +4,8 @@
 //! debounces rapid saves, and invokes a callback with the new [`AppConfig`].
 
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use notify::RecursiveMode;
 use notify_debouncer_full::{DebounceEventResult, Debouncer, RecommendedCache, new_debouncer};
@@ -17,17 +17,49 @@ use crate::types::{AppConfig, AppError};
 /// Default debounce timeout in milliseconds.
 const DEBOUNCE_TIMEOUT_MS: u64 = 500;
 
+/// Callback invoked when a reload fails and the previous config stays active.
+pub type ReloadErrorCallback = Arc<dyn Fn(AppError) + Send + Sync>;
+
+/// Shared switch that makes the watcher ignore changes for a short period.
+///
+/// Used when the app writes the config file itself, so its own save is not
+/// reloaded as if the user had edited it.
+#[derive(Debug, Default)]
+pub struct ReloadPause {
+    paused_until: Mutex<Option<Instant>>,
+}
+
+impl ReloadPause {
+    /// Ignores changes detected before `now + duration`.
+    pub fn pause_for(&self, duration: Duration, now: Instant) {
+        *self.lock() = Some(now + duration);
+    }
+
+    /// Whether changes detected at `now` should be ignored.
+    pub fn is_paused(&self, now: Instant) -> bool {
+        self.lock().is_some_and(|until| now < until)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<Instant>> {
+        self.paused_until
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
 /// Watches a configuration file for changes and invokes a callback on reload.
 ///
 /// Uses a debounced file watcher to avoid reloading on every intermediate
 /// write during rapid saves. On each debounced change event, the config
 /// file is re-read and parsed; if successful, the callback receives the
 /// new [`AppConfig`]. Parse or I/O errors are logged as warnings without
-/// crashing.
+/// crashing, and forwarded to the optional error callback.
 pub struct ConfigWatcher {
     debouncer: Option<Debouncer<notify::RecommendedWatcher, RecommendedCache>>,
     path: PathBuf,
     callback: Arc<dyn Fn(AppConfig) + Send + Sync>,
+    error_callback: Option<ReloadErrorCallback>,
+    pause: Option<Arc<ReloadPause>>,
 }
 
 impl ConfigWatcher {
@@ -57,9 +89,25 @@ impl ConfigWatcher {
             // The Box<dyn Fn + Send> is automatically Send + Sync-safe since Fn is
             // immutably callable from multiple threads.
             callback: Arc::from(Box::leak(callback) as &(dyn Fn(AppConfig) + Send + Sync)),
+            error_callback: None,
+            pause: None,
         })
     }
 
+    /// Registers a callback for reloads that fail to read or parse.
+    ///
+    /// Takes effect on the next call to [`ConfigWatcher::start`].
+    pub fn set_error_callback(&mut self, callback: ReloadErrorCallback) {
+        self.error_callback = Some(callback);
+    }
+
+    /// Shares a pause switch that suppresses reloads while it is active.
+    ///
+    /// Takes effect on the next call to [`ConfigWatcher::start`].
+    pub fn set_reload_pause(&mut self, pause: Arc<ReloadPause>) {
+        self.pause = Some(pause);
+    }
+
     /// Starts watching the config file for changes.
     ///
     /// Creates a debounced file watcher that monitors the parent directory
@@ -79,12 +127,20 @@ impl ConfigWatcher {
 
         let config_path = self.path.clone();
         let callback = Arc::clone(&self.callback);
+        let error_callback = self.error_callback.clone();
+        let pause = self.pause.clone();
 
         let mut debouncer = new_debouncer(
             Duration::from_millis(DEBOUNCE_TIMEOUT_MS),
             None,
             move |result: DebounceEventResult| {
-                handle_debounce_event(result, &config_path, &callback);
+                handle_debounce_event(
+                    result,
+                    &config_path,
+                    &callback,
+                    error_callback.as_ref(),
+                    pause.as_deref(),
+                );
             },
         )
         .map_err(|err| AppError::Watcher(format!("failed to create debouncer: {err}")))?;
@@ -133,13 +189,33 @@ impl std::fmt::Debug for ConfigWatcher {
 }
 
 /// Handles a debounced file system event by reloading config and invoking the callback.
+///
+/// Failed reloads leave the previous config in place and are reported to `error_callback`.
+/// Deleting the config file is logged and likewise keeps the previous config.
+/// Changes arriving while `pause` is active are ignored.
 fn handle_debounce_event(
     result: DebounceEventResult,
     config_path: &Path,
     callback: &Arc<dyn Fn(AppConfig) + Send + Sync>,
+    error_callback: Option<&ReloadErrorCallback>,
+    pause: Option<&ReloadPause>,
 ) {
     match result {
         Ok(events) => {
+            let config_removed = events.iter().any(|event| {
+                matches!(event.kind, notify::EventKind::Remove(_))
+                    && event.paths.iter().any(|path| path == config_path)
+            });
+            // An atomic save may remove and recreate the file in one batch; only
+            // treat it as a deletion if the file is still gone.
+            if config_removed && !config_path.exists() {
+                warn!(
+                    "Config file '{}' was removed; keeping last-known-good config",
+                    config_path.display()
+                );
+                return;
+            }
+
             let dominated = events.iter().any(|event| {
                 matches!(
                     event.kind,
@@ -149,6 +225,10 @@ fn handle_debounce_event(
             if !dominated {
                 return;
             }
+            if pause.is_some_and(|pause| pause.is_paused(Instant::now())) {
+                info!("Config file changed while reloads are paused; ignoring");
+                return;
+            }
 
             info!("Config file changed, reloading...");
             match load_config(config_path) {
@@ -158,6 +238,9 @@ fn handle_debounce_event(
                 }
                 Err(err) => {
                     warn!("Failed to reload config: {err}");
+                    if let Some(error_callback) = error_callback {
+                        error_callback(err);
+                    }
                 }
             }
         }
@@ -370,7 +453,7 @@ color = "0,255,0,255"
             notify_debouncer_full::DebouncedEvent::new(event, std::time::Instant::now());
         let result: DebounceEventResult = Ok(vec![debounced]);
 
-        handle_debounce_event(result, &path, &callback);
+        handle_debounce_event(result, &path, &callback, None, None);
 
         assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
@@ -392,9 +475,87 @@ color = "0,255,0,255"
             notify_debouncer_full::DebouncedEvent::new(event, std::time::Instant::now());
         let result: DebounceEventResult = Ok(vec![debounced]);
 
-        handle_debounce_event(result, &path, &callback);
+        handle_debounce_event(result, &path, &callback, None, None);
+
+        assert_eq!(counter.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_handle_debounce_event_removed_config_keeps_previous_config() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = write_temp_config(dir.path(), valid_toml());
+        fs::remove_file(&path).expect("remove config");
+
+        let applied = Arc::new(AtomicUsize::new(0));
+        let applied_clone = Arc::clone(&applied);
+        let callback: Arc<dyn Fn(AppConfig) + Send + Sync> = Arc::new(move |_| {
+            applied_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        let errors = Arc::new(AtomicUsize::new(0));
+        let errors_clone = Arc::clone(&errors);
+        let error_callback: ReloadErrorCallback = Arc::new(move |_| {
+            errors_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        // Editors often report a Modify of the parent alongside the removal.
+        let removed =
+            notify::Event::new(notify::EventKind::Remove(notify::event::RemoveKind::File))
+                .add_path(path.clone());
+        let modified =
+            notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Any))
+                .add_path(dir.path().to_path_buf());
+        let now = std::time::Instant::now();
+        let result: DebounceEventResult = Ok(vec![
+            notify_debouncer_full::DebouncedEvent::new(removed, now),
+            notify_debouncer_full::DebouncedEvent::new(modified, now),
+        ]);
+
+        handle_debounce_event(result, &path, &callback, Some(&error_callback), None);
+
+        assert_eq!(applied.load(Ordering::SeqCst), 0);
+        assert_eq!(errors.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn test_reload_pause_expires_after_duration() {
+        let pause = ReloadPause::default();
+        let now = Instant::now();
+        assert!(!pause.is_paused(now));
+
+        pause.pause_for(Duration::from_millis(500), now);
+
+        assert!(pause.is_paused(now + Duration::from_millis(499)));
+        assert!(!pause.is_paused(now + Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_handle_debounce_event_skips_reload_while_paused() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = write_temp_config(dir.path(), valid_toml());
 
+        let counter = Arc::new(AtomicUsize::new(0));
+        let counter_clone = Arc::clone(&counter);
+        let callback: Arc<dyn Fn(AppConfig) + Send + Sync> = Arc::new(move |_| {
+            counter_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        let modify = || {
+            let event = notify::Event::new(notify::EventKind::Modify(
+                notify::event::ModifyKind::Data(notify::event::DataChange::Content),
+            ));
+            Ok(vec![notify_debouncer_full::DebouncedEvent::new(
+                event,
+                Instant::now(),
+            )])
+        };
+
+        let pause = ReloadPause::default();
+        pause.pause_for(Duration::from_secs(60), Instant::now());
+        handle_debounce_event(modify(), &path, &callback, None, Some(&pause));
         assert_eq!(counter.load(Ordering::SeqCst), 0);
+
+        pause.pause_for(Duration::ZERO, Instant::now());
+        handle_debounce_event(modify(), &path, &callback, None, Some(&pause));
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
     }
 
     #[test]
@@ -413,8 +574,43 @@ color = "0,255,0,255"
         let result: DebounceEventResult = Err(errors);
 
         // Should not panic.
-        handle_debounce_event(result, &path, &callback);
+        handle_debounce_event(result, &path, &callback, None, None);
 
         assert_eq!(counter.load(Ordering::SeqCst), 0);
     }
+
+    #[test]
+    fn test_handle_debounce_event_invalid_reload_signals_error_without_config() {
+        let dir = tempfile::tempdir().expect("create temp dir");
+        let path = write_temp_config(dir.path(), "{{{{invalid toml");
+
+        let applied = Arc::new(AtomicUsize::new(0));
+        let applied_clone = Arc::clone(&applied);
+        let callback: Arc<dyn Fn(AppConfig) + Send + Sync> = Arc::new(move |_| {
+            applied_clone.fetch_add(1, Ordering::SeqCst);
+        });
+        let errors = Arc::new(AtomicUsize::new(0));
+        let errors_clone = Arc::clone(&errors);
+        let error_callback: ReloadErrorCallback = Arc::new(move |err| {
+            assert!(err.to_string().contains("failed to parse TOML"));
+            errors_clone.fetch_add(1, Ordering::SeqCst);
+        });
+
+        let event = notify::Event::new(notify::EventKind::Modify(notify::event::ModifyKind::Data(
+            notify::event::DataChange::Content,
+        )));
+        let debounced =
+            notify_debouncer_full::DebouncedEvent::new(event, std::time::Instant::now());
+
+        handle_debounce_event(
+            Ok(vec![debounced]),
+            &path,
+            &callback,
+            Some(&error_callback),
+            None,
+        );
+
+        assert_eq!(applied.load(Ordering::SeqCst), 0);
+        assert_eq!(errors.load(Ordering::SeqCst), 1);
+    }
 }